@@ -1,7 +1,15 @@
 pub mod builder;
+pub mod config;
 pub mod context;
+pub mod dashboard;
+pub mod forward;
+pub mod hook;
+pub mod image;
+pub mod project_graph;
+pub mod project_init;
 pub mod runtime;
+pub mod session;
 pub mod setup;
 pub mod update;
 
-pub use context::RuntimeContext;
+pub use context::{Placement, Route, RuntimeContext};
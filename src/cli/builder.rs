@@ -1,11 +1,19 @@
-use crate::infra::PodmanAdapter;
-use crate::infra::config::{load_app_config, load_mise_config};
-use crate::services::{CleanupOptions, ContainerService, Orchestrator, SystemService};
+use crate::cli::context::{CONTAINER_MARKER, RuntimeContext};
+use crate::domain::BuildOptions;
+use crate::infra::config::{AppConfig, BuildConfig, load_app_config, load_mise_config};
+use crate::infra::engine::{create_runtime, is_dry_run};
+use crate::infra::podman_api_adapter::detect_podman_socket;
+use crate::infra::service_resolver::ServiceResolver;
+use crate::services::{
+    CleanupOptions, ContainerService, DEFAULT_KEEP_IMAGES, ImageHistory, Orchestrator,
+    SystemService,
+};
 use anyhow::{Context, Result, bail};
-use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::os::unix::fs::FileTypeExt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
+use std::time::SystemTime;
 use tracing::{debug, info, warn};
 
 const CONTAINER_SSH_SOCK_PATH: &str = "/run/host-services/ssh-auth.sock";
@@ -16,40 +24,6 @@ const PERSISTENT_NVIM_SHARE_PATH: &str = "/home/dev/.local/share/nvim";
 const PERSISTENT_NVIM_STATE_PATH: &str = "/home/dev/.local/state/nvim";
 const PERSISTENT_BASH_HISTORY_PATH: &str = "/home/dev/.local/state/bash";
 
-/// Detects Podman socket strictly following Linux standards
-/// Priority: Env Var -> XDG Rootless -> UID Rootless -> System Rootful
-fn detect_podman_socket() -> Option<PathBuf> {
-    if let Ok(sock) = std::env::var("PODMAN_SOCK") {
-        let path = PathBuf::from(sock);
-        if path.exists()
-            && std::fs::metadata(&path)
-                .map(|m| m.file_type().is_socket())
-                .unwrap_or(false)
-        {
-            return Some(path);
-        }
-    }
-
-    let uid = std::fs::metadata("/proc/self").map(|m| m.uid()).ok()?;
-
-    let candidates = vec![
-        std::env::var("XDG_RUNTIME_DIR")
-            .ok()
-            .map(|dir| PathBuf::from(dir).join("podman/podman.sock")),
-        Some(PathBuf::from(format!(
-            "/run/user/{}/podman/podman.sock",
-            uid
-        ))),
-        Some(PathBuf::from("/run/podman/podman.sock")),
-    ];
-
-    candidates.into_iter().flatten().find(|path| {
-        std::fs::metadata(path)
-            .map(|m| m.file_type().is_socket())
-            .unwrap_or(false)
-    })
-}
-
 /// Accumulated configuration fragment from a feature
 #[derive(Debug, Default, Clone)]
 struct ContainerConfigFragment {
@@ -178,7 +152,7 @@ struct PodmanFeature;
 impl HostFeature for PodmanFeature {
     fn configure(&self, _ctx: &BuildContext) -> Result<Option<ContainerConfigFragment>> {
         // 1. Prevent "Inception" (Devobox inside Devobox)
-        if std::env::var("DEVOBOX_CONTAINER").is_ok() {
+        if RuntimeContext::detect().is_container() {
             debug!("  Detectado ambiente containerizado: pulando montagem do socket Podman.");
             return Ok(None);
         }
@@ -335,7 +309,12 @@ impl HostFeature for CodeMountFeature {
         let code_dir = shellexpand::tilde(code_dir.to_string_lossy().as_ref()).into_owned();
         let path = PathBuf::from(&code_dir);
 
-        if !path.exists() {
+        if !path.exists() && is_dry_run() {
+            warn!(
+                "  Diretório {:?} não existe (seria criado para o bind mount)",
+                path
+            );
+        } else if !path.exists() {
             warn!(
                 "  Diretório {:?} não existe. Criando para o bind mount...",
                 path
@@ -350,21 +329,26 @@ impl HostFeature for CodeMountFeature {
     }
 }
 
-pub fn build(config_dir: &Path, skip_cleanup: bool) -> Result<()> {
+/// Builds the dev image and (re)creates the containers; `flags` are the
+/// command line options, on top of `[build]`
+pub fn build(config_dir: &Path, skip_cleanup: bool, flags: &BuildOptions) -> Result<()> {
     let app_config = load_app_config(config_dir)?;
 
-    let runtime = Arc::new(PodmanAdapter::new());
+    let runtime = create_runtime(&app_config.runtime)?;
     let container_service = Arc::new(ContainerService::new(runtime.clone()));
     let system_service = Arc::new(SystemService::new(runtime));
 
-    let containerfile_path_from_config = app_config
-        .paths
-        .containerfile
-        .clone()
-        .context("Containerfile path not set in config")?;
-    let containerfile = config_dir.join(containerfile_path_from_config);
-
-    if !containerfile.exists() {
+    // Projects with [build] containerfile build from their own directory
+    let containerfile = app_config.dev_containerfile(config_dir);
+    if let Some(containerfile) = &containerfile
+        && !containerfile.exists()
+    {
+        if app_config.has_project_image() {
+            bail!(
+                "Containerfile do projeto não encontrado em {:?}",
+                containerfile
+            );
+        }
         bail!(
             "Containerfile não encontrado em {:?}. Rode 'devobox setup install' primeiro.",
             config_dir
@@ -382,15 +366,30 @@ pub fn build(config_dir: &Path, skip_cleanup: bool) -> Result<()> {
         let _ = orchestrator.cleanup(&cleanup_options);
     }
 
-    let context = config_dir.to_path_buf();
     let image_name = app_config
         .build
         .image_name
         .clone()
         .context("Image name not set in config")?;
 
-    info!("  Construindo imagem {} (Arch)...", image_name);
-    system_service.build_image(&image_name, &containerfile, &context)?;
+    // Each build gets its own tag and the configured image moves to it, so
+    // older builds stay around for `devobox image rollback`
+    let history = containerfile.as_ref().map(|_| {
+        let keep = app_config.build.keep_images.unwrap_or(DEFAULT_KEEP_IMAGES);
+        ImageHistory::new(system_service.clone(), &image_name, keep)
+    });
+
+    match (&containerfile, &history) {
+        (Some(containerfile), Some(history)) => {
+            let context = containerfile.parent().unwrap_or(config_dir);
+            let version = history.version_at(SystemTime::now());
+            info!("  Construindo imagem {} (Arch)...", version);
+            let options = build_options(&app_config.build, flags)?;
+            system_service.build_image(&version, containerfile, context, &options)?;
+            history.activate(&version)?;
+        }
+        _ => info!(" Usando a imagem {} do projeto", image_name),
+    }
 
     info!(" Validando mise.toml...");
     let mise_toml_path = config_dir.join(
@@ -403,7 +402,14 @@ pub fn build(config_dir: &Path, skip_cleanup: bool) -> Result<()> {
     load_mise_config(&mise_toml_path)?;
 
     info!(" Resolvendo serviços (incluindo dependências)...");
-    let services = crate::infra::config::resolve_all_services(config_dir, &app_config)?;
+    let resolved =
+        ServiceResolver::new(config_dir)?.resolve_workspace(&app_config, Path::new("./"))?;
+    for r in &resolved {
+        if let Some(project) = &r.via_project {
+            info!("   {} (via {})", r.service.name, project);
+        }
+    }
+    let services: Vec<_> = resolved.into_iter().map(|r| r.service).collect();
 
     if services.is_empty() {
         warn!("  Nenhum serviço configurado. Pulei criação de serviços.");
@@ -413,6 +419,65 @@ pub fn build(config_dir: &Path, skip_cleanup: bool) -> Result<()> {
         container_service.recreate(&svc.to_spec())?;
     }
 
+    // Forwarded from the dev container: recreating it would end this very
+    // process, and its bind mounts only make sense from the host
+    if RuntimeContext::detect().is_container() {
+        warn!("  Build pelo container: o container de desenvolvimento não foi recriado.");
+        warn!("   Rode 'devobox build' no host para usar a nova imagem nele.");
+        info!(" Imagem e serviços prontos.");
+        return Ok(());
+    }
+
+    recreate_dev_container(&app_config, &container_service)?;
+
+    if let Some(history) = history {
+        for removed in history.apply_retention()? {
+            info!(" Versão antiga removida: {}", removed);
+        }
+    }
+
+    info!(" Build concluído! Tudo pronto.");
+    Ok(())
+}
+
+/// `[build]` settings plus the command line flags, whose build args win
+fn build_options(config: &BuildConfig, flags: &BuildOptions) -> Result<BuildOptions> {
+    let mut args: Vec<String> = config
+        .args
+        .iter()
+        .flatten()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect();
+    args.extend(flags.args.iter().cloned());
+
+    let mut secrets = Vec::new();
+    for (id, src) in config.secrets.iter().flatten() {
+        if !src.is_file() {
+            bail!(
+                "Secret '{}' de [build] secrets não encontrado em {:?}",
+                id,
+                src
+            );
+        }
+        secrets.push((id.clone(), src.clone()));
+    }
+
+    Ok(BuildOptions {
+        args,
+        target: config.target.clone(),
+        secrets,
+        platform: config.platform.clone(),
+        no_cache: flags.no_cache,
+        pull: flags.pull,
+    })
+}
+
+/// (Re)creates the dev container from the configured image, with every host
+/// integration (code, SSH, GPG, podman socket, GUI, persistent volumes)
+pub fn recreate_dev_container(
+    app_config: &AppConfig,
+    container_service: &ContainerService,
+) -> Result<()> {
     let features: Vec<Box<dyn HostFeature>> = vec![
         Box::new(CodeMountFeature),
         Box::new(SshFeature),
@@ -434,25 +499,32 @@ pub fn build(config_dir: &Path, skip_cleanup: bool) -> Result<()> {
     let main_container_name = app_config
         .container
         .name
+        .as_deref()
         .context("Main container name not set in config")?;
     let main_container_workdir = app_config
         .container
         .workdir
+        .as_deref()
         .context("Main container workdir not set in config")?;
+    let image_name = app_config
+        .build
+        .image_name
+        .as_deref()
+        .context("Image name not set in config")?;
 
     let mut all_extra_args = vec!["-it".to_string()];
     all_extra_args.extend(final_config.extra_args);
     all_extra_args.extend(final_config.devices);
 
     let mut container_env = final_config.env.clone();
-    container_env.push("DEVOBOX_CONTAINER=1".to_string());
+    container_env.push(format!("{}={}", CONTAINER_MARKER, main_container_name));
     container_env.push("XDG_RUNTIME_DIR=/tmp".to_string());
 
     let extra_args_refs: Vec<&str> = all_extra_args.iter().map(|s| s.as_str()).collect();
 
     let dev_spec = crate::domain::ContainerSpec {
-        name: &main_container_name,
-        image: &image_name,
+        name: main_container_name,
+        image: image_name,
         ports: &[],
         env: &container_env,
         network: Some("host"),
@@ -469,11 +541,10 @@ pub fn build(config_dir: &Path, skip_cleanup: bool) -> Result<()> {
         healthcheck_interval: None,
         healthcheck_timeout: None,
         healthcheck_retries: None,
+        project: None,
     };
 
-    container_service.recreate(&dev_spec)?;
-    info!(" Build concluído! Tudo pronto.");
-    Ok(())
+    container_service.recreate(&dev_spec)
 }
 
 #[cfg(test)]
@@ -512,6 +583,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_build_options_merge_config_and_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let npmrc = dir.path().join("npmrc");
+        std::fs::write(&npmrc, "token").unwrap();
+
+        let config = BuildConfig {
+            args: Some(
+                [("NODE_VERSION", "20"), ("USER_UID", "1000")]
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            target: Some("dev".to_string()),
+            secrets: Some([("npmrc".to_string(), npmrc.clone())].into()),
+            ..Default::default()
+        };
+        let flags = BuildOptions {
+            args: vec!["NODE_VERSION=22".to_string()],
+            no_cache: true,
+            ..Default::default()
+        };
+
+        let options = build_options(&config, &flags).unwrap();
+        // Flags come last, so they win over [build] args
+        assert_eq!(
+            options.args,
+            vec!["NODE_VERSION=20", "USER_UID=1000", "NODE_VERSION=22"]
+        );
+        assert_eq!(options.target.as_deref(), Some("dev"));
+        assert_eq!(options.secrets, vec![("npmrc".to_string(), npmrc)]);
+        assert!(options.no_cache && !options.pull);
+
+        let missing = BuildConfig {
+            secrets: Some([("pip".to_string(), dir.path().join("pip.conf"))].into()),
+            ..Default::default()
+        };
+        let err = build_options(&missing, &BuildOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("Secret 'pip'"), "{err}");
+    }
+
     #[test]
     fn test_config_fragment_merge() {
         let f1 = ContainerConfigFragment {
@@ -0,0 +1,132 @@
+use crate::infra::config_provenance::{EffectiveEntry, effective_config};
+use crate::infra::config_schema::config_schema;
+use crate::infra::config_validate::validate_config;
+use anyhow::{Result, bail};
+use std::path::Path;
+use tracing::info;
+
+pub fn validate(config_dir: &Path) -> Result<()> {
+    let issues = validate_config(config_dir, Path::new("."));
+
+    if issues.is_empty() {
+        info!(" Configuração válida");
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("  {issue}");
+    }
+    bail!("{} problema(s) encontrado(s) na configuração", issues.len());
+}
+
+pub fn show(config_dir: &Path) -> Result<()> {
+    let entries = effective_config(config_dir, Path::new("."))?;
+    print!("{}", render(&entries));
+    Ok(())
+}
+
+pub fn schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&config_schema())?);
+    Ok(())
+}
+
+/// Renders entries as TOML with the origin of each value as a trailing comment
+fn render(entries: &[EffectiveEntry]) -> String {
+    let mut lines: Vec<(String, Option<String>)> = Vec::new();
+    let mut section = None;
+    let mut index = 0;
+
+    while index < entries.len() {
+        let entry = &entries[index];
+        if section != Some(&entry.section) {
+            if section.is_some() {
+                lines.push((String::new(), None));
+            }
+            lines.push((format!("[{}]", entry.section), None));
+            section = Some(&entry.section);
+        }
+
+        // List items come as consecutive entries with the same key
+        let run = entries[index..]
+            .iter()
+            .take_while(|e| e.section == entry.section && e.key == entry.key)
+            .count();
+
+        if entry.key == "include_projects" {
+            lines.push((format!("{} = [", entry.key), None));
+            for item in &entries[index..index + run] {
+                lines.push((
+                    format!("    {},", item.value),
+                    Some(item.origin.to_string()),
+                ));
+            }
+            lines.push(("]".to_string(), None));
+            index += run;
+            continue;
+        }
+
+        lines.push((
+            format!("{} = {}", entry.key, entry.value),
+            Some(entry.origin.to_string()),
+        ));
+        index += 1;
+    }
+
+    let width = lines
+        .iter()
+        .filter(|(_, origin)| origin.is_some())
+        .map(|(text, _)| text.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    for (text, origin) in lines {
+        match origin {
+            Some(origin) => {
+                let pad = width - text.chars().count();
+                out.push_str(&format!("{text}{}  # {origin}\n", " ".repeat(pad)));
+            }
+            None => {
+                out.push_str(&text);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::config_provenance::ConfigOrigin;
+    use std::path::PathBuf;
+
+    fn entry(section: &str, key: &str, value: toml::Value, origin: ConfigOrigin) -> EffectiveEntry {
+        EffectiveEntry {
+            section: section.to_string(),
+            key: key.to_string(),
+            value,
+            origin,
+        }
+    }
+
+    #[test]
+    fn test_render_annotates_values() {
+        let global = ConfigOrigin::Global(PathBuf::from("/cfg/devobox.toml"));
+        let entries = vec![
+            entry("build", "image_name", "img".into(), global.clone()),
+            entry("dependencies", "include_projects", "../api".into(), global),
+        ];
+
+        assert_eq!(
+            render(&entries),
+            "[build]\n\
+             image_name = \"img\"  # global: /cfg/devobox.toml\n\
+             \n\
+             [dependencies]\n\
+             include_projects = [\n\
+             \x20   \"../api\",       # global: /cfg/devobox.toml\n\
+             ]\n"
+        );
+    }
+}
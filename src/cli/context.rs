@@ -9,32 +9,67 @@ pub enum RuntimeContext {
     Container,
 }
 
+/// Where a subcommand has to run to do its job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Needs the host's container engine (build, cleanup)
+    Host,
+    /// Needs the dev container's filesystem and sessions
+    Container,
+    /// Works from both sides
+    Anywhere,
+}
+
+/// How a subcommand reaches the place it has to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// Already in the right place
+    Local,
+    /// From the container, through the host's podman socket
+    ToHost,
+    /// From the host, through `podman exec`
+    ToContainer,
+}
+
 impl RuntimeContext {
     /// Detects the current runtime context
     ///
-    /// Detection strategy:
-    /// 1. Check for DEVOBOX_CONTAINER environment variable (set by builder)
-    /// 2. Fallback: Check for container marker files (/.dockerenv, /run/.containerenv)
+    /// The builder sets `DEVOBOX_CONTAINER` to the dev container's name. When
+    /// podman's `/run/.containerenv` names the container, it has to match, so
+    /// the variable leaking into another container doesn't count. Generic
+    /// marker files alone (/.dockerenv) say nothing about *which* container
+    /// this is and are ignored.
     ///
     /// # Returns
     /// * `RuntimeContext::Container` - Running inside devobox container
     /// * `RuntimeContext::Host` - Running on host machine
     pub fn detect() -> Self {
-        // Primary detection: environment variable set by builder
-        if env::var("DEVOBOX_CONTAINER").is_ok() {
-            return Self::Container;
-        }
+        Self::from_marker(
+            env::var(CONTAINER_MARKER).ok().as_deref(),
+            std::fs::read_to_string("/run/.containerenv")
+                .ok()
+                .as_deref(),
+        )
+    }
+
+    /// Decides the context from the marker variable and `/run/.containerenv`
+    pub fn from_marker(marker: Option<&str>, containerenv: Option<&str>) -> Self {
+        let Some(marker) = marker.map(str::trim).filter(|m| !m.is_empty()) else {
+            return Self::Host;
+        };
 
-        // Fallback detection: container marker files
-        if Self::is_inside_container() {
+        // Containers built before the marker carried the name set it to "1"
+        if marker == "1" {
             return Self::Container;
         }
 
-        Self::Host
+        match containerenv.and_then(container_name) {
+            Some(name) if name != marker => Self::Host,
+            _ => Self::Container,
+        }
     }
 
     /// Checks if running inside a container
-    #[allow(dead_code)]
     pub fn is_container(&self) -> bool {
         matches!(self, Self::Container)
     }
@@ -44,17 +79,28 @@ impl RuntimeContext {
         matches!(self, Self::Host)
     }
 
-    /// Heuristic check for container environment
-    ///
-    /// Checks for the presence of container marker files:
-    /// - /.dockerenv (Docker/Podman containers)
-    /// - /run/.containerenv (Podman containers)
-    fn is_inside_container() -> bool {
-        std::path::Path::new("/.dockerenv").exists()
-            || std::path::Path::new("/run/.containerenv").exists()
+    /// How a command with `placement` gets to run from this context
+    pub fn route(&self, placement: Placement) -> Route {
+        match (self, placement) {
+            (Self::Container, Placement::Host) => Route::ToHost,
+            (Self::Host, Placement::Container) => Route::ToContainer,
+            _ => Route::Local,
+        }
     }
 }
 
+/// Environment variable the builder sets in the dev container
+pub const CONTAINER_MARKER: &str = "DEVOBOX_CONTAINER";
+
+/// `name="..."` from podman's `/run/.containerenv`
+fn container_name(containerenv: &str) -> Option<&str> {
+    containerenv
+        .lines()
+        .find_map(|line| line.strip_prefix("name="))
+        .map(|name| name.trim_matches('"'))
+        .filter(|name| !name.is_empty())
+}
+
 impl std::fmt::Display for RuntimeContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -69,15 +115,51 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_context_detection_without_env() {
-        // When DEVOBOX_CONTAINER is not set, should default to Host
-        // (unless running in actual container with marker files)
-        unsafe {
-            env::remove_var("DEVOBOX_CONTAINER");
-        }
-        let ctx = RuntimeContext::detect();
-        // This will be Host on normal dev machines, Container if running in actual container
-        assert!(ctx == RuntimeContext::Host || ctx == RuntimeContext::Container);
+    fn test_context_detection_from_marker() {
+        let containerenv = "engine=\"podman-5.4.2\"\nname=\"devobox\"\nid=\"abc\"\n";
+
+        assert_eq!(
+            RuntimeContext::from_marker(None, None),
+            RuntimeContext::Host
+        );
+        // Any container has /run/.containerenv; only the marker makes it ours
+        assert_eq!(
+            RuntimeContext::from_marker(None, Some(containerenv)),
+            RuntimeContext::Host
+        );
+        assert_eq!(
+            RuntimeContext::from_marker(Some("devobox"), Some(containerenv)),
+            RuntimeContext::Container
+        );
+        assert_eq!(
+            RuntimeContext::from_marker(Some("devobox"), None),
+            RuntimeContext::Container
+        );
+        // The marker leaked into another container
+        assert_eq!(
+            RuntimeContext::from_marker(Some("other"), Some(containerenv)),
+            RuntimeContext::Host
+        );
+        assert_eq!(
+            RuntimeContext::from_marker(Some("1"), Some(containerenv)),
+            RuntimeContext::Container
+        );
+        assert_eq!(
+            RuntimeContext::from_marker(Some(""), None),
+            RuntimeContext::Host
+        );
+    }
+
+    #[test]
+    fn test_route() {
+        use RuntimeContext::{Container, Host};
+
+        assert_eq!(Container.route(Placement::Host), Route::ToHost);
+        assert_eq!(Host.route(Placement::Container), Route::ToContainer);
+        assert_eq!(Host.route(Placement::Host), Route::Local);
+        assert_eq!(Container.route(Placement::Container), Route::Local);
+        assert_eq!(Container.route(Placement::Anywhere), Route::Local);
+        assert_eq!(Host.route(Placement::Anywhere), Route::Local);
     }
 
     #[test]
@@ -0,0 +1,665 @@
+use crate::cli::RuntimeContext;
+use crate::cli::runtime::Runtime;
+use crate::domain::traits::ContainerHealthStatus;
+use crate::domain::{ContainerFilter, ContainerState, Project, ServiceKind};
+use crate::infra::ProjectDiscovery;
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const LOG_TAIL_LINES: usize = 200;
+
+/// Role of a container listed in the dashboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowKind {
+    DevContainer,
+    Service(ServiceKind),
+}
+
+/// One line of the dashboard table
+#[derive(Debug, Clone)]
+pub struct ContainerRow {
+    pub name: String,
+    pub kind: RowKind,
+    pub state: ContainerState,
+    pub health: ContainerHealthStatus,
+}
+
+/// Queries the state and health of the dev container and every resolved
+/// service in one listing
+pub fn snapshot(runtime: &Runtime) -> Result<Vec<ContainerRow>> {
+    let main_name = runtime.main_container_name()?;
+    let mut targets = vec![(main_name.to_string(), RowKind::DevContainer)];
+    targets.extend(
+        runtime
+            .services()
+            .iter()
+            .map(|svc| (svc.name.clone(), RowKind::Service(svc.kind.clone()))),
+    );
+
+    let snapshot = runtime.container_service.list(&ContainerFilter::names(
+        targets.iter().map(|(name, _)| name.clone()),
+    ))?;
+
+    let rows = targets
+        .into_iter()
+        .map(|(name, kind)| {
+            let info = snapshot.iter().find(|info| info.name == name);
+            ContainerRow {
+                state: info
+                    .map(|info| info.state.clone())
+                    .unwrap_or(ContainerState::NotCreated),
+                health: info
+                    .map(|info| info.health.clone())
+                    .unwrap_or(ContainerHealthStatus::NotApplicable),
+                name,
+                kind,
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Start,
+    Stop,
+    Restart,
+}
+
+enum DashboardEvent {
+    Snapshot(Vec<ContainerRow>),
+    Logs { name: String, content: String },
+    Message(String),
+}
+
+/// UI state of the dashboard, independent of the terminal
+pub struct Dashboard {
+    rows: Vec<ContainerRow>,
+    table_state: TableState,
+    logs: Option<(String, String)>,
+    log_target: Arc<Mutex<Option<String>>>,
+    message: String,
+    info: Vec<String>,
+}
+
+impl Dashboard {
+    pub fn new(info: Vec<String>) -> Self {
+        Self {
+            rows: Vec::new(),
+            table_state: TableState::default(),
+            logs: None,
+            log_target: Arc::new(Mutex::new(None)),
+            message: "Carregando estado dos containers...".to_string(),
+            info,
+        }
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<ContainerRow>) {
+        self.rows = rows;
+        match self.table_state.selected() {
+            _ if self.rows.is_empty() => self.table_state.select(None),
+            Some(i) if i >= self.rows.len() => self.table_state.select(Some(self.rows.len() - 1)),
+            None => self.table_state.select(Some(0)),
+            Some(_) => {}
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let next = match self.table_state.selected() {
+            Some(i) => (i + 1) % self.rows.len(),
+            None => 0,
+        };
+        self.table_state.select(Some(next));
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let previous = match self.table_state.selected() {
+            Some(0) | None => self.rows.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.table_state.select(Some(previous));
+    }
+
+    pub fn selected(&self) -> Option<&ContainerRow> {
+        self.table_state.selected().and_then(|i| self.rows.get(i))
+    }
+
+    /// Toggles the log pane for the selected container
+    pub fn toggle_logs(&mut self) {
+        let selected = self.selected().map(|row| row.name.clone());
+        let mut target = self.log_target.lock().unwrap();
+
+        if target.is_some() && *target == selected {
+            *target = None;
+            self.logs = None;
+        } else if let Some(name) = selected {
+            self.logs = Some((name.clone(), String::new()));
+            *target = Some(name);
+        }
+    }
+
+    fn handle_event(&mut self, event: DashboardEvent) {
+        match event {
+            DashboardEvent::Snapshot(rows) => self.set_rows(rows),
+            DashboardEvent::Logs { name, content } => {
+                if let Some((current, logs)) = &mut self.logs
+                    && *current == name
+                {
+                    *logs = content;
+                }
+            }
+            DashboardEvent::Message(message) => self.message = message,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let log_height = if self.logs.is_some() {
+            Constraint::Percentage(40)
+        } else {
+            Constraint::Length(0)
+        };
+
+        let outer = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(5),
+                log_height,
+                Constraint::Length(2),
+            ])
+            .split(frame.area());
+
+        frame.render_widget(
+            Paragraph::new(
+                Line::from(" devobox dashboard").style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ),
+            outer[0],
+        );
+
+        let main = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(outer[1]);
+
+        self.render_table(frame, main[0]);
+
+        frame.render_widget(
+            Paragraph::new(
+                self.info
+                    .iter()
+                    .map(|l| Line::from(l.as_str()))
+                    .collect::<Vec<_>>(),
+            )
+            .block(Block::default().borders(Borders::ALL).title(" Projeto "))
+            .wrap(Wrap { trim: false }),
+            main[1],
+        );
+
+        if let Some((name, logs)) = &self.logs {
+            let lines: Vec<Line> = logs.lines().map(Line::from).collect();
+            // Keep the tail visible: scroll past everything that doesn't fit
+            let visible = outer[2].height.saturating_sub(2) as usize;
+            let scroll = lines.len().saturating_sub(visible) as u16;
+
+            frame.render_widget(
+                Paragraph::new(lines)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(format!(" Logs: {} ", name)),
+                    )
+                    .scroll((scroll, 0)),
+                outer[2],
+            );
+        }
+
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(format!(" {}", self.message)),
+                Line::from(" ↑/↓ navegar  s iniciar  x parar  r reiniciar  l logs  e exec  q sair")
+                    .style(Style::default().fg(Color::DarkGray)),
+            ]),
+            outer[3],
+        );
+    }
+
+    fn render_table(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(["Nome", "Tipo", "Estado", "Saúde"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let rows = self.rows.iter().map(|row| {
+            let kind = match &row.kind {
+                RowKind::DevContainer => "dev",
+                RowKind::Service(ServiceKind::Database) => "database",
+                RowKind::Service(ServiceKind::Generic) => "generic",
+            };
+            let (state, state_color) = match row.state {
+                ContainerState::Running => ("rodando", Color::Green),
+                ContainerState::Stopped => ("parado", Color::Yellow),
+                ContainerState::NotCreated => ("não criado", Color::Red),
+            };
+            let (health, health_color) = match row.health {
+                ContainerHealthStatus::Healthy => ("saudável", Color::Green),
+                ContainerHealthStatus::Unhealthy => ("unhealthy", Color::Red),
+                ContainerHealthStatus::Starting => ("iniciando", Color::Yellow),
+                ContainerHealthStatus::Unknown => ("?", Color::DarkGray),
+                ContainerHealthStatus::NotApplicable => ("-", Color::DarkGray),
+            };
+
+            Row::new([
+                Cell::from(row.name.clone()),
+                Cell::from(kind),
+                Cell::from(state).style(Style::default().fg(state_color)),
+                Cell::from(health).style(Style::default().fg(health_color)),
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(40),
+                Constraint::Percentage(20),
+                Constraint::Percentage(20),
+                Constraint::Percentage(20),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(" Containers "))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+}
+
+/// Opens the interactive dashboard
+pub fn dashboard(config_dir: &Path) -> Result<()> {
+    let runtime = Arc::new(Runtime::new(config_dir)?);
+    let mut app = Dashboard::new(environment_info(&runtime));
+
+    let (tx, rx) = mpsc::channel();
+    spawn_poller(runtime.clone(), app.log_target.clone(), tx.clone());
+
+    let mut terminal = ratatui::init();
+    let result = run_loop(&mut terminal, &mut app, &runtime, &tx, &rx);
+    ratatui::restore();
+
+    result
+}
+
+fn run_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut Dashboard,
+    runtime: &Arc<Runtime>,
+    tx: &Sender<DashboardEvent>,
+    rx: &Receiver<DashboardEvent>,
+) -> Result<()> {
+    loop {
+        while let Ok(event) = rx.try_recv() {
+            app.handle_event(event);
+        }
+
+        terminal.draw(|frame| app.render(frame))?;
+
+        if !event::poll(INPUT_POLL_INTERVAL)? {
+            continue;
+        }
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Down | KeyCode::Char('j') => app.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => app.select_previous(),
+            KeyCode::Char('s') => dispatch(app, runtime, tx, Action::Start),
+            KeyCode::Char('x') => dispatch(app, runtime, tx, Action::Stop),
+            KeyCode::Char('r') => dispatch(app, runtime, tx, Action::Restart),
+            KeyCode::Char('l') => app.toggle_logs(),
+            KeyCode::Char('e') => {
+                if let Some(row) = app.selected().cloned() {
+                    app.message = exec_into(terminal, runtime, &row)?;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Refreshes container states (and the followed logs) in the background
+fn spawn_poller(
+    runtime: Arc<Runtime>,
+    log_target: Arc<Mutex<Option<String>>>,
+    tx: Sender<DashboardEvent>,
+) {
+    thread::spawn(move || {
+        loop {
+            let event = match snapshot(&runtime) {
+                Ok(rows) => DashboardEvent::Snapshot(rows),
+                Err(e) => DashboardEvent::Message(format!("Erro ao consultar containers: {e}")),
+            };
+            if tx.send(event).is_err() {
+                return;
+            }
+
+            let target = log_target.lock().unwrap().clone();
+            if let Some(name) = target {
+                let content = runtime
+                    .container_service
+                    .logs(&name, LOG_TAIL_LINES)
+                    .unwrap_or_else(|e| format!("Erro ao ler logs: {e}"));
+                if tx.send(DashboardEvent::Logs { name, content }).is_err() {
+                    return;
+                }
+            }
+
+            thread::sleep(REFRESH_INTERVAL);
+        }
+    });
+}
+
+/// Runs a lifecycle action on the selected container without blocking the UI
+fn dispatch(
+    app: &mut Dashboard,
+    runtime: &Arc<Runtime>,
+    tx: &Sender<DashboardEvent>,
+    action: Action,
+) {
+    let Some(row) = app.selected().cloned() else {
+        return;
+    };
+
+    app.message = match action {
+        Action::Start => format!("Iniciando {}...", row.name),
+        Action::Stop => format!("Parando {}...", row.name),
+        Action::Restart => format!("Reiniciando {}...", row.name),
+    };
+
+    let runtime = runtime.clone();
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let message = match run_action(&runtime, &row, action) {
+            Ok(()) => format!("{}: concluído", row.name),
+            Err(e) => format!("{}: {e}", row.name),
+        };
+        let _ = tx.send(DashboardEvent::Message(message));
+    });
+}
+
+fn run_action(runtime: &Runtime, row: &ContainerRow, action: Action) -> Result<()> {
+    if matches!(action, Action::Stop | Action::Restart) {
+        runtime.container_service.stop(&row.name)?;
+    }
+    if action == Action::Stop {
+        return Ok(());
+    }
+
+    match row.kind {
+        RowKind::DevContainer => runtime.ensure_dev_container(),
+        RowKind::Service(_) => {
+            let svc = runtime
+                .services()
+                .iter()
+                .find(|s| s.name == row.name)
+                .with_context(|| format!("Serviço '{}' não encontrado", row.name))?;
+            runtime.ensure_svc_created(svc)?;
+            // start_all waits for the healthcheck, so the message reflects real readiness
+            runtime.orchestrator.start_all(std::slice::from_ref(svc))
+        }
+    }
+}
+
+/// Suspends the TUI, opens an interactive shell in the container and restores the TUI
+fn exec_into(
+    terminal: &mut DefaultTerminal,
+    runtime: &Runtime,
+    row: &ContainerRow,
+) -> Result<String> {
+    if row.state != ContainerState::Running {
+        return Ok(format!("{} não está rodando", row.name));
+    }
+
+    disable_raw_mode()?;
+    execute!(std::io::stdout(), LeaveAlternateScreen)?;
+
+    let result = match row.kind {
+        RowKind::DevContainer => runtime.container_service.exec_shell(
+            &row.name,
+            None,
+            &[],
+            runtime.multiplexer().as_ref(),
+            Some("devobox-default"),
+        ),
+        // Service images rarely ship bash; fall back to sh
+        RowKind::Service(_) => runtime.container_service.exec_command(
+            &row.name,
+            None,
+            &[],
+            &[
+                "sh",
+                "-c",
+                "command -v bash >/dev/null && exec bash || exec sh",
+            ],
+        ),
+    };
+
+    enable_raw_mode()?;
+    execute!(std::io::stdout(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    Ok(match result {
+        Ok(()) => format!("Sessão em {} encerrada", row.name),
+        Err(e) => format!("Falha ao entrar em {}: {e}", row.name),
+    })
+}
+
+/// Lines describing the current project and Zellij sessions
+fn environment_info(runtime: &Runtime) -> Vec<String> {
+    let context = RuntimeContext::detect();
+    let mut info = vec![format!("Contexto: {}", context)];
+
+    let project = std::env::current_dir().ok().and_then(|pwd| {
+        let devobox_toml = pwd.join("devobox.toml");
+        if !devobox_toml.exists() {
+            return None;
+        }
+        let discovery = ProjectDiscovery::new(None).ok()?;
+        let config = discovery.load_project_config(&devobox_toml).ok()?;
+        Some(Project::new(pwd, config))
+    });
+
+    match project {
+        Some(project) => {
+            info.push(format!("Projeto: {}", project.name));
+            info.push(format!("Diretório: {}", project.path.display()));
+            info.push(format!("Sessão: {}", project.session_name()));
+            if let Some(cmd) = project.startup_command() {
+                info.push(format!("Startup: {}", cmd));
+            }
+        }
+        None => info.push("Projeto: (nenhum devobox.toml no diretório atual)".to_string()),
+    }
+
+    info.push(format!(
+        "Serviços configurados: {}",
+        runtime.services().len()
+    ));
+    info.push(String::new());
+
+    let multiplexer = runtime.multiplexer();
+    let name = multiplexer.name();
+    if context.is_host() {
+        info.push(format!(
+            "Sessões {name}: disponíveis apenas dentro do container"
+        ));
+    } else {
+        match multiplexer.list_sessions() {
+            Ok(sessions) if !sessions.is_empty() => {
+                info.push(format!("Sessões {name}:"));
+                info.extend(sessions.into_iter().map(|s| format!("  - {}", s.name)));
+            }
+            Ok(_) => info.push(format!("Nenhuma sessão {name} ativa")),
+            Err(e) => info.push(format!("Erro ao listar sessões: {e}")),
+        }
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockRuntime;
+    use std::fs;
+
+    fn runtime_with_services(mock: Arc<MockRuntime>) -> (Runtime, tempfile::TempDir) {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(
+            temp.path().join("devobox.toml"),
+            r#"
+[container]
+name = "devobox-test"
+
+[services.pg]
+image = "postgres:16"
+type = "database"
+
+[services.api]
+image = "api:latest"
+"#,
+        )
+        .unwrap();
+
+        let runtime = Runtime::with_runtime(temp.path(), mock).unwrap();
+        (runtime, temp)
+    }
+
+    fn row(name: &str) -> ContainerRow {
+        ContainerRow {
+            name: name.to_string(),
+            kind: RowKind::Service(ServiceKind::Generic),
+            state: ContainerState::Running,
+            health: ContainerHealthStatus::NotApplicable,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_lists_dev_container_first() {
+        let mock = Arc::new(MockRuntime::new());
+        mock.add_container("devobox-test", ContainerState::Running);
+        mock.add_container("pg", ContainerState::Stopped);
+        let (runtime, _temp) = runtime_with_services(mock.clone());
+
+        let rows = snapshot(&runtime).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].name, "devobox-test");
+        assert_eq!(rows[0].kind, RowKind::DevContainer);
+
+        let pg = rows.iter().find(|r| r.name == "pg").unwrap();
+        assert_eq!(pg.state, ContainerState::Stopped);
+        assert_eq!(pg.kind, RowKind::Service(ServiceKind::Database));
+
+        let api = rows.iter().find(|r| r.name == "api").unwrap();
+        assert_eq!(api.state, ContainerState::NotCreated);
+
+        // Everything comes from a single listing
+        assert_eq!(mock.get_commands(), vec!["list_containers".to_string()]);
+    }
+
+    #[test]
+    fn test_run_action_start_creates_missing_service() {
+        let mock = Arc::new(MockRuntime::new());
+        let (runtime, _temp) = runtime_with_services(mock.clone());
+
+        let mut api = row("api");
+        api.state = ContainerState::NotCreated;
+        run_action(&runtime, &api, Action::Start).unwrap();
+
+        assert_eq!(mock.get_state("api"), Some(ContainerState::Running));
+        assert!(mock.get_commands().contains(&"create:api".to_string()));
+    }
+
+    #[test]
+    fn test_run_action_stop_only_stops() {
+        let mock = Arc::new(MockRuntime::new());
+        mock.add_container("pg", ContainerState::Running);
+        let (runtime, _temp) = runtime_with_services(mock.clone());
+
+        run_action(&runtime, &row("pg"), Action::Stop).unwrap();
+
+        assert_eq!(mock.get_state("pg"), Some(ContainerState::Stopped));
+        assert!(!mock.get_commands().contains(&"start:pg".to_string()));
+    }
+
+    #[test]
+    fn test_selection_wraps_around() {
+        let mut app = Dashboard::new(Vec::new());
+        app.set_rows(vec![row("a"), row("b"), row("c")]);
+        assert_eq!(app.selected().unwrap().name, "a");
+
+        app.select_previous();
+        assert_eq!(app.selected().unwrap().name, "c");
+
+        app.select_next();
+        assert_eq!(app.selected().unwrap().name, "a");
+    }
+
+    #[test]
+    fn test_selection_clamped_when_rows_shrink() {
+        let mut app = Dashboard::new(Vec::new());
+        app.set_rows(vec![row("a"), row("b"), row("c")]);
+        app.select_previous();
+
+        app.set_rows(vec![row("a")]);
+        assert_eq!(app.selected().unwrap().name, "a");
+    }
+
+    #[test]
+    fn test_toggle_logs_follows_selection() {
+        let mut app = Dashboard::new(Vec::new());
+        app.set_rows(vec![row("a"), row("b")]);
+
+        app.toggle_logs();
+        assert_eq!(app.log_target.lock().unwrap().as_deref(), Some("a"));
+
+        app.select_next();
+        app.toggle_logs();
+        assert_eq!(app.log_target.lock().unwrap().as_deref(), Some("b"));
+
+        app.toggle_logs();
+        assert!(app.log_target.lock().unwrap().is_none());
+        assert!(app.logs.is_none());
+    }
+}
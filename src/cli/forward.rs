@@ -0,0 +1,82 @@
+use crate::cli::runtime::Runtime;
+use anyhow::{Result, bail};
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use tracing::info;
+
+/// Points the container engine at the host's podman socket, mounted in the dev
+/// container by `devobox build`, so host-only commands act on the host
+pub fn to_host() -> Result<()> {
+    let socket = std::env::var("PODMAN_SOCK").unwrap_or_default();
+    let mounted = !socket.is_empty()
+        && std::fs::metadata(&socket)
+            .map(|m| m.file_type().is_socket())
+            .unwrap_or(false);
+    if !mounted {
+        bail!(
+            "Este comando roda no host e o socket do Podman do host não está montado no container.\n\
+             Rode-o no host, ou habilite o socket (systemctl --user enable --now podman.socket) e rode 'devobox build' no host."
+        );
+    }
+
+    info!(
+        " Encaminhando para o host pelo socket do Podman ({})",
+        socket
+    );
+    // SAFETY: called from main before any thread is spawned
+    unsafe { std::env::set_var("CONTAINER_HOST", format!("unix://{socket}")) };
+    Ok(())
+}
+
+/// Runs this same command line inside the dev container via `podman exec`
+pub fn to_container(config_dir: &Path, args: &[String]) -> Result<()> {
+    let runtime = Runtime::new(config_dir)?;
+    exec_in_container(&runtime, &std::env::current_dir()?, args)
+}
+
+/// Runs `args` with devobox in the dev container, in its view of `cwd`
+pub fn exec_in_container(runtime: &Runtime, cwd: &Path, args: &[String]) -> Result<()> {
+    runtime.ensure_dev_container()?;
+
+    info!(" Encaminhando para o container de desenvolvimento");
+    let args = container_args(args);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    runtime.exec_devobox(cwd, &args)
+}
+
+/// The command line without `--config-dir`, a host path the container can't see
+fn container_args(args: &[String]) -> Vec<String> {
+    let mut forwarded = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config-dir" {
+            args.next();
+        } else if !arg.starts_with("--config-dir=") {
+            forwarded.push(arg.clone());
+        }
+    }
+    forwarded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_args_drop_config_dir() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            container_args(&args(&["--config-dir", "/host/cfg", "project", "info"])),
+            args(&["project", "info"])
+        );
+        assert_eq!(
+            container_args(&args(&["-v", "--config-dir=/host/cfg", "project", "info"])),
+            args(&["-v", "project", "info"])
+        );
+        assert_eq!(
+            container_args(&args(&["--dry-run", "project", "info"])),
+            args(&["--dry-run", "project", "info"])
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use crate::domain::{ContainerState, Project};
+use crate::infra::project_env::{nearest_project, nearest_project_dir, project_env};
+use crate::infra::service_resolver::ServiceResolver;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use tracing::{info, warn};
+
+use super::runtime::Runtime;
+
+/// Name of the active project, for prompts and scripts
+const PROJECT_VAR: &str = "DEVOBOX_PROJECT";
+/// Directory of the active project (the one holding its devobox.toml)
+const PROJECT_DIR_VAR: &str = "DEVOBOX_PROJECT_DIR";
+/// Values the active project replaced, restored when leaving it (JSON)
+const BACKUP_VAR: &str = "__DEVOBOX_ENV_BACKUP";
+
+/// Shells `devobox hook` can integrate with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HookShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Prints the snippet to `eval` from the shell's rc file
+pub fn hook_init(shell: HookShell) -> Result<()> {
+    let exe = env::current_exe().context("localizando o executável do devobox")?;
+    print!("{}", render_init(shell, &exe.to_string_lossy()));
+    Ok(())
+}
+
+/// Run by the snippet on each prompt/cd: prints the commands that move the
+/// shell from the project it was in to the one containing the current directory
+pub fn hook_export(config_dir: &Path, shell: HookShell) -> Result<()> {
+    let pwd = env::current_dir()?;
+    let active = env::var(PROJECT_DIR_VAR).ok();
+
+    // Cheap check first: most prompts don't change projects
+    let target_dir = nearest_project_dir(&pwd);
+    if target_dir.map(|dir| dir.to_string_lossy().into_owned()) == active {
+        return Ok(());
+    }
+
+    let target = match nearest_project(&pwd) {
+        Ok(project) => project,
+        Err(e) => {
+            warn!("devobox: {:#}", e);
+            return Ok(());
+        }
+    };
+    let target = match target {
+        Some(project) => {
+            let env = project_env(&project)?;
+            Some((project, env))
+        }
+        None => None,
+    };
+
+    let vars: HashMap<String, String> = env::vars().collect();
+    print!(
+        "{}",
+        render_changes(shell, &transition(&vars, target.as_ref()))
+    );
+
+    if let Some((project, env)) = &target {
+        info!(
+            "devobox: projeto {} ativado ({} variável(is))",
+            project.name,
+            env.len()
+        );
+        if let Err(e) = offer_services(config_dir, project) {
+            warn!("devobox: não foi possível verificar os serviços: {:#}", e);
+        }
+    } else if let Ok(name) = env::var(PROJECT_VAR) {
+        info!("devobox: projeto {} desativado", name);
+    }
+
+    Ok(())
+}
+
+/// Asks to start the project's stopped services (interactive shells only)
+fn offer_services(config_dir: &Path, project: &Project) -> Result<()> {
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return Ok(());
+    }
+
+    let services: Vec<_> = ServiceResolver::new(config_dir)?
+        .resolve_project(project)?
+        .into_iter()
+        .map(|resolved| resolved.service)
+        .collect();
+    if services.is_empty() {
+        return Ok(());
+    }
+
+    let runtime = Runtime::new(config_dir)?;
+    let mut stopped = Vec::new();
+    for svc in services {
+        if runtime.container_service.get_status(&svc.name)?.state != ContainerState::Running {
+            stopped.push(svc);
+        }
+    }
+    if stopped.is_empty() {
+        return Ok(());
+    }
+
+    let names: Vec<&str> = stopped.iter().map(|svc| svc.name.as_str()).collect();
+    eprint!(
+        "devobox: iniciar serviços de {} ({})? [s/N] ",
+        project.name,
+        names.join(", ")
+    );
+    std::io::stderr().flush()?;
+
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    if matches!(
+        input.trim().to_lowercase().as_str(),
+        "s" | "sim" | "y" | "yes"
+    ) {
+        runtime.start_project_services(&stopped);
+    }
+    Ok(())
+}
+
+/// Variable assignments (`None` unsets) to leave the active project, if any,
+/// and enter `target`
+fn transition(
+    vars: &HashMap<String, String>,
+    target: Option<&(Project, Vec<String>)>,
+) -> Vec<(String, Option<String>)> {
+    let mut changes = Vec::new();
+
+    // Leave: put back whatever the previous project replaced
+    if let Some(backup) = vars.get(BACKUP_VAR) {
+        let backup: BTreeMap<String, Option<String>> =
+            serde_json::from_str(backup).unwrap_or_default();
+        for (key, previous) in backup {
+            change(&mut changes, key, previous);
+        }
+    }
+    for key in [PROJECT_VAR, PROJECT_DIR_VAR, BACKUP_VAR] {
+        if vars.contains_key(key) {
+            change(&mut changes, key.to_string(), None);
+        }
+    }
+
+    let Some((project, env)) = target else {
+        return changes;
+    };
+
+    // Enter: remember the values being replaced, as seen after leaving
+    let mut backup = BTreeMap::new();
+    for entry in env {
+        let Some((key, value)) = entry.split_once('=') else {
+            continue;
+        };
+        if !is_valid_name(key) {
+            warn!("devobox: ignorando variável inválida '{}'", key);
+            continue;
+        }
+        let previous = match changes.iter().find(|(k, _)| k == key) {
+            Some((_, previous)) => previous.clone(),
+            None => vars.get(key).cloned(),
+        };
+        backup.entry(key.to_string()).or_insert(previous);
+        change(&mut changes, key.to_string(), Some(value.to_string()));
+    }
+
+    change(
+        &mut changes,
+        PROJECT_VAR.to_string(),
+        Some(project.name.clone()),
+    );
+    change(
+        &mut changes,
+        PROJECT_DIR_VAR.to_string(),
+        Some(project.path.to_string_lossy().into_owned()),
+    );
+    change(
+        &mut changes,
+        BACKUP_VAR.to_string(),
+        Some(serde_json::to_string(&backup).unwrap_or_default()),
+    );
+    changes
+}
+
+fn change(changes: &mut Vec<(String, Option<String>)>, key: String, value: Option<String>) {
+    match changes.iter_mut().find(|(k, _)| *k == key) {
+        Some(existing) => existing.1 = value,
+        None => changes.push((key, value)),
+    }
+}
+
+fn is_valid_name(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn render_changes(shell: HookShell, changes: &[(String, Option<String>)]) -> String {
+    let mut out = String::new();
+    for (key, value) in changes {
+        let line = match (shell, value) {
+            (HookShell::Fish, Some(value)) => format!("set -gx {key} {};\n", fish_quote(value)),
+            (HookShell::Fish, None) => format!("set -e {key};\n"),
+            (_, Some(value)) => format!("export {key}={};\n", sh_quote(value)),
+            (_, None) => format!("unset {key};\n"),
+        };
+        out.push_str(&line);
+    }
+    out
+}
+
+pub(crate) fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+pub(crate) fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn render_init(shell: HookShell, exe: &str) -> String {
+    match shell {
+        HookShell::Bash => format!(
+            r#"_devobox_hook() {{
+  local previous_exit_status=$?
+  eval "$({exe} hook bash --export)"
+  return $previous_exit_status
+}}
+if [[ ";${{PROMPT_COMMAND[*]:-}};" != *";_devobox_hook;"* ]]; then
+  PROMPT_COMMAND="_devobox_hook${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}"
+fi
+"#,
+            exe = sh_quote(exe)
+        ),
+        HookShell::Zsh => format!(
+            r#"_devobox_hook() {{
+  eval "$({exe} hook zsh --export)"
+}}
+typeset -ag chpwd_functions
+if (( ! ${{chpwd_functions[(I)_devobox_hook]}} )); then
+  chpwd_functions=(_devobox_hook $chpwd_functions)
+fi
+_devobox_hook
+"#,
+            exe = sh_quote(exe)
+        ),
+        HookShell::Fish => format!(
+            r#"function __devobox_hook --on-variable PWD
+    {exe} hook fish --export | source
+end
+__devobox_hook
+"#,
+            exe = fish_quote(exe)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ProjectConfig, ProjectSettings};
+    use std::path::PathBuf;
+
+    fn project(name: &str, env: &[&str]) -> (Project, Vec<String>) {
+        let config = ProjectConfig {
+            project: Some(ProjectSettings {
+                name: Some(name.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let env = env.iter().map(|entry| entry.to_string()).collect();
+        (
+            Project::new(PathBuf::from(format!("/code/{name}")), config),
+            env,
+        )
+    }
+
+    fn vars(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// What the shell ends up with after evaluating the hook's output
+    fn apply(vars: &mut HashMap<String, String>, target: Option<&(Project, Vec<String>)>) {
+        for (key, value) in transition(vars, target) {
+            match value {
+                Some(value) => vars.insert(key, value),
+                None => vars.remove(&key),
+            };
+        }
+    }
+
+    #[test]
+    fn test_enter_and_leave_restores_previous_values() {
+        let mut env = vars(&[("PORT", "80"), ("HOME", "/home/dev")]);
+        let original = env.clone();
+
+        let api = project("api", &["PORT=3000", "DEBUG=1"]);
+        apply(&mut env, Some(&api));
+        assert_eq!(env["PORT"], "3000");
+        assert_eq!(env["DEBUG"], "1");
+        assert_eq!(env[PROJECT_VAR], "api");
+        assert_eq!(env[PROJECT_DIR_VAR], "/code/api");
+
+        apply(&mut env, None);
+        assert_eq!(env, original);
+    }
+
+    #[test]
+    fn test_switching_projects_does_not_leak_env() {
+        let mut env = vars(&[("PORT", "80")]);
+
+        let api = project("api", &["PORT=3000", "DEBUG=1"]);
+        apply(&mut env, Some(&api));
+
+        let web = project("web", &["PORT=8080"]);
+        apply(&mut env, Some(&web));
+        assert_eq!(env["PORT"], "8080");
+        assert!(!env.contains_key("DEBUG"));
+        assert_eq!(env[PROJECT_VAR], "web");
+
+        apply(&mut env, None);
+        assert_eq!(env, vars(&[("PORT", "80")]));
+    }
+
+    #[test]
+    fn test_render_changes_quotes_for_each_shell() {
+        let changes = vec![
+            ("A".to_string(), Some("it's".to_string())),
+            ("B".to_string(), None),
+        ];
+        assert_eq!(
+            render_changes(HookShell::Bash, &changes),
+            "export A='it'\\''s';\nunset B;\n"
+        );
+        assert_eq!(
+            render_changes(HookShell::Fish, &changes),
+            "set -gx A 'it\\'s';\nset -e B;\n"
+        );
+    }
+
+    #[test]
+    fn test_render_init_calls_export() {
+        assert!(
+            render_init(HookShell::Bash, "/usr/bin/devobox")
+                .contains("eval \"$('/usr/bin/devobox' hook bash --export)\"")
+        );
+        assert!(render_init(HookShell::Zsh, "/usr/bin/devobox").contains("chpwd_functions"));
+        assert!(
+            render_init(HookShell::Fish, "/usr/bin/devobox")
+                .contains("'/usr/bin/devobox' hook fish --export | source")
+        );
+    }
+}
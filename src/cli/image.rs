@@ -0,0 +1,93 @@
+use crate::cli::builder::recreate_dev_container;
+use crate::cli::context::RuntimeContext;
+use crate::infra::config::{AppConfig, load_app_config};
+use crate::infra::engine::create_runtime;
+use crate::services::{ContainerService, DEFAULT_KEEP_IMAGES, ImageHistory, SystemService};
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Lists the builds of the dev image, marking the one in use
+pub fn image_list(config_dir: &Path) -> Result<()> {
+    let app_config = load_app_config(config_dir)?;
+    let history = history(
+        &app_config,
+        Arc::new(SystemService::new(create_runtime(&app_config.runtime)?)),
+    )?;
+    let versions = history.versions()?;
+
+    if versions.is_empty() {
+        info!(
+            " Nenhuma versão de {} encontrada. Rode 'devobox build'.",
+            history.repository()
+        );
+        return Ok(());
+    }
+
+    println!(" Versões de {}:", history.repository());
+    for version in versions {
+        let current = if version.current { " (atual)" } else { "" };
+        println!(
+            "- {:<17} | {:<14}{}",
+            version.tag,
+            short_id(&version.id),
+            current
+        );
+    }
+
+    Ok(())
+}
+
+/// Points the dev image back at an older build (the previous one by default)
+/// and recreates the dev container from it
+pub fn image_rollback(config_dir: &Path, tag: Option<&str>) -> Result<()> {
+    let app_config = load_app_config(config_dir)?;
+    let runtime = create_runtime(&app_config.runtime)?;
+    let history = history(&app_config, Arc::new(SystemService::new(runtime.clone())))?;
+
+    let target = match tag {
+        Some(tag) => tag.to_string(),
+        None => history.previous()?.tag,
+    };
+    let version = history.activate(&target)?;
+    info!(
+        " Imagem de desenvolvimento agora aponta para {}",
+        version.reference
+    );
+
+    // Same as `devobox build` from the dev container: it can't recreate itself
+    if RuntimeContext::detect().is_container() {
+        warn!("  Rollback pelo container: o container de desenvolvimento não foi recriado.");
+        warn!(
+            "   Rode 'devobox image rollback {}' no host para usá-la.",
+            version.tag
+        );
+        return Ok(());
+    }
+
+    recreate_dev_container(&app_config, &ContainerService::new(runtime))?;
+    info!(
+        " Container de desenvolvimento recriado a partir de {}",
+        version.tag
+    );
+    Ok(())
+}
+
+fn history(app_config: &AppConfig, system: Arc<SystemService>) -> Result<ImageHistory> {
+    if app_config.build.image.is_some() && app_config.build.containerfile.is_none() {
+        bail!("A imagem do projeto vem de [build] image e não é versionada pelo devobox");
+    }
+
+    let image = app_config
+        .build
+        .image_name
+        .as_deref()
+        .context("Image name not set in config")?;
+    let keep = app_config.build.keep_images.unwrap_or(DEFAULT_KEEP_IMAGES);
+    Ok(ImageHistory::new(system, image, keep))
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..12).unwrap_or(id)
+}
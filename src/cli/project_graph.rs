@@ -0,0 +1,302 @@
+use crate::domain::{Project, ProjectConfig};
+use crate::infra::ProjectDiscovery;
+use crate::infra::config::DEFAULT_DEVOBOX_TOML_NAME;
+use crate::infra::project_graph::{ProjectGraph, ProjectNode};
+use crate::infra::service_resolver::{ServiceContribution, ServiceResolver};
+use anyhow::{Context, Result, bail};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Output of `devobox project graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Árvore legível no terminal
+    Text,
+    /// Graphviz DOT (ex.: `| dot -Tsvg > deps.svg`)
+    Dot,
+}
+
+/// Shows the projects `name` (or the project in the current directory) depends
+/// on, transitively, and the services each one contributes
+pub fn project_graph(config_dir: &Path, name: Option<&str>, format: GraphFormat) -> Result<()> {
+    let project = match name {
+        Some(name) => ProjectDiscovery::from_config(config_dir)?
+            .find_project(name)?
+            .with_context(|| format!("Projeto '{}' não encontrado", name))?,
+        None => current_project()?,
+    };
+
+    let graph = ProjectGraph::build(project.clone(), &project.path)?;
+    let resolver = ServiceResolver::new(config_dir)?;
+    let contributions =
+        resolver.resolve_graph(&graph, resolver.project_services(&project, None)?)?;
+
+    let output = match format {
+        GraphFormat::Text => render_text(&graph, &contributions),
+        GraphFormat::Dot => render_dot(&graph, &contributions),
+    };
+    print!("{output}");
+    Ok(())
+}
+
+fn current_project() -> Result<Project> {
+    let dir = env::current_dir()?;
+    let path = dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    if !path.exists() {
+        bail!(
+            "Nenhum {} em {}. Informe o nome do projeto: devobox project graph <nome>",
+            DEFAULT_DEVOBOX_TOML_NAME,
+            dir.display()
+        );
+    }
+
+    let content = fs::read_to_string(&path).with_context(|| format!("lendo {:?}", path))?;
+    let config: ProjectConfig =
+        toml::from_str(&content).with_context(|| format!("parse de {:?}", path))?;
+    Ok(Project::new(dir, config))
+}
+
+fn by_path<'a>(
+    contributions: &'a [ServiceContribution<'a>],
+) -> HashMap<&'a Path, &'a ServiceContribution<'a>> {
+    contributions
+        .iter()
+        .map(|c| (c.node.project.path.as_path(), c))
+        .collect()
+}
+
+fn render_text(graph: &ProjectGraph, contributions: &[ServiceContribution]) -> String {
+    let mut out = String::new();
+    let mut printed = HashSet::new();
+    let contributions = by_path(contributions);
+    write_tree(
+        &mut out,
+        graph,
+        &contributions,
+        &graph.root,
+        ("", ""),
+        &mut printed,
+    );
+    out
+}
+
+/// `prefixes` are what precedes the node's own line and its children's lines
+fn write_tree<'g>(
+    out: &mut String,
+    graph: &'g ProjectGraph,
+    contributions: &HashMap<&Path, &ServiceContribution>,
+    node: &'g ProjectNode,
+    (head, indent): (&str, &str),
+    printed: &mut HashSet<&'g Path>,
+) {
+    let project = &node.project;
+    let _ = write!(out, "{head}{}  ({})", project.name, project.path.display());
+
+    // Diamonds: expand a project only the first time it shows up
+    if !printed.insert(project.path.as_path()) {
+        out.push_str("  (já listado acima)\n");
+        return;
+    }
+    out.push('\n');
+
+    let detail = format!(
+        "{indent}{}",
+        if node.dependencies.is_empty() {
+            "    "
+        } else {
+            "│   "
+        }
+    );
+    if let Some(contribution) = contributions.get(project.path.as_path()) {
+        let services: Vec<&str> = contribution
+            .services
+            .iter()
+            .map(|r| r.service.name.as_str())
+            .collect();
+        if services.is_empty() {
+            let _ = writeln!(out, "{detail}sem serviços");
+        } else {
+            let _ = writeln!(out, "{detail}serviços: {}", services.join(", "));
+        }
+        if !contribution.shadowed.is_empty() {
+            let _ = writeln!(
+                out,
+                "{detail}substituídos (override): {}",
+                contribution.shadowed.join(", ")
+            );
+        }
+    }
+
+    let deps: Vec<&ProjectNode> = node
+        .dependencies
+        .iter()
+        .filter_map(|path| graph.find(path))
+        .collect();
+    for (i, dep) in deps.iter().enumerate() {
+        let last = i == deps.len() - 1;
+        let head = format!("{indent}{}", if last { "└── " } else { "├── " });
+        let indent = format!("{indent}{}", if last { "    " } else { "│   " });
+        write_tree(out, graph, contributions, dep, (&head, &indent), printed);
+    }
+}
+
+fn render_dot(graph: &ProjectGraph, contributions: &[ServiceContribution]) -> String {
+    let contributions = by_path(contributions);
+    let mut out = String::from("digraph devobox {\n    rankdir=LR;\n    node [shape=box];\n");
+
+    for node in graph.projects() {
+        let project = &node.project;
+        let mut label = project.name.clone();
+        if let Some(contribution) = contributions.get(project.path.as_path()) {
+            let services: Vec<&str> = contribution
+                .services
+                .iter()
+                .map(|r| r.service.name.as_str())
+                .collect();
+            if !services.is_empty() {
+                label.push('\n');
+                label.push_str(&services.join(", "));
+            }
+            if !contribution.shadowed.is_empty() {
+                label.push_str(&format!(
+                    "\n(substituídos: {})",
+                    contribution.shadowed.join(", ")
+                ));
+            }
+        }
+
+        let id = dot_quote(&project.path.to_string_lossy());
+        let _ = writeln!(out, "    {id} [label={}];", dot_quote(&label));
+    }
+
+    for node in graph.projects() {
+        let from = dot_quote(&node.project.path.to_string_lossy());
+        for dep in &node.dependencies {
+            let _ = writeln!(out, "    {from} -> {};", dot_quote(&dep.to_string_lossy()));
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn dot_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Service, ServiceKind};
+    use crate::infra::service_resolver::ResolvedService;
+    use std::path::PathBuf;
+
+    fn node(name: &str, deps: &[&str]) -> ProjectNode {
+        ProjectNode {
+            project: Project::new(
+                PathBuf::from(format!("/code/{name}")),
+                ProjectConfig::default(),
+            ),
+            dependencies: deps
+                .iter()
+                .map(|dep| PathBuf::from(format!("/code/{dep}")))
+                .collect(),
+        }
+    }
+
+    fn resolved(name: &str) -> ResolvedService {
+        let service = Service {
+            name: name.to_string(),
+            image: "img".to_string(),
+            kind: ServiceKind::Generic,
+            ports: vec![],
+            env: vec![],
+            volumes: vec![],
+            healthcheck_command: None,
+            healthcheck_interval: None,
+            healthcheck_timeout: None,
+            healthcheck_retries: None,
+            project: None,
+        };
+        ResolvedService {
+            service,
+            source_file: PathBuf::from("/code/devobox.toml"),
+            via_project: None,
+            shared: false,
+        }
+    }
+
+    // app -> auth -> shared, app -> billing -> shared
+    fn diamond() -> ProjectGraph {
+        ProjectGraph {
+            root: node("app", &["auth", "billing"]),
+            dependencies: vec![
+                node("auth", &["shared"]),
+                node("shared", &[]),
+                node("billing", &["shared"]),
+            ],
+        }
+    }
+
+    fn contributions(graph: &ProjectGraph) -> Vec<ServiceContribution<'_>> {
+        graph
+            .projects()
+            .map(|node| {
+                let (services, shadowed) = match node.project.name.as_str() {
+                    "app" => (vec![resolved("pg")], vec![]),
+                    "shared" => (vec![resolved("mq")], vec!["pg".to_string()]),
+                    _ => (vec![], vec![]),
+                };
+                ServiceContribution {
+                    node,
+                    services,
+                    shadowed,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_text_tree() {
+        let graph = diamond();
+
+        assert_eq!(
+            render_text(&graph, &contributions(&graph)),
+            "app  (/code/app)\n\
+             │   serviços: pg\n\
+             ├── auth  (/code/auth)\n\
+             │   │   sem serviços\n\
+             │   └── shared  (/code/shared)\n\
+             │           serviços: mq\n\
+             │           substituídos (override): pg\n\
+             └── billing  (/code/billing)\n\
+             \x20   │   sem serviços\n\
+             \x20   └── shared  (/code/shared)  (já listado acima)\n"
+        );
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let graph = diamond();
+        let dot = render_dot(&graph, &contributions(&graph));
+
+        assert!(dot.starts_with("digraph devobox {\n"));
+        assert!(dot.contains("    \"/code/app\" [label=\"app\\npg\"];\n"));
+        assert!(dot.contains("[label=\"shared\\nmq\\n(substituídos: pg)\"];\n"));
+        assert!(dot.contains("    \"/code/auth\" -> \"/code/shared\";\n"));
+        assert!(dot.contains("    \"/code/billing\" -> \"/code/shared\";\n"));
+        assert_eq!(dot.matches("[label=\"shared").count(), 1);
+    }
+
+    #[test]
+    fn test_dot_quote_escapes() {
+        assert_eq!(dot_quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+}
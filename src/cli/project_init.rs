@@ -0,0 +1,181 @@
+use crate::domain::ServiceKind;
+use crate::infra::config::DEFAULT_DEVOBOX_TOML_NAME;
+use crate::infra::engine::is_dry_run;
+use crate::infra::stack_detection::{StackReport, detect_stack};
+use anyhow::{Context, Result, bail};
+use std::fmt::Write;
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use tracing::info;
+
+/// Generates a devobox.toml for `path` (default: current directory) from the
+/// detected stack, showing it before writing
+pub fn project_init(path: Option<&Path>, yes: bool, force: bool) -> Result<()> {
+    let dir = match path {
+        Some(path) => path.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    if !dir.is_dir() {
+        bail!("Diretório não encontrado: {}", dir.display());
+    }
+
+    let target = dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    if target.exists() && !force {
+        bail!(
+            "Já existe {}. Use --force para sobrescrever.",
+            target.display()
+        );
+    }
+
+    let report = detect_stack(&dir)?;
+    if report.stacks.is_empty() {
+        info!(" Nenhuma stack reconhecida; gerando um devobox.toml mínimo");
+    } else {
+        let stacks: Vec<String> = report.stacks.iter().map(ToString::to_string).collect();
+        info!(" Stack detectada: {}", stacks.join(", "));
+    }
+
+    let content = render(&report);
+    info!(" Prévia de {}:\n", target.display());
+    println!("{content}");
+
+    if is_dry_run() {
+        info!(" Dry-run: nada foi gravado");
+        return Ok(());
+    }
+
+    if !yes && !confirm(&format!("Gravar {}?", target.display()))? {
+        info!(" Nada foi gravado.");
+        return Ok(());
+    }
+
+    fs::write(&target, content).with_context(|| format!("gravando {:?}", target))?;
+    info!(" Criado {}", target.display());
+    info!(" Confira com: devobox config validate");
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{question} [s/N] ");
+    std::io::stdout().flush()?;
+
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(matches!(
+        input.trim().to_lowercase().as_str(),
+        "s" | "sim" | "y" | "yes"
+    ))
+}
+
+/// The devobox.toml text, with a comment saying where each service came from
+fn render(report: &StackReport) -> String {
+    let mut out = String::new();
+
+    let stacks: Vec<String> = report.stacks.iter().map(ToString::to_string).collect();
+    if stacks.is_empty() {
+        out.push_str("# Gerado por `devobox project init`\n");
+    } else {
+        let _ = writeln!(
+            out,
+            "# Gerado por `devobox project init` (stack: {})",
+            stacks.join(", ")
+        );
+    }
+
+    out.push_str("[project]\n");
+    if let Some(command) = &report.startup_command {
+        let _ = writeln!(out, "startup_command = {}", quote(command));
+    }
+    if !report.env.is_empty() {
+        let _ = writeln!(out, "env = {}", list(&report.env));
+    }
+
+    for service in &report.services {
+        let _ = writeln!(out, "\n# Sugerido por {}", service.reason);
+        let _ = writeln!(out, "[services.{}]", key(&service.name));
+        if service.kind == ServiceKind::Database {
+            out.push_str("type = \"database\"\n");
+        }
+        let _ = writeln!(out, "image = {}", quote(&service.image));
+        if !service.ports.is_empty() {
+            let _ = writeln!(out, "ports = {}", list(&service.ports));
+        }
+        if !service.env.is_empty() {
+            let _ = writeln!(out, "env = {}", list(&service.env));
+        }
+        if let Some(command) = &service.healthcheck_command {
+            let _ = writeln!(out, "healthcheck_command = {}", quote(command));
+        }
+    }
+
+    out
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn list(values: &[String]) -> String {
+    let items: Vec<String> = values.iter().map(|value| quote(value)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Bare keys when possible, quoted otherwise (compose names may contain dots)
+fn key(name: &str) -> String {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProjectConfig;
+    use crate::infra::stack_detection::{Stack, SuggestedService};
+
+    #[test]
+    fn test_render_round_trips() {
+        let report = StackReport {
+            stacks: vec![Stack::Node],
+            startup_command: Some("npm run dev".to_string()),
+            env: vec!["DATABASE_URL=postgres://localhost/app?ssl=\"off\"".to_string()],
+            services: vec![SuggestedService {
+                name: "db.main".to_string(),
+                kind: ServiceKind::Database,
+                image: "postgres:16".to_string(),
+                ports: vec!["5432:5432".to_string()],
+                env: vec!["POSTGRES_PASSWORD=dev".to_string()],
+                healthcheck_command: Some("pg_isready -U postgres".to_string()),
+                reason: "docker-compose.yml".to_string(),
+            }],
+        };
+
+        let content = render(&report);
+        assert!(content.starts_with("# Gerado por `devobox project init` (stack: Node)\n"));
+        assert!(content.contains("\n# Sugerido por docker-compose.yml\n[services.\"db.main\"]\n"));
+
+        let config: ProjectConfig = toml::from_str(&content).unwrap();
+        let settings = config.project.unwrap();
+        assert_eq!(settings.startup_command.as_deref(), Some("npm run dev"));
+        assert_eq!(settings.env, report.env);
+
+        let services = config.services.unwrap();
+        let db = &services["db.main"];
+        assert_eq!(db.kind, Some(ServiceKind::Database));
+        assert_eq!(db.image.as_deref(), Some("postgres:16"));
+    }
+
+    #[test]
+    fn test_render_minimal() {
+        assert_eq!(
+            render(&StackReport::default()),
+            "# Gerado por `devobox project init`\n[project]\n"
+        );
+    }
+}
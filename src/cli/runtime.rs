@@ -1,8 +1,18 @@
-use crate::domain::{ContainerState, Service, ServiceKind};
-use crate::infra::config::{AppConfig, load_app_config, resolve_project_services};
-use crate::infra::{PodmanAdapter, ProjectDiscovery};
+use crate::cli::hook::{fish_quote, sh_quote};
+use crate::domain::traits::ContainerHealthStatus;
+use crate::domain::{BuildOptions, ContainerFilter, ContainerState, Service, ServiceKind};
+use crate::infra::ProjectDiscovery;
+use crate::infra::config::{
+    AppConfig, DEFAULT_CONTAINER_NAME, MultiplexerKind, load_app_config, load_app_config_from,
+    load_global_config,
+};
+use crate::infra::engine::create_runtime;
+use crate::infra::project_env::{nearest_project, project_env};
+use crate::infra::project_graph::ProjectGraph;
+use crate::infra::service_resolver::ServiceResolver;
 use crate::services::{
-    CleanupOptions, ContainerService, Orchestrator, SystemService, ZellijService,
+    CleanupOptions, ContainerService, Multiplexer, NoMultiplexer, Orchestrator, SystemService,
+    TmuxService, ZellijService,
 };
 use anyhow::{Context, Result, bail};
 use std::env;
@@ -22,18 +32,45 @@ pub struct Runtime {
 
 impl Runtime {
     pub fn new(global_config_dir: &Path) -> Result<Self> {
-        let runtime = Arc::new(PodmanAdapter::new());
-        Self::with_runtime(global_config_dir, runtime)
+        Self::for_dir(global_config_dir, Path::new("./"))
+    }
+
+    /// Runtime of the project in `dir`, whose own dev image (if any) picks the
+    /// dev container regardless of the current directory
+    pub fn for_dir(global_config_dir: &Path, dir: &Path) -> Result<Self> {
+        let app_config = load_app_config_from(global_config_dir, dir)?;
+        let runtime = create_runtime(&app_config.runtime)?;
+        Self::from_parts(global_config_dir, dir, app_config, runtime)
     }
 
     pub fn with_runtime(
         global_config_dir: &Path,
         runtime: Arc<dyn crate::domain::ContainerRuntime>,
     ) -> Result<Self> {
-        let app_config = load_app_config(global_config_dir)?;
+        Self::for_dir_with_runtime(global_config_dir, Path::new("./"), runtime)
+    }
+
+    pub fn for_dir_with_runtime(
+        global_config_dir: &Path,
+        dir: &Path,
+        runtime: Arc<dyn crate::domain::ContainerRuntime>,
+    ) -> Result<Self> {
+        let app_config = load_app_config_from(global_config_dir, dir)?;
+        Self::from_parts(global_config_dir, dir, app_config, runtime)
+    }
 
-        // Use resolve_all_services to load local services AND dependencies
-        let services = crate::infra::config::resolve_all_services(global_config_dir, &app_config)?;
+    fn from_parts(
+        global_config_dir: &Path,
+        dir: &Path,
+        app_config: AppConfig,
+        runtime: Arc<dyn crate::domain::ContainerRuntime>,
+    ) -> Result<Self> {
+        // Local services AND those of included projects
+        let services = ServiceResolver::new(global_config_dir)?
+            .resolve_workspace(&app_config, dir)?
+            .into_iter()
+            .map(|resolved| resolved.service)
+            .collect();
 
         let container_service = Arc::new(ContainerService::new(runtime.clone()));
         let system_service = Arc::new(SystemService::new(runtime));
@@ -111,60 +148,134 @@ impl Runtime {
     }
 
     pub fn start_svc(&self, service_name: &str) -> Result<()> {
-        let svc = self
-            .services
-            .iter()
-            .find(|s| s.name == service_name)
-            .context(format!(
-                "Serviço '{}' não está listado na configuração",
-                service_name
-            ))?;
-
+        let svc = self.known_svc(service_name)?;
         self.ensure_svc_created(svc)?;
-        self.container_service.start(service_name)
+        self.container_service.start(&svc.name)
     }
 
     pub fn stop_svc(&self, service_name: &str) -> Result<()> {
-        if !self.is_known_svc(service_name) {
-            bail!(
-                "Serviço '{}' não está listado na configuração",
-                service_name
-            );
-        }
-        self.container_service.stop(service_name)
+        let svc = self.known_svc(service_name)?;
+        self.container_service.stop(&svc.name)
     }
 
     pub fn restart_svc(&self, service_name: &str) -> Result<()> {
-        if !self.is_known_svc(service_name) {
-            bail!(
-                "Serviço '{}' não está listado na configuração",
-                service_name
-            );
-        }
-        self.container_service.stop(service_name)?;
-        self.container_service.start(service_name)
+        let svc = self.known_svc(service_name)?;
+        self.container_service.stop(&svc.name)?;
+        self.container_service.start(&svc.name)
     }
 
     pub fn is_known_svc(&self, name: &str) -> bool {
-        self.services.iter().any(|svc| svc.name == name)
+        matches!(self.find_svc(name), Ok(Some(_)))
+    }
+
+    /// Finds a service by container name or by the name in its `[services.NAME]`
+    ///
+    /// With namespaced containers a short name defined by several projects is
+    /// ambiguous and must be qualified as `<project>-<service>`.
+    pub fn find_svc(&self, name: &str) -> Result<Option<&Service>> {
+        if let Some(svc) = self.services.iter().find(|svc| svc.name == name) {
+            return Ok(Some(svc));
+        }
+
+        let matches: Vec<&Service> = self
+            .services
+            .iter()
+            .filter(|svc| svc.short_name() == name)
+            .collect();
+        match matches.as_slice() {
+            [] => Ok(None),
+            [svc] => Ok(Some(svc)),
+            _ => {
+                let names: Vec<&str> = matches.iter().map(|svc| svc.name.as_str()).collect();
+                bail!(
+                    "Serviço '{}' existe em mais de um projeto; use o nome completo: {}",
+                    name,
+                    names.join(", ")
+                )
+            }
+        }
+    }
+
+    fn known_svc(&self, name: &str) -> Result<&Service> {
+        self.find_svc(name)?
+            .with_context(|| format!("Serviço '{}' não está listado na configuração", name))
+    }
+
+    /// Services resolved from the configuration (local + dependencies)
+    pub fn services(&self) -> &[Service] {
+        &self.services
+    }
+
+    pub fn main_container_name(&self) -> Result<&str> {
+        self.app_config
+            .container
+            .name
+            .as_deref()
+            .context("Main container name not set in config")
+    }
+
+    /// Dev containers: this directory's first, then the shared one and those of
+    /// projects with an image of their own
+    pub fn dev_containers(&self) -> Result<Vec<String>> {
+        let mut names = vec![self.main_container_name()?.to_string()];
+        let shared = load_global_config(&self.global_config_dir)?
+            .container
+            .name
+            .unwrap_or_else(|| DEFAULT_CONTAINER_NAME.to_string());
+        names.push(shared);
+
+        // Projects that can't be discovered or loaded just aren't listed
+        let projects = ProjectDiscovery::from_config(&self.global_config_dir)
+            .and_then(|discovery| discovery.discover_all())
+            .unwrap_or_default();
+        for project in projects {
+            if let Ok(config) = load_app_config_from(&self.global_config_dir, &project.path)
+                && config.has_project_image()
+                && let Some(name) = config.container.name
+            {
+                names.push(name);
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+        Ok(names)
     }
 
     pub fn status(&self) -> Result<()> {
         println!(" Status dos containers:");
         let mut missing = false;
 
-        for name in self.all_containers() {
-            let container = self.container_service.get_status(&name)?;
-            let state = match container.state {
-                crate::domain::ContainerState::Running => "rodando",
-                crate::domain::ContainerState::Stopped => "parado",
-                crate::domain::ContainerState::NotCreated => {
+        let dev_containers = self.dev_containers()?;
+        let mut names = dev_containers.clone();
+        names.extend(self.services.iter().map(|svc| svc.name.clone()));
+        let snapshot = self
+            .container_service
+            .list(&ContainerFilter::names(names.iter().cloned()))?;
+
+        for name in names {
+            let info = snapshot.iter().find(|info| info.name == name);
+            // Dev containers other than this directory's only show up once built
+            if info.is_none() && dev_containers[1..].contains(&name) {
+                continue;
+            }
+            let state = match info.map(|info| &info.state) {
+                Some(ContainerState::Running) => "rodando",
+                Some(ContainerState::Stopped) => "parado",
+                Some(ContainerState::NotCreated) | None => {
                     missing = true;
                     "não criado"
                 }
             };
 
-            println!("- {:<10} | {}", name, state);
+            let health = match info.map(|info| &info.health) {
+                Some(ContainerHealthStatus::Healthy) => " (saudável)",
+                Some(ContainerHealthStatus::Unhealthy) => " (não saudável)",
+                Some(ContainerHealthStatus::Starting) => " (iniciando)",
+                _ => "",
+            };
+
+            println!("- {:<10} | {}{}", name, state, health);
         }
 
         if missing {
@@ -174,7 +285,9 @@ impl Runtime {
         Ok(())
     }
 
-    pub fn run_shell(&self, with_dbs: bool, auto_stop: bool) -> Result<()> {
+    /// Opens a shell in the dev container: inside the multiplexer session of
+    /// the current directory, or a bare login shell when `plain`
+    pub fn run_shell(&self, with_dbs: bool, auto_stop: bool, plain: bool) -> Result<()> {
         if with_dbs {
             self.start_services_by_filter(None)?;
         }
@@ -200,26 +313,17 @@ impl Runtime {
 
         let home = std::env::var("HOME").unwrap_or_default();
         let code_dir = PathBuf::from(&home).join("code");
+        let plain = plain || self.app_config.container.multiplexer == Some(MultiplexerKind::None);
 
-        if devobox_toml.exists() {
+        // A plain shell has no layout to open; it only takes the project's env
+        if devobox_toml.exists() && !plain {
             let discovery = ProjectDiscovery::new(None)?;
             match discovery.load_project_config(&devobox_toml) {
                 Ok(config) => {
                     let project = crate::domain::Project::new(pwd.clone(), config);
                     info!(" Detectado projeto: {}", project.name);
 
-                    let cmd = vec!["devobox", "project", "up", &project.name];
-
-                    let status = std::process::Command::new("podman")
-                        .args(["exec", "-it"])
-                        .arg(main_container_name)
-                        .args(cmd)
-                        .status()
-                        .context("Falha ao executar devobox project up via podman")?;
-
-                    if !status.success() {
-                        bail!("Falha ao iniciar projeto via devobox project up inside container");
-                    }
+                    self.exec_project_up(&project, &project.name, false)?;
 
                     if auto_stop {
                         self.stop_all_containers()?;
@@ -246,9 +350,31 @@ impl Runtime {
             "devobox-default".to_string()
         };
 
+        // Subdirectories of a project still get its env in the new session
+        let project = nearest_project(&pwd).unwrap_or_else(|e| {
+            warn!("  Ignorando env do projeto: {}", e);
+            None
+        });
+        let env = match &project {
+            Some(project) => project_env(project)?,
+            None => Vec::new(),
+        };
+
+        let shell = project.as_ref().and_then(|p| p.shell());
+        if let Some(shell) = shell {
+            self.ensure_shell(shell)?;
+        }
+
+        let multiplexer: Box<dyn Multiplexer> = if plain {
+            Box::new(NoMultiplexer::with_shell(shell))
+        } else {
+            multiplexer(&self.app_config, shell)
+        };
         let result = self.container_service.exec_shell(
             main_container_name,
             workdir_in_container.as_deref(),
+            &env,
+            multiplexer.as_ref(),
             Some(&session_name),
         );
 
@@ -259,6 +385,53 @@ impl Runtime {
         result
     }
 
+    /// Runs `devobox project up <name>` in the dev container, which opens
+    /// (or attaches to) the project's session. With `session_only` the
+    /// caller has already started the project's services
+    pub fn exec_project_up(
+        &self,
+        project: &crate::domain::Project,
+        name: &str,
+        session_only: bool,
+    ) -> Result<()> {
+        let mut args = vec!["project", "up", name];
+        if session_only {
+            args.push("--session-only");
+        }
+        self.exec_devobox(&project.path, &args)
+            .context("Falha ao iniciar projeto via devobox project up inside container")
+    }
+
+    /// Runs `devobox <args>` in the dev container, in the container's view of
+    /// the host directory `dir`
+    pub fn exec_devobox(&self, dir: &Path, args: &[&str]) -> Result<()> {
+        let mut command = vec!["devobox"];
+        command.extend_from_slice(args);
+        self.container_service.exec_command(
+            self.main_container_name()?,
+            Some(&container_path(dir)),
+            &[],
+            &command,
+        )
+    }
+
+    /// Fails with a hint when `shell` isn't installed in the dev container
+    pub fn ensure_shell(&self, shell: &str) -> Result<()> {
+        let found = self.container_service.exec_output(
+            self.main_container_name()?,
+            &["sh", "-c", "command -v \"$1\"", "sh", shell],
+        );
+        if found.is_err() {
+            bail!(missing_shell(shell));
+        }
+        Ok(())
+    }
+
+    /// The multiplexer configured in `[container] multiplexer`
+    pub fn multiplexer(&self) -> Box<dyn Multiplexer> {
+        multiplexer(&self.app_config, None)
+    }
+
     pub fn stop_all_containers(&self) -> Result<()> {
         let containers = self.all_containers();
         self.orchestrator.stop_all(&containers)
@@ -291,6 +464,24 @@ impl Runtime {
         self.orchestrator.reset_system()
     }
 
+    /// Creates and starts a project's services; failures are reported, not fatal
+    pub fn start_project_services(&self, services: &[Service]) {
+        // Ensure all services are created first
+        for svc in services {
+            if let Err(e) = self.ensure_svc_created(svc) {
+                warn!("  Aviso ao criar serviço {}: {}", svc.name, e);
+            }
+        }
+
+        // Start all services
+        if let Err(e) = self.orchestrator.start_all(services) {
+            warn!("  Erro ao iniciar serviços: {}", e);
+            warn!("  Continuando mesmo assim...");
+        } else {
+            info!(" Serviços iniciados com sucesso!");
+        }
+    }
+
     pub fn ensure_svc_created(&self, svc: &Service) -> Result<()> {
         let status = self.container_service.get_status(&svc.name)?;
 
@@ -303,7 +494,7 @@ impl Runtime {
     }
 }
 
-pub fn shell(config_dir: &Path, with_dbs: bool, auto_stop: bool) -> Result<()> {
+pub fn shell(config_dir: &Path, with_dbs: bool, auto_stop: bool, plain: bool) -> Result<()> {
     if !config_dir.exists() {
         warn!("  Ambiente não configurado.");
         info!(" Executando setup inicial automaticamente...\n");
@@ -324,12 +515,12 @@ pub fn shell(config_dir: &Path, with_dbs: bool, auto_stop: bool) -> Result<()> {
         warn!("  Container '{}' não encontrado.", main_container_name);
         info!(" Construindo ambiente...\n");
 
-        crate::cli::builder::build(config_dir, false)?;
+        crate::cli::builder::build(config_dir, false, &BuildOptions::default())?;
     }
 
     info!("\n Ambiente pronto! Abrindo shell...\n");
 
-    runtime.run_shell(with_dbs, auto_stop)
+    runtime.run_shell(with_dbs, auto_stop, plain)
 }
 
 pub fn up(config_dir: &Path, dbs_only: bool, services_only: bool) -> Result<()> {
@@ -348,9 +539,7 @@ pub fn up(config_dir: &Path, dbs_only: bool, services_only: bool) -> Result<()>
 
 pub fn down(config_dir: &Path) -> Result<()> {
     let runtime = Runtime::new(config_dir)?;
-    for name in runtime.all_containers() {
-        runtime.container_service.stop(&name)?;
-    }
+    runtime.stop_all_containers()?;
     info!(" Tudo parado");
     Ok(())
 }
@@ -368,7 +557,7 @@ pub fn smart_start(
     let runtime = Runtime::new(config_dir)?;
 
     if let Some(name) = service {
-        if runtime.is_known_svc(name) {
+        if runtime.find_svc(name)?.is_some() {
             runtime.start_svc(name)
         } else {
             // Check if it matches the main container name
@@ -404,7 +593,7 @@ pub fn smart_stop(
     let runtime = Runtime::new(config_dir)?;
 
     if let Some(name) = service {
-        if runtime.is_known_svc(name) {
+        if runtime.find_svc(name)?.is_some() {
             runtime.stop_svc(name)
         } else {
             let main_name = runtime
@@ -435,7 +624,7 @@ pub fn smart_restart(
     let runtime = Runtime::new(config_dir)?;
 
     if let Some(name) = service {
-        if runtime.is_known_svc(name) {
+        if runtime.find_svc(name)?.is_some() {
             runtime.restart_svc(name)
         } else {
             let main_name = runtime
@@ -463,7 +652,6 @@ pub fn smart_restart(
     }
 }
 
-#[allow(dead_code)]
 pub fn exec_cmd(config_dir: &Path, command: Vec<String>) -> Result<()> {
     let runtime = Runtime::new(config_dir)?;
 
@@ -478,25 +666,31 @@ pub fn exec_cmd(config_dir: &Path, command: Vec<String>) -> Result<()> {
         .context("Main container name not set in config")?;
 
     let workdir_in_container = container_workdir()?;
+    let project = nearest_project(&env::current_dir()?)?;
+    let env = match &project {
+        Some(project) => project_env(project)?,
+        None => Vec::new(),
+    };
+
+    // Through the project's shell, so its rc files (mise, PATH) apply
+    let command = match project.as_ref().and_then(|p| p.shell()) {
+        Some(shell) => {
+            runtime.ensure_shell(shell)?;
+            in_shell(shell, &command)
+        }
+        None => command,
+    };
+    let command: Vec<&str> = command.iter().map(String::as_str).collect();
 
-    // Construct the podman exec command
-    let mut args = vec!["exec".to_string(), "-it".to_string()];
-    if let Some(wd) = workdir_in_container {
-        args.push("-w".to_string());
-        args.push(wd.to_string_lossy().to_string());
-    }
-    args.push(main_container_name.to_string());
-    args.extend(command);
-
-    let status = std::process::Command::new("podman")
-        .args(&args)
-        .status()
-        .context("Falha ao executar comando via podman exec")?;
-
-    if !status.success() {
-        bail!("Comando falhou com status: {:?}", status);
-    }
-    Ok(())
+    runtime
+        .container_service
+        .exec_command(
+            main_container_name,
+            workdir_in_container.as_deref(),
+            &env,
+            &command,
+        )
+        .context("Falha ao executar comando no container")
 }
 
 pub fn cleanup(config_dir: &Path, options: &CleanupOptions) -> Result<()> {
@@ -505,11 +699,13 @@ pub fn cleanup(config_dir: &Path, options: &CleanupOptions) -> Result<()> {
 }
 
 pub fn nuke(config_dir: &Path) -> Result<()> {
+    refuse_from_container("--nuke")?;
     let runtime = Runtime::new(config_dir)?;
     runtime.nuke()
 }
 
 pub fn reset(config_dir: &Path) -> Result<()> {
+    refuse_from_container("--reset")?;
     warn!(" System reset irá DELETAR TUDO do Podman!");
     warn!("   Esta ação é IRREVERSÍVEL!");
     info!("");
@@ -531,13 +727,29 @@ pub fn reset(config_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Forwarded from the dev container, `cleanup --nuke`/`--reset` would remove
+/// the container running them too
+fn refuse_from_container(flag: &str) -> Result<()> {
+    if RuntimeContext::detect().is_container() {
+        bail!(
+            "'devobox cleanup {flag}' remove também o container de desenvolvimento. Rode-o no host."
+        );
+    }
+    Ok(())
+}
+
 /// Lists all available projects
-pub fn project_list(_config_dir: &Path) -> Result<()> {
-    let discovery = ProjectDiscovery::new(None)?;
+pub fn project_list(config_dir: &Path) -> Result<()> {
+    let discovery = ProjectDiscovery::from_config(config_dir)?;
     let projects = discovery.discover_all()?;
 
     if projects.is_empty() {
-        info!(" Nenhum projeto encontrado em ~/code");
+        let roots: Vec<String> = discovery
+            .roots()
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect();
+        info!(" Nenhum projeto encontrado em {}", roots.join(", "));
         info!(" Dica: Crie um diretório com devobox.toml para começar");
         info!("");
         info!(" Exemplo:");
@@ -556,121 +768,115 @@ pub fn project_list(_config_dir: &Path) -> Result<()> {
         } else {
             ""
         };
-        info!("  - {}{}", project.name, services_info);
+        let location = discovery
+            .roots()
+            .iter()
+            .find_map(|root| project.path.strip_prefix(root).ok())
+            .filter(|relative| relative.as_os_str() != project.name.as_str())
+            .map(|relative| format!(" [{}]", relative.display()))
+            .unwrap_or_default();
+        info!("  - {}{}{}", project.name, location, services_info);
     }
 
     Ok(())
 }
 
-/// Activates a project workspace (container context only)
-pub fn project_up(config_dir: &Path, project_name: &str) -> Result<()> {
+/// Activates a project workspace. From the host, starts the dev container and
+/// the services, then opens the session inside the container with
+/// `session_only` so the forwarded run doesn't start them a second time
+pub fn project_up(config_dir: &Path, project_name: &str, session_only: bool) -> Result<()> {
     let context = RuntimeContext::detect();
 
-    if context.is_host() {
-        bail!(
-            "'devobox project up' só funciona dentro do container.\nUse 'devobox' ou 'devobox shell' primeiro."
-        );
-    }
-
     // 1. Find project
-    let discovery = ProjectDiscovery::new(None)?;
+    let discovery = ProjectDiscovery::from_config(config_dir)?;
     let project = discovery
         .find_project(project_name)?
-        .with_context(|| format!("Projeto '{}' não encontrado em ~/code", project_name))?;
+        .with_context(|| format!("Projeto '{}' não encontrado", project_name))?;
 
     info!(" Ativando projeto: {}", project.name);
 
-    // 2. Load and start project-specific services
-    let services = resolve_project_services(&project, config_dir)?;
+    // 2. Load and start project-specific services, unless the host already did
+    let services: Vec<_> = if session_only {
+        Vec::new()
+    } else {
+        ServiceResolver::new(config_dir)?
+            .resolve_project(&project)?
+            .into_iter()
+            .map(|resolved| resolved.service)
+            .collect()
+    };
+
+    if context.is_host() {
+        let runtime = Runtime::for_dir(config_dir, &project.path)?;
+        runtime.ensure_dev_container()?;
+        if !services.is_empty() {
+            info!(" Iniciando {} serviço(s)...", services.len());
+            runtime.start_project_services(&services);
+        }
+
+        // The session lives in the container
+        return runtime.exec_project_up(&project, project_name, true);
+    }
 
     if !services.is_empty() {
         info!(" Iniciando {} serviço(s)...", services.len());
 
         // Create Runtime to access orchestrator
         let runtime = Runtime::new(config_dir)?;
-
-        // Ensure all services are created first
-        for svc in &services {
-            if let Err(e) = runtime.ensure_svc_created(svc) {
-                warn!("  Aviso ao criar serviço {}: {}", svc.name, e);
-            }
-        }
-
-        // Start all services
-        if let Err(e) = runtime.orchestrator.start_all(&services) {
-            warn!("  Erro ao iniciar serviços: {}", e);
-            warn!("  Continuando mesmo assim...");
-        } else {
-            info!(" Serviços iniciados com sucesso!");
-        }
+        runtime.start_project_services(&services);
     }
 
-    // 3. Log environment variables (can't actually set them for parent shell)
-    if !project.env_vars().is_empty() {
-        info!(" Variáveis de ambiente do projeto:");
-        for env_var in project.env_vars() {
-            info!("   {}", env_var);
-        }
+    // 3. Environment for the session and each tab (env_file + [project] env)
+    let env = project_env(&project)?;
+    if !env.is_empty() {
+        info!(
+            " {} variável(is) de ambiente aplicada(s) à sessão",
+            env.len()
+        );
     }
 
-    // 4. Gather dependent projects info for layout
-    let mut dependencies_info =
-        Vec::with_capacity(project.config.dependencies.include_projects.len());
-    if !project.config.dependencies.include_projects.is_empty() {
-        for relative_path in &project.config.dependencies.include_projects {
-            let dep_path = project.path.join(relative_path);
-            let canonical_path = match std::fs::canonicalize(&dep_path) {
-                Ok(p) => p,
-                Err(_) => {
-                    warn!("  Caminho de dependência inválido: {:?}", dep_path);
-                    continue;
-                }
-            };
-
-            // Try to load project config to get startup_command
-            let config_path = canonical_path.join("devobox.toml");
-            let startup_command = if config_path.exists() {
-                match std::fs::read_to_string(&config_path) {
-                    Ok(content) => match toml::from_str::<crate::domain::ProjectConfig>(&content) {
-                        Ok(cfg) => cfg.project.and_then(|p| p.startup_command),
-                        Err(_) => None,
-                    },
-                    Err(_) => None,
-                }
-            } else {
-                None
-            };
+    // 4. Gather dependent projects info for layout (transitively, each once)
+    let graph = ProjectGraph::build(project.clone(), &project.path)?;
+    let dependencies_info = graph
+        .dependencies
+        .iter()
+        .map(|node| {
+            Ok(crate::services::ProjectLayoutInfo {
+                name: node.project.name.clone(),
+                path: node.project.path.clone(),
+                startup_command: node.project.startup_command().map(String::from),
+                env: project_env(&node.project)?,
+                shell: node.project.shell().map(String::from),
+                layout: node.project.layout().cloned(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-            let name = canonical_path
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string();
-
-            dependencies_info.push(crate::services::ProjectLayoutInfo {
-                name,
-                path: canonical_path,
-                startup_command,
-            });
-        }
+    if let Some(shell) = project.shell()
+        && !is_installed(shell)
+    {
+        bail!(missing_shell(shell));
     }
 
-    // 5. Create/attach Zellij session
-    let zellij = ZellijService::new();
+    // 5. Create/attach the multiplexer session
+    let multiplexer = multiplexer(&load_app_config(config_dir)?, project.shell());
     let session_name = project.session_name();
 
-    info!(" Abrindo sessão Zellij: {}", session_name);
+    info!(" Abrindo sessão {}: {}", multiplexer.name(), session_name);
     info!(" Diretório de trabalho: {}", project.path.display());
     if !dependencies_info.is_empty() {
         info!(" Projetos incluídos no layout: {}", dependencies_info.len());
     }
 
-    zellij.create_with_layout(
+    multiplexer.create_with_layout(
         &session_name,
         &crate::services::ProjectLayoutInfo {
             name: project.name.clone(),
             path: project.path.clone(),
             startup_command: project.startup_command().map(String::from),
+            env,
+            shell: project.shell().map(String::from),
+            layout: project.layout().cloned(),
         },
         &dependencies_info,
     )?;
@@ -679,7 +885,7 @@ pub fn project_up(config_dir: &Path, project_name: &str) -> Result<()> {
 }
 
 /// Shows current project info
-pub fn project_info() -> Result<()> {
+pub fn project_info(config_dir: &Path) -> Result<()> {
     let context = RuntimeContext::detect();
 
     info!(" Contexto: {}", context);
@@ -709,43 +915,95 @@ pub fn project_info() -> Result<()> {
 
     info!(" Diretório: {}", pwd.display());
 
-    // Show active Zellij sessions
-    let zellij = ZellijService::new();
-    if zellij.is_available() {
-        match zellij.list_sessions() {
+    // Show active multiplexer sessions
+    let app_config = load_app_config(config_dir)?;
+    if app_config.container.multiplexer == Some(MultiplexerKind::None) {
+        return Ok(());
+    }
+    let multiplexer = multiplexer(&app_config, None);
+    let name = multiplexer.name();
+    if multiplexer.is_available() {
+        match multiplexer.list_sessions() {
             Ok(sessions) if !sessions.is_empty() => {
                 info!("");
-                info!(" Sessões Zellij ativas:");
+                info!(" Sessões {} ativas:", name);
                 for session in sessions {
-                    info!("   - {}", session);
+                    info!("   - {}", session.name);
                 }
             }
             Ok(_) => {
                 info!("");
-                info!(" Nenhuma sessão Zellij ativa");
+                info!(" Nenhuma sessão {} ativa", name);
             }
             Err(e) => {
-                warn!("  Erro ao listar sessões Zellij: {}", e);
+                warn!("  Erro ao listar sessões {}: {}", name, e);
             }
         }
     } else {
         info!("");
-        info!(" Zellij não está instalado");
-        info!(" Instale com: mise install zellij");
+        info!(" {} não está instalado", name);
+        info!(" Instale com: mise install {}", name.to_lowercase());
     }
 
     Ok(())
 }
 
+/// Picks the multiplexer backend from `[container] multiplexer` (default:
+/// Zellij). `shell` is the project's, for sessions without a layout
+pub(crate) fn multiplexer(app_config: &AppConfig, shell: Option<&str>) -> Box<dyn Multiplexer> {
+    match app_config.container.multiplexer.unwrap_or_default() {
+        MultiplexerKind::Zellij => Box::new(ZellijService::with_shell(shell)),
+        MultiplexerKind::Tmux => Box::new(TmuxService::with_shell(shell)),
+        MultiplexerKind::None => Box::new(NoMultiplexer::with_shell(shell)),
+    }
+}
+
+/// `command` run by `shell` as a login shell
+fn in_shell(shell: &str, command: &[String]) -> Vec<String> {
+    let quote = if shell == "fish" {
+        fish_quote
+    } else {
+        sh_quote
+    };
+    let words: Vec<String> = command.iter().map(|word| quote(word)).collect();
+    vec![
+        shell.to_string(),
+        "-l".to_string(),
+        "-c".to_string(),
+        format!("exec {}", words.join(" ")),
+    ]
+}
+
+fn is_installed(program: &str) -> bool {
+    std::process::Command::new("sh")
+        .args(["-c", "command -v \"$1\" >/dev/null", "sh", program])
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+fn missing_shell(shell: &str) -> String {
+    format!(
+        "O shell '{shell}' do projeto não está instalado na imagem do devobox.\n\
+         Adicione ao Containerfile:\n    \
+         RUN apt-get update && apt-get install -y {shell}\n\
+         (ou instale pelo mise.toml) e rode 'devobox build'.\n\
+         Para usar o shell padrão, remova 'shell' de [project] no devobox.toml."
+    )
+}
+
 fn container_workdir() -> Result<Option<PathBuf>> {
-    let pwd = std::env::current_dir()?;
+    Ok(Some(container_path(&std::env::current_dir()?)))
+}
+
+/// Where a host directory is mounted in the dev container: ~/code maps to
+/// /home/dev/code, anything else to the home directory
+fn container_path(host_path: &Path) -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_default();
-    let home_path = PathBuf::from(&home);
-    let code_dir = home_path.join("code");
+    let code_dir = PathBuf::from(&home).join("code");
 
-    if let Ok(stripped) = pwd.strip_prefix(&code_dir) {
-        return Ok(Some(PathBuf::from("/home/dev/code").join(stripped)));
+    match host_path.strip_prefix(&code_dir) {
+        Ok(stripped) => PathBuf::from("/home/dev/code").join(stripped),
+        Err(_) => PathBuf::from("/home/dev"),
     }
-
-    Ok(Some(PathBuf::from("/home/dev")))
 }
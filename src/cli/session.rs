@@ -0,0 +1,313 @@
+use crate::cli::RuntimeContext;
+use crate::cli::runtime::Runtime;
+use crate::domain::{ContainerState, Project};
+use crate::infra::ProjectDiscovery;
+use crate::infra::engine::is_dry_run;
+use crate::services::{Multiplexer, SessionInfo};
+use anyhow::{Result, bail};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// Sessions opened by `devobox shell` outside any project
+const SHELL_SESSIONS: &[&str] = &["devobox-default", "devobox-code-root"];
+
+/// What a session was opened for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionOwner {
+    /// `devobox project up <name>`
+    Project(String),
+    /// `devobox shell` in a directory of ~/code without devobox.toml
+    Directory(PathBuf),
+    /// `devobox shell` outside any project
+    Shell,
+    /// Named after a project that no longer exists
+    Missing,
+    /// Not created by devobox
+    Other,
+}
+
+impl SessionOwner {
+    /// Classifies `session` against the known projects and ~/code
+    pub fn of(session: &str, projects: &[Project], code_dir: &Path) -> Self {
+        if SHELL_SESSIONS.contains(&session) {
+            return Self::Shell;
+        }
+        if let Some(project) = projects.iter().find(|p| p.session_name() == session) {
+            return Self::Project(project.name.clone());
+        }
+        match session.strip_prefix("devobox-") {
+            Some(dir) if !dir.is_empty() && code_dir.join(dir).is_dir() => {
+                Self::Directory(code_dir.join(dir))
+            }
+            Some(_) => Self::Missing,
+            None => Self::Other,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Project(name) => name.clone(),
+            Self::Directory(dir) => dir.display().to_string(),
+            Self::Shell => "(shell)".to_string(),
+            Self::Missing => "(projeto removido)".to_string(),
+            Self::Other => "-".to_string(),
+        }
+    }
+}
+
+/// Sessions of the dev container's multiplexer: run directly inside the
+/// container, through `podman exec` from the host
+pub struct SessionManager<'a> {
+    runtime: &'a Runtime,
+    multiplexer: Box<dyn Multiplexer>,
+    context: RuntimeContext,
+}
+
+impl<'a> SessionManager<'a> {
+    pub fn new(runtime: &'a Runtime, context: RuntimeContext) -> Result<Self> {
+        let multiplexer = runtime.multiplexer();
+        if multiplexer.list_command().is_empty() {
+            bail!(
+                "Sem multiplexador configurado ([container] multiplexer = \"none\"): não há sessões"
+            );
+        }
+        Ok(Self {
+            runtime,
+            multiplexer,
+            context,
+        })
+    }
+
+    pub fn list(&self) -> Result<Vec<SessionInfo>> {
+        if self.context.is_container() {
+            return self.multiplexer.list_sessions();
+        }
+
+        // A stopped container has no sessions to report
+        let container = self.runtime.main_container_name()?;
+        if self.runtime.container_service.get_status(container)?.state != ContainerState::Running {
+            return Ok(Vec::new());
+        }
+
+        let command = self.multiplexer.list_command();
+        let command: Vec<&str> = command.iter().map(String::as_str).collect();
+        let output = self
+            .runtime
+            .container_service
+            .exec_output(container, &command)?;
+        Ok(self.multiplexer.parse_sessions(&output))
+    }
+
+    /// The session named `name`, or the one of project `name`
+    pub fn find(&self, name: &str) -> Result<SessionInfo> {
+        let sessions = self.list()?;
+        let project_session = format!("devobox-{name}");
+        sessions
+            .iter()
+            .find(|session| session.name == name)
+            .or_else(|| sessions.iter().find(|s| s.name == project_session))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Sessão '{}' não encontrada. Veja 'devobox session list'",
+                    name
+                )
+            })
+    }
+
+    pub fn attach(&self, session: &str) -> Result<()> {
+        if self.context.is_container() {
+            return self
+                .multiplexer
+                .attach_or_create(session, &std::env::current_dir()?);
+        }
+
+        let container = self.runtime.main_container_name()?;
+        self.runtime.container_service.exec_shell(
+            container,
+            None,
+            &[],
+            self.multiplexer.as_ref(),
+            Some(session),
+        )
+    }
+
+    pub fn kill(&self, session: &str) -> Result<()> {
+        if self.context.is_container() {
+            if is_dry_run() {
+                println!(
+                    "[dry-run] {}",
+                    self.multiplexer.kill_command(session).join(" ")
+                );
+                return Ok(());
+            }
+            return self.multiplexer.kill_session(session);
+        }
+
+        // Dry runs plan the exec instead of running it
+        let command = self.multiplexer.kill_command(session);
+        let command: Vec<&str> = command.iter().map(String::as_str).collect();
+        self.runtime.container_service.exec_command(
+            self.runtime.main_container_name()?,
+            None,
+            &[],
+            &command,
+        )
+    }
+}
+
+/// `1d 2h`, `3h 5m`, `12m`, `40s`
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, m) => format!("{m}m"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, h, _) => format!("{d}d {h}h"),
+    }
+}
+
+fn projects(config_dir: &Path) -> Result<(Vec<Project>, PathBuf)> {
+    let projects = ProjectDiscovery::from_config(config_dir)?.discover_all()?;
+    let home = std::env::var("HOME").unwrap_or_default();
+    Ok((projects, PathBuf::from(home).join("code")))
+}
+
+/// Lists the sessions with their project and age
+pub fn session_list(config_dir: &Path) -> Result<()> {
+    let runtime = Runtime::new(config_dir)?;
+    let manager = SessionManager::new(&runtime, RuntimeContext::detect())?;
+    let sessions = manager.list()?;
+
+    if sessions.is_empty() {
+        info!(" Nenhuma sessão {} ativa", manager.multiplexer.name());
+        return Ok(());
+    }
+
+    let (projects, code_dir) = projects(config_dir)?;
+    println!(" Sessões {}:", manager.multiplexer.name());
+    for session in sessions {
+        let owner = SessionOwner::of(&session.name, &projects, &code_dir);
+        let age = session
+            .age
+            .map(format_age)
+            .unwrap_or_else(|| "-".to_string());
+        let exited = if session.exited { " (encerrada)" } else { "" };
+        println!(
+            "- {:<24} | {:<20} | {}{}",
+            session.name,
+            owner.label(),
+            age,
+            exited
+        );
+    }
+
+    Ok(())
+}
+
+/// Attaches to a session by its name or its project's
+pub fn session_attach(config_dir: &Path, name: &str) -> Result<()> {
+    let runtime = Runtime::new(config_dir)?;
+    let manager = SessionManager::new(&runtime, RuntimeContext::detect())?;
+    let session = manager.find(name)?;
+
+    info!(" Anexando à sessão {}", session.name);
+    manager.attach(&session.name)
+}
+
+pub fn session_kill(config_dir: &Path, name: &str) -> Result<()> {
+    let runtime = Runtime::new(config_dir)?;
+    let manager = SessionManager::new(&runtime, RuntimeContext::detect())?;
+    let session = manager.find(name)?;
+
+    manager.kill(&session.name)?;
+    info!(" Sessão {} encerrada", session.name);
+    Ok(())
+}
+
+/// Kills devobox sessions whose project no longer exists
+pub fn session_prune(config_dir: &Path, yes: bool) -> Result<()> {
+    let runtime = Runtime::new(config_dir)?;
+    let manager = SessionManager::new(&runtime, RuntimeContext::detect())?;
+    let (projects, code_dir) = projects(config_dir)?;
+
+    let orphans: Vec<SessionInfo> = manager
+        .list()?
+        .into_iter()
+        .filter(|s| SessionOwner::of(&s.name, &projects, &code_dir) == SessionOwner::Missing)
+        .collect();
+
+    if orphans.is_empty() {
+        info!(" Nenhuma sessão órfã");
+        return Ok(());
+    }
+
+    info!(" Sessões sem projeto:");
+    for session in &orphans {
+        info!("   - {}", session.name);
+    }
+
+    if !yes && !is_dry_run() {
+        print!("Encerrar {} sessão(ões)? [s/N] ", orphans.len());
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(
+            answer.trim().to_lowercase().as_str(),
+            "s" | "sim" | "y" | "yes"
+        ) {
+            info!(" Nada foi encerrado.");
+            return Ok(());
+        }
+    }
+
+    for session in &orphans {
+        manager.kill(&session.name)?;
+    }
+    info!(" {} sessão(ões) encerrada(s)", orphans.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProjectConfig;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(40)), "40s");
+        assert_eq!(format_age(Duration::from_secs(12 * 60 + 5)), "12m");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600 + 5 * 60)), "3h 5m");
+        assert_eq!(
+            format_age(Duration::from_secs(86_400 + 2 * 3600 + 59)),
+            "1d 2h"
+        );
+    }
+
+    #[test]
+    fn test_session_owner() {
+        let code = tempfile::tempdir().unwrap();
+        std::fs::create_dir(code.path().join("scratch")).unwrap();
+        let projects = vec![Project::new(
+            code.path().join("app"),
+            ProjectConfig::default(),
+        )];
+
+        let owner = |session| SessionOwner::of(session, &projects, code.path());
+        assert_eq!(
+            owner("devobox-app"),
+            SessionOwner::Project("app".to_string())
+        );
+        // Exact names only: app2 is not app's session
+        assert_eq!(owner("devobox-app2"), SessionOwner::Missing);
+        assert_eq!(
+            owner("devobox-scratch"),
+            SessionOwner::Directory(code.path().join("scratch"))
+        );
+        assert_eq!(owner("devobox-default"), SessionOwner::Shell);
+        assert_eq!(owner("work"), SessionOwner::Other);
+    }
+}
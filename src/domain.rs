@@ -1,7 +1,16 @@
 mod container;
+pub mod layout;
 pub mod project;
+mod service_config;
 pub mod traits;
 
-pub use container::{Container, ContainerSpec, ContainerState, Service, ServiceKind};
+pub use container::{
+    BuildOptions, Container, ContainerFilter, ContainerInfo, ContainerSpec, ContainerState,
+    DEV_IMAGE_LABEL, ImageInfo, PROJECT_LABEL, Service, ServiceKind, parse_duration,
+};
+pub use layout::{LayoutConfig, LayoutPane, LayoutTab, SplitDirection};
 pub use project::{Project, ProjectConfig, ProjectDependencies, ProjectSettings};
+pub use service_config::{
+    ListPatch, ServiceConfig, merge_service_configs, resolve_service_configs,
+};
 pub use traits::ContainerRuntime;
@@ -1,4 +1,16 @@
-use serde::Deserialize;
+use super::traits::ContainerHealthStatus;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Label naming the project a service container belongs to
+pub const PROJECT_LABEL: &str = "devobox.project";
+
+/// Label on dev images built by devobox: `image prune` leaves them alone and
+/// the `[build] keep_images` retention decides which versions go
+pub const DEV_IMAGE_LABEL: &str = "devobox.dev-image";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ContainerState {
@@ -9,12 +21,98 @@ pub enum ContainerState {
 
 #[derive(Debug, Clone)]
 pub struct Container {
+    pub name: String,
     pub state: ContainerState,
 }
 
 impl Container {
-    pub fn new(_name: String, state: ContainerState) -> Self {
-        Self { state }
+    pub fn new(name: String, state: ContainerState) -> Self {
+        Self { name, state }
+    }
+}
+
+/// Full record of a container as reported by a bulk listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub state: ContainerState,
+    pub health: ContainerHealthStatus,
+    /// Published ports as `host:container/proto`
+    pub ports: Vec<String>,
+    pub labels: BTreeMap<String, String>,
+    /// Creation time in seconds since the Unix epoch
+    pub created: Option<i64>,
+    /// Exit code of the last run, for stopped containers
+    pub exit_code: Option<i32>,
+}
+
+/// A tag of a local image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    /// Repository without the `localhost/` prefix podman adds
+    pub repository: String,
+    pub tag: String,
+    pub id: String,
+}
+
+impl ImageInfo {
+    /// `repository:tag`
+    pub fn reference(&self) -> String {
+        format!("{}:{}", self.repository, self.tag)
+    }
+}
+
+/// How an image is built, beyond its tag, Containerfile and context
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildOptions {
+    /// `KEY=value` build arguments, later ones winning
+    pub args: Vec<String>,
+    /// Stage of a multi-stage Containerfile
+    pub target: Option<String>,
+    /// Secret id and the file exposed under it
+    pub secrets: Vec<(String, PathBuf)>,
+    /// e.g. `linux/arm64`
+    pub platform: Option<String>,
+    /// Rebuild every layer
+    pub no_cache: bool,
+    /// Pull newer base images
+    pub pull: bool,
+}
+
+/// Selects which containers a listing returns; an empty filter matches everything
+#[derive(Debug, Clone, Default)]
+pub struct ContainerFilter {
+    /// Exact container names
+    pub names: Vec<String>,
+    /// `key` or `key=value` label selectors, all of which must match
+    pub labels: Vec<String>,
+}
+
+impl ContainerFilter {
+    pub fn names<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            names: names.into_iter().map(Into::into).collect(),
+            labels: Vec::new(),
+        }
+    }
+
+    pub fn matches(&self, info: &ContainerInfo) -> bool {
+        let name_ok = self.names.is_empty() || self.names.contains(&info.name);
+        let labels_ok = self
+            .labels
+            .iter()
+            .all(|selector| match selector.split_once('=') {
+                Some((key, value)) => info.labels.get(key).is_some_and(|v| v == value),
+                None => info.labels.contains_key(selector),
+            });
+
+        name_ok && labels_ok
     }
 }
 
@@ -34,36 +132,69 @@ pub struct ContainerSpec<'a> {
     pub healthcheck_interval: Option<&'a str>,
     pub healthcheck_timeout: Option<&'a str>,
     pub healthcheck_retries: Option<u32>,
+    /// Project owning the container, set as the `devobox.project` label
+    pub project: Option<&'a str>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Default)]
+/// How a service is grouped by the CLI
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ServiceKind {
+    /// Controlled by `devobox service`
     #[default]
     Generic,
+    /// Controlled by `devobox db`
     Database,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+/// A support container, resolved from the `[services.NAME]` tables of every config layer
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Service {
-    #[serde(default)]
+    /// Taken from the table key, never written in the file
+    #[serde(default, skip_serializing)]
     pub name: String,
+    /// Container image, e.g. "postgres:16"
     pub image: String,
     #[serde(default, rename = "type")]
     pub kind: ServiceKind,
+    /// Published ports, e.g. "5432:5432" or "8080/udp"
     #[serde(default)]
     pub ports: Vec<String>,
+    /// Environment variables as KEY=VALUE
     #[serde(default)]
     pub env: Vec<String>,
+    /// Volume mounts, e.g. "pgdata:/var/lib/postgresql/data"
     #[serde(default)]
     pub volumes: Vec<String>,
+    /// Command run inside the container to check its health
     pub healthcheck_command: Option<String>,
-    pub healthcheck_interval: Option<String>, // e.g., "5s"
-    pub healthcheck_timeout: Option<String>,  // e.g., "3s"
+    /// Interval between health checks, e.g. "5s"
+    pub healthcheck_interval: Option<String>,
+    /// Timeout for each health check, e.g. "3s"
+    pub healthcheck_timeout: Option<String>,
+    /// Failed checks before the container is unhealthy
     pub healthcheck_retries: Option<u32>,
+    /// Project the container is namespaced under; `name` is then `<project>-<service>`
+    #[serde(skip)]
+    pub project: Option<String>,
 }
 
 impl Service {
+    /// The name as written in `[services.NAME]`, without the project prefix
+    pub fn short_name(&self) -> &str {
+        self.project
+            .as_deref()
+            .and_then(|project| self.name.strip_prefix(project))
+            .and_then(|rest| rest.strip_prefix('-'))
+            .unwrap_or(&self.name)
+    }
+
+    /// Qualifies the container name with `project` and labels it
+    pub fn namespace(&mut self, project: &str) {
+        self.name = format!("{}-{}", project, self.name);
+        self.project = Some(project.to_string());
+    }
+
     pub fn to_spec(&self) -> ContainerSpec<'_> {
         ContainerSpec {
             name: &self.name,
@@ -80,12 +211,72 @@ impl Service {
             healthcheck_interval: self.healthcheck_interval.as_deref(),
             healthcheck_timeout: self.healthcheck_timeout.as_deref(),
             healthcheck_retries: self.healthcheck_retries,
+            project: self.project.as_deref(),
         }
     }
+}
+
+/// Parses healthcheck durations like `500ms`, `5s` or `2m`
+pub fn parse_duration(s: &str) -> anyhow::Result<Duration> {
+    let s = s.trim();
+    if let Some(stripped) = s.strip_suffix("ms") {
+        let millis: u64 = stripped.parse()?;
+        Ok(Duration::from_millis(millis))
+    } else if let Some(stripped) = s.strip_suffix('s') {
+        let secs: u64 = stripped.parse()?;
+        Ok(Duration::from_secs(secs))
+    } else if let Some(stripped) = s.strip_suffix('m') {
+        let mins: u64 = stripped.parse()?;
+        Ok(Duration::from_secs(mins * 60))
+    } else {
+        Err(anyhow::anyhow!("Formato de duração inválido: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, labels: &[(&str, &str)]) -> ContainerInfo {
+        ContainerInfo {
+            id: String::new(),
+            name: name.to_string(),
+            image: String::new(),
+            state: ContainerState::Running,
+            health: ContainerHealthStatus::NotApplicable,
+            ports: Vec::new(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            created: None,
+            exit_code: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_matches_names_and_labels() {
+        let pg = info("pg", &[("devobox.project", "api")]);
+
+        assert!(ContainerFilter::default().matches(&pg));
+        assert!(ContainerFilter::names(["redis", "pg"]).matches(&pg));
+        assert!(!ContainerFilter::names(["p"]).matches(&pg));
+
+        let by_label = |labels: &[&str]| ContainerFilter {
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(by_label(&["devobox.project"]).matches(&pg));
+        assert!(by_label(&["devobox.project=api"]).matches(&pg));
+        assert!(!by_label(&["devobox.project=web"]).matches(&pg));
+    }
 
-    /// Create a Service from TOML HashMap entry (name comes from key)
-    pub fn with_name(mut self, name: String) -> Self {
-        self.name = name;
-        self
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration(" 5s").unwrap(), Duration::from_secs(5));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        assert!(parse_duration("5").is_err());
+        assert!(parse_duration("fives").is_err());
     }
 }
@@ -0,0 +1,93 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// `[layout]`: the tabs `devobox project up` opens for a project
+///
+/// Either describe the tabs inline (`[[layout.tabs]]`) or point `template` at a
+/// KDL file with the project's `tab { ... }` nodes.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+pub struct LayoutConfig {
+    /// KDL file with the project's tabs, relative to the project directory.
+    /// `{{project_name}}`, `{{project_dir}}`, `{{startup_command}}`, `{{shell}}`
+    /// and `{{env}}` (the project env as KDL strings) are replaced
+    #[serde(default)]
+    pub template: Option<PathBuf>,
+
+    /// Tabs, in order
+    #[serde(default)]
+    pub tabs: Vec<LayoutTab>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+pub struct LayoutTab {
+    /// Tab title (default: project name)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Select this tab when the session opens
+    #[serde(default)]
+    pub focus: bool,
+
+    /// How the tab's panes are laid out
+    #[serde(default)]
+    pub split: Option<SplitDirection>,
+
+    /// Working directory for the tab's panes, relative to the project
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+
+    /// Panes (default: one shell in the project directory)
+    #[serde(default)]
+    pub panes: Vec<LayoutPane>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+pub struct LayoutPane {
+    /// Pane title
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Command to run (default: a shell); quoted arguments are kept together
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Working directory, relative to the tab's
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+
+    /// Size within the parent split, e.g. "60%" or "10" (lines/columns)
+    #[serde(default)]
+    pub size: Option<String>,
+
+    /// Put the cursor in this pane
+    #[serde(default)]
+    pub focus: bool,
+
+    /// How nested `panes` are laid out
+    #[serde(default)]
+    pub split: Option<SplitDirection>,
+
+    /// Nested panes; a pane with children runs no command itself
+    #[serde(default)]
+    pub panes: Vec<LayoutPane>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    /// Side by side
+    Vertical,
+    /// Stacked
+    Horizontal,
+}
+
+impl SplitDirection {
+    /// Value of Zellij's `split_direction`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Vertical => "vertical",
+            Self::Horizontal => "horizontal",
+        }
+    }
+}
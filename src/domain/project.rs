@@ -1,8 +1,9 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use super::Service;
+use super::{LayoutConfig, ServiceConfig};
 
 /// Represents a logical project workspace (NOT a container)
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -16,7 +17,7 @@ pub struct Project {
 }
 
 /// Configuration loaded from project's devobox.toml
-#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 pub struct ProjectConfig {
     /// Project-specific settings
     #[serde(default)]
@@ -28,11 +29,15 @@ pub struct ProjectConfig {
 
     /// Project-specific services
     #[serde(default)]
-    pub services: Option<HashMap<String, Service>>,
+    pub services: Option<HashMap<String, ServiceConfig>>,
+
+    /// Tabs and panes opened by `devobox project up`
+    #[serde(default)]
+    pub layout: Option<LayoutConfig>,
 }
 
 /// Project-specific settings
-#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 pub struct ProjectSettings {
     /// Explicit project name (overrides directory name)
     #[serde(default)]
@@ -42,6 +47,10 @@ pub struct ProjectSettings {
     #[serde(default)]
     pub env: Vec<String>,
 
+    /// Dotenv file loaded before `env` (relative to the project directory)
+    #[serde(default)]
+    pub env_file: Option<PathBuf>,
+
     /// Shell to use (bash, zsh, fish)
     #[serde(default)]
     pub shell: Option<String>,
@@ -51,7 +60,7 @@ pub struct ProjectSettings {
     pub startup_command: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 pub struct ProjectDependencies {
     /// Other projects to include services from
     #[serde(default)]
@@ -81,6 +90,27 @@ impl Project {
         format!("devobox-{}", self.name)
     }
 
+    /// The name as container and image names accept it: lowercase ASCII
+    /// letters, digits, `_`, `.` and `-`, starting with a letter or digit
+    pub fn slug(&self) -> String {
+        let mut slug = String::new();
+        for c in self.name.chars() {
+            match c {
+                c if c.is_ascii_alphanumeric() => slug.push(c.to_ascii_lowercase()),
+                '_' | '.' | '-' if !slug.is_empty() => slug.push(c),
+                _ if !slug.is_empty() && !slug.ends_with('-') => slug.push('-'),
+                _ => {}
+            }
+        }
+        let slug = slug.trim_end_matches(['-', '_', '.']);
+
+        if slug.is_empty() {
+            "project".to_string()
+        } else {
+            slug.to_string()
+        }
+    }
+
     /// Get environment variables for this project
     pub fn env_vars(&self) -> &[String] {
         self.config
@@ -90,6 +120,15 @@ impl Project {
             .unwrap_or(&[])
     }
 
+    /// Dotenv file declared by the project, resolved against its directory
+    pub fn env_file(&self) -> Option<PathBuf> {
+        self.config
+            .project
+            .as_ref()
+            .and_then(|p| p.env_file.as_ref())
+            .map(|file| self.path.join(file))
+    }
+
     /// Get preferred shell for this project
     pub fn shell(&self) -> Option<&str> {
         self.config
@@ -98,6 +137,11 @@ impl Project {
             .and_then(|p| p.shell.as_deref())
     }
 
+    /// Custom Zellij layout, if the project declares one
+    pub fn layout(&self) -> Option<&LayoutConfig> {
+        self.config.layout.as_ref()
+    }
+
     /// Get startup command for this project
     pub fn startup_command(&self) -> Option<&str> {
         self.config
@@ -135,6 +179,17 @@ mod tests {
         assert_eq!(project.session_name(), "devobox-custom-name");
     }
 
+    #[test]
+    fn test_slug_is_a_valid_container_name() {
+        let slug = |name: &str| Project::new(PathBuf::from(name), ProjectConfig::default()).slug();
+        assert_eq!(slug("my-app"), "my-app");
+        assert_eq!(slug("My App"), "my-app");
+        assert_eq!(slug("app(old)"), "app-old");
+        assert_eq!(slug("_tmp.x"), "tmp.x");
+        assert_eq!(slug("ação"), "a-o");
+        assert_eq!(slug("日本"), "project");
+    }
+
     #[test]
     fn test_project_config_defaults() {
         let config = ProjectConfig::default();
@@ -153,6 +208,7 @@ mod tests {
         let config = ProjectConfig {
             project: Some(ProjectSettings {
                 env: vec!["NODE_ENV=development".into(), "DEBUG=app:*".into()],
+                env_file: None,
                 shell: None,
                 startup_command: None,
                 name: None,
@@ -0,0 +1,401 @@
+use super::{Service, ServiceKind};
+use anyhow::{Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A `[services.NAME]` table as written in a devobox.toml
+///
+/// Every field is optional: a later config layer (or a service naming this one
+/// in `extends`) only sets the fields it changes. Resolving the layers produces
+/// the final [`Service`].
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct ServiceConfig {
+    /// Another service of the same config whose fields this one starts from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// Set to false to drop an inherited service (default: true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Set to true to take the name over from the projects this one includes,
+    /// instead of failing on the duplicate
+    #[serde(default, rename = "override", skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<bool>,
+    /// Set to true to keep one container for every project defining this
+    /// service, even when service containers are namespaced per project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared: Option<bool>,
+    /// Container image, e.g. "postgres:16"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ServiceKind>,
+    /// Published ports, e.g. "5432:5432" or "8080/udp"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ports: Option<ListPatch>,
+    /// Environment variables as KEY=VALUE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<ListPatch>,
+    /// Volume mounts, e.g. "pgdata:/var/lib/postgresql/data"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volumes: Option<ListPatch>,
+    /// Command run inside the container to check its health
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub healthcheck_command: Option<String>,
+    /// Interval between health checks, e.g. "5s"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub healthcheck_interval: Option<String>,
+    /// Timeout for each health check, e.g. "3s"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub healthcheck_timeout: Option<String>,
+    /// Failed checks before the container is unhealthy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub healthcheck_retries: Option<u32>,
+}
+
+/// How a list field combines with the list it inherits
+///
+/// A plain list replaces the inherited one; `{ append = [...] }` adds to it and
+/// `{ replace = [...] }` makes the replacement explicit.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(from = "ListPatchRepr", into = "ListPatchRepr")]
+pub struct ListPatch {
+    pub replace: Option<Vec<String>>,
+    pub append: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+enum ListPatchRepr {
+    List(Vec<String>),
+    Markers(ListMarkers),
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct ListMarkers {
+    /// Items added after the inherited ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    append: Vec<String>,
+    /// Items used instead of the inherited ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replace: Option<Vec<String>>,
+}
+
+impl From<ListPatchRepr> for ListPatch {
+    fn from(repr: ListPatchRepr) -> Self {
+        match repr {
+            ListPatchRepr::List(items) => ListPatch::replace(items),
+            ListPatchRepr::Markers(ListMarkers { append, replace }) => {
+                ListPatch { replace, append }
+            }
+        }
+    }
+}
+
+impl From<ListPatch> for ListPatchRepr {
+    fn from(patch: ListPatch) -> Self {
+        match patch {
+            ListPatch {
+                replace: Some(items),
+                append,
+            } if append.is_empty() => ListPatchRepr::List(items),
+            ListPatch { replace, append } => {
+                ListPatchRepr::Markers(ListMarkers { append, replace })
+            }
+        }
+    }
+}
+
+impl ListPatch {
+    pub fn replace(items: Vec<String>) -> Self {
+        Self {
+            replace: Some(items),
+            append: Vec::new(),
+        }
+    }
+
+    /// Applies `over` on top of this patch, as a later layer would
+    fn then(self, over: ListPatch) -> ListPatch {
+        if over.replace.is_some() {
+            return over;
+        }
+
+        let mut append = self.append;
+        append.extend(over.append);
+        ListPatch {
+            replace: self.replace,
+            append,
+        }
+    }
+
+    /// Produces the final list; appended items already present are skipped
+    pub fn apply(self, base: Vec<String>) -> Vec<String> {
+        let mut items = self.replace.unwrap_or(base);
+        for item in self.append {
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+        items
+    }
+}
+
+fn combine(base: Option<ListPatch>, over: Option<ListPatch>) -> Option<ListPatch> {
+    match (base, over) {
+        (Some(base), Some(over)) => Some(base.then(over)),
+        (base, over) => over.or(base),
+    }
+}
+
+impl ServiceConfig {
+    /// Field-level merge: whatever `over` sets wins, lists follow their markers
+    pub fn overlay(&mut self, over: ServiceConfig) {
+        self.extends = over.extends.or(self.extends.take());
+        self.enabled = over.enabled.or(self.enabled);
+        self.overrides = over.overrides.or(self.overrides);
+        self.shared = over.shared.or(self.shared);
+        self.image = over.image.or(self.image.take());
+        self.kind = over.kind.or(self.kind.take());
+        self.ports = combine(self.ports.take(), over.ports);
+        self.env = combine(self.env.take(), over.env);
+        self.volumes = combine(self.volumes.take(), over.volumes);
+        self.healthcheck_command = over.healthcheck_command.or(self.healthcheck_command.take());
+        self.healthcheck_interval = over
+            .healthcheck_interval
+            .or(self.healthcheck_interval.take());
+        self.healthcheck_timeout = over.healthcheck_timeout.or(self.healthcheck_timeout.take());
+        self.healthcheck_retries = over.healthcheck_retries.or(self.healthcheck_retries);
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.enabled == Some(false)
+    }
+
+    pub fn is_override(&self) -> bool {
+        self.overrides == Some(true)
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.shared == Some(true)
+    }
+
+    fn into_service(self, name: &str) -> Result<Service> {
+        let Some(image) = self.image else {
+            bail!("Serviço '{}' sem campo 'image'", name);
+        };
+
+        Ok(Service {
+            name: name.to_string(),
+            image,
+            kind: self.kind.unwrap_or_default(),
+            ports: self.ports.map(|p| p.apply(Vec::new())).unwrap_or_default(),
+            env: self.env.map(|p| p.apply(Vec::new())).unwrap_or_default(),
+            volumes: self
+                .volumes
+                .map(|p| p.apply(Vec::new()))
+                .unwrap_or_default(),
+            healthcheck_command: self.healthcheck_command,
+            healthcheck_interval: self.healthcheck_interval,
+            healthcheck_timeout: self.healthcheck_timeout,
+            healthcheck_retries: self.healthcheck_retries,
+            project: None,
+        })
+    }
+}
+
+/// Follows the `extends` chain of `name` and folds it into a single config
+///
+/// `enabled` and `override` are not inherited, so a disabled service can serve
+/// as a template.
+fn flatten(name: &str, configs: &HashMap<String, ServiceConfig>) -> Result<ServiceConfig> {
+    let mut chain = vec![name];
+    let mut current = &configs[name];
+
+    while let Some(base) = current.extends.as_deref() {
+        if chain.contains(&base) {
+            chain.push(base);
+            bail!("Ciclo em 'extends': {}", chain.join(" -> "));
+        }
+        let Some(next) = configs.get(base) else {
+            bail!(
+                "Serviço '{}' estende '{}', que não existe",
+                chain[chain.len() - 1],
+                base
+            );
+        };
+        chain.push(base);
+        current = next;
+    }
+
+    let mut merged = ServiceConfig::default();
+    for link in chain.iter().rev() {
+        merged.overlay(configs[*link].clone());
+    }
+    merged.extends = None;
+    merged.enabled = configs[name].enabled;
+    merged.overrides = configs[name].overrides;
+    Ok(merged)
+}
+
+/// Turns the merged `[services]` tables into services, sorted by name
+///
+/// Disabled services are left out.
+pub fn resolve_service_configs(configs: &HashMap<String, ServiceConfig>) -> Result<Vec<Service>> {
+    let mut names: Vec<&String> = configs.keys().collect();
+    names.sort();
+
+    let mut services = Vec::new();
+    for name in names {
+        let config = flatten(name, configs)?;
+        if config.is_disabled() {
+            continue;
+        }
+        services.push(config.into_service(name)?);
+    }
+
+    Ok(services)
+}
+
+/// Applies the service tables of a later layer over those of an earlier one
+pub fn merge_service_configs(
+    base: &mut HashMap<String, ServiceConfig>,
+    over: HashMap<String, ServiceConfig>,
+) {
+    for (name, config) in over {
+        base.entry(name).or_default().overlay(config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> HashMap<String, ServiceConfig> {
+        #[derive(Deserialize)]
+        struct File {
+            services: HashMap<String, ServiceConfig>,
+        }
+        toml::from_str::<File>(toml).unwrap().services
+    }
+
+    fn resolve(configs: &HashMap<String, ServiceConfig>) -> HashMap<String, Service> {
+        resolve_service_configs(configs)
+            .unwrap()
+            .into_iter()
+            .map(|svc| (svc.name.clone(), svc))
+            .collect()
+    }
+
+    #[test]
+    fn test_overrides_single_fields() {
+        let mut configs = parse(
+            r#"
+[services.pg]
+image = "postgres:16"
+type = "database"
+ports = ["5432:5432"]
+env = ["POSTGRES_PASSWORD=dev"]
+"#,
+        );
+        merge_service_configs(
+            &mut configs,
+            parse(
+                "[services.pg]\nports = [\"5433:5432\"]\nenv = { append = [\"POSTGRES_DB=app\"] }\n",
+            ),
+        );
+
+        let pg = &resolve(&configs)["pg"];
+        assert_eq!(pg.image, "postgres:16");
+        assert_eq!(pg.kind, ServiceKind::Database);
+        assert_eq!(pg.ports, vec!["5433:5432"]);
+        assert_eq!(pg.env, vec!["POSTGRES_PASSWORD=dev", "POSTGRES_DB=app"]);
+    }
+
+    #[test]
+    fn test_list_markers_compose_across_layers() {
+        let mut configs = parse("[services.api]\nimage = \"api\"\nenv = [\"A=1\"]\n");
+        merge_service_configs(
+            &mut configs,
+            parse("[services.api]\nenv = { append = [\"B=2\"] }\n"),
+        );
+        merge_service_configs(
+            &mut configs,
+            parse("[services.api]\nenv = { append = [\"C=3\", \"A=1\"] }\n"),
+        );
+        assert_eq!(resolve(&configs)["api"].env, vec!["A=1", "B=2", "C=3"]);
+
+        merge_service_configs(
+            &mut configs,
+            parse("[services.api]\nenv = { replace = [\"D=4\"] }\n"),
+        );
+        assert_eq!(resolve(&configs)["api"].env, vec!["D=4"]);
+    }
+
+    #[test]
+    fn test_extends_and_disabled_templates() {
+        let configs = parse(
+            r#"
+[services.pg]
+image = "postgres:16"
+type = "database"
+ports = ["5432:5432"]
+enabled = false
+
+[services.pg-test]
+extends = "pg"
+ports = ["5433:5432"]
+volumes = { append = ["pgtest:/var/lib/postgresql/data"] }
+"#,
+        );
+
+        let services = resolve(&configs);
+        assert!(!services.contains_key("pg"));
+
+        let test = &services["pg-test"];
+        assert_eq!(test.image, "postgres:16");
+        assert_eq!(test.kind, ServiceKind::Database);
+        assert_eq!(test.ports, vec!["5433:5432"]);
+        assert_eq!(test.volumes, vec!["pgtest:/var/lib/postgresql/data"]);
+    }
+
+    #[test]
+    fn test_disabling_an_inherited_service() {
+        let mut configs =
+            parse("[services.pg]\nimage = \"postgres\"\n[services.redis]\nimage = \"redis\"\n");
+        merge_service_configs(&mut configs, parse("[services.redis]\nenabled = false\n"));
+
+        let names: Vec<String> = resolve_service_configs(&configs)
+            .unwrap()
+            .into_iter()
+            .map(|svc| svc.name)
+            .collect();
+        assert_eq!(names, vec!["pg"]);
+    }
+
+    #[test]
+    fn test_extends_errors() {
+        let missing = parse("[services.a]\nextends = \"b\"\n");
+        let err = resolve_service_configs(&missing).unwrap_err();
+        assert!(err.to_string().contains("estende 'b'"));
+
+        let cycle = parse("[services.a]\nextends = \"b\"\n[services.b]\nextends = \"a\"\n");
+        let err = resolve_service_configs(&cycle).unwrap_err();
+        assert_eq!(err.to_string(), "Ciclo em 'extends': a -> b -> a");
+
+        let no_image = parse("[services.a]\nports = [\"80:80\"]\n");
+        let err = resolve_service_configs(&no_image).unwrap_err();
+        assert_eq!(err.to_string(), "Serviço 'a' sem campo 'image'");
+    }
+
+    #[test]
+    fn test_list_patch_round_trips() {
+        let configs = parse("[services.a]\nports = [\"80:80\"]\nenv = { append = [\"A=1\"] }\n");
+        let serialized = toml::to_string(&configs["a"]).unwrap();
+        assert!(serialized.contains("ports = [\"80:80\"]"));
+        assert!(serialized.contains("append = [\"A=1\"]"));
+
+        let markers = toml::from_str::<ServiceConfig>("env = { prepend = [\"A=1\"] }\n");
+        assert!(markers.is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Container, ContainerSpec};
+use super::{BuildOptions, Container, ContainerFilter, ContainerInfo, ContainerSpec, ImageInfo};
 use anyhow::Result;
 use std::fmt::Debug;
 use std::path::Path;
@@ -17,6 +17,9 @@ pub trait ContainerRuntime: Send + Sync + Debug {
     /// Get the current state of a container
     fn get_container(&self, name: &str) -> Result<Container>;
 
+    /// List every container (running or not) matching the filter in a single query
+    fn list_containers(&self, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>>;
+
     /// Get the health status of a container
     fn get_container_health(&self, name: &str) -> Result<ContainerHealthStatus>;
 
@@ -32,24 +35,47 @@ pub trait ContainerRuntime: Send + Sync + Debug {
     /// Remove a container
     fn remove_container(&self, name: &str) -> Result<()>;
 
-    /// Execute a shell in a container with an optional session name
-    fn exec_shell(
+    /// Execute an interactive command in a running container
+    fn exec_command(
         &self,
         container: &str,
         workdir: Option<&Path>,
-        session_name: Option<&str>,
+        env: &[String],
+        command: &[&str],
     ) -> Result<()>;
 
+    /// Run a non-interactive command in a running container and capture its
+    /// stdout. Meant for queries: dry runs still execute it
+    fn exec_output(&self, container: &str, command: &[&str]) -> Result<String>;
+
+    /// Fetch the last `tail` lines of a container's logs
+    fn container_logs(&self, name: &str, tail: usize) -> Result<String>;
+
     /// Check if a command is available
     fn is_command_available(&self, cmd: &str) -> bool;
 
-    /// Build an image
-    fn build_image(&self, tag: &str, containerfile: &Path, context_dir: &Path) -> Result<()>;
+    /// Build a dev image, labelled `DEV_IMAGE_LABEL`
+    fn build_image(
+        &self,
+        tag: &str,
+        containerfile: &Path,
+        context_dir: &Path,
+        options: &BuildOptions,
+    ) -> Result<()>;
+
+    /// Tags of the local images of `repository`
+    fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>>;
+
+    /// Add the `target` tag to the `source` image
+    fn tag_image(&self, source: &str, target: &str) -> Result<()>;
+
+    /// Remove an image tag; the image goes with its last tag
+    fn remove_image(&self, reference: &str) -> Result<()>;
 
     /// Prune stopped containers
     fn prune_containers(&self) -> Result<()>;
 
-    /// Prune unused images
+    /// Prune unused images, except dev images (see `DEV_IMAGE_LABEL`)
     fn prune_images(&self) -> Result<()>;
 
     /// Prune unused volumes
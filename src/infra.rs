@@ -1,6 +1,20 @@
 pub mod config;
+pub mod config_provenance;
+pub mod config_schema;
+pub mod config_validate;
+pub mod docker_adapter;
+pub mod dry_run;
+pub mod engine;
+mod engine_cli;
 pub mod podman_adapter;
+pub mod podman_api_adapter;
 pub mod project_discovery;
+pub mod project_env;
+pub mod project_graph;
+pub mod service_resolver;
+pub mod stack_detection;
 
+pub use docker_adapter::DockerAdapter;
 pub use podman_adapter::PodmanAdapter;
+pub use podman_api_adapter::PodmanApiAdapter;
 pub use project_discovery::ProjectDiscovery;
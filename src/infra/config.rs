@@ -1,11 +1,13 @@
-use crate::domain::{Project, ProjectConfig, Service};
+use crate::domain::{
+    Project, ProjectConfig, Service, ServiceConfig, merge_service_configs, resolve_service_configs,
+};
 use anyhow::{Context, Result, bail};
-use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use toml;
-use tracing::{info, warn};
 
 #[derive(Deserialize, Debug)]
 pub struct MiseConfig {
@@ -24,34 +26,119 @@ pub fn ensure_config_dir(config_dir: &Path) -> Result<()> {
 }
 
 pub const DEFAULT_DEVOBOX_TOML_NAME: &str = "devobox.toml";
+/// Dev container shared by projects without an image of their own
+pub const DEFAULT_CONTAINER_NAME: &str = "devobox";
 pub const MISE_TOML: &str = include_str!("../../config/mise.toml");
 pub const STARSHIP_TOML: &str = include_str!("../../config/starship.toml");
 pub const ZELLIJ_KDL: &str = include_str!("../../config/zellij.kdl");
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct PathsConfig {
+    /// Containerfile of the dev image, relative to the config dir
     pub containerfile: Option<PathBuf>,
+    /// mise tool versions baked into the image
     pub mise_toml: Option<PathBuf>,
+    /// Starship prompt configuration
     pub starship_toml: Option<PathBuf>,
 }
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct BuildConfig {
+    /// Tag of the dev image
     pub image_name: Option<String>,
+    /// Project only: Containerfile of the project's own dev image, relative to
+    /// its devobox.toml
+    pub containerfile: Option<PathBuf>,
+    /// Project only: tag of the project's own dev image (default:
+    /// `devobox-<project>-img`); without `containerfile`, an existing image
+    pub image: Option<String>,
+    /// Previous dev image versions kept for `devobox image rollback` (default: 3)
+    pub keep_images: Option<usize>,
+    /// Build arguments (`--build-arg KEY=value`), merged key by key
+    pub args: Option<BTreeMap<String, String>>,
+    /// Stage of a multi-stage Containerfile to build
+    pub target: Option<String>,
+    /// Files exposed to `RUN --mount=type=secret,id=<key>` (e.g. an `.npmrc`
+    /// for a private registry), merged key by key; never stored in the image
+    pub secrets: Option<BTreeMap<String, PathBuf>>,
+    /// Platform of the image, e.g. `linux/arm64`
+    pub platform: Option<String>,
+}
+
+impl BuildConfig {
+    /// Secret files are relative to the devobox.toml declaring them
+    fn resolve_secrets(&mut self, dir: &Path) {
+        for path in self
+            .secrets
+            .iter_mut()
+            .flat_map(|secrets| secrets.values_mut())
+        {
+            let expanded = PathBuf::from(shellexpand::tilde(&path.to_string_lossy()).into_owned());
+            *path = dir.join(expanded);
+        }
+    }
 }
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct ContainerConfig {
+    /// Name of the dev container
     pub name: Option<String>,
+    /// Working directory inside the dev container
     pub workdir: Option<PathBuf>,
+    /// Terminal multiplexer for shells and project sessions: zellij, tmux or none
+    pub multiplexer: Option<MultiplexerKind>,
+}
+
+/// Terminal multiplexer holding shell and project sessions
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MultiplexerKind {
+    #[default]
+    Zellij,
+    Tmux,
+    /// A plain login shell, without sessions
+    None,
+}
+
+/// Container engine used to run devobox containers
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineKind {
+    /// Podman if available, Docker otherwise
+    #[default]
+    Auto,
+    Podman,
+    Docker,
 }
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct RuntimeConfig {
+    /// Container engine: auto, podman or docker
+    pub engine: Option<EngineKind>,
+    /// Talk to Podman through its REST socket when available (default: true)
+    pub api: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct DependenciesConfig {
+    /// Projects whose services are also started, relative to this file
     pub include_projects: Option<Vec<PathBuf>>,
 }
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct ProjectsConfig {
+    /// Directories searched for projects (default: ["~/code"])
+    pub roots: Option<Vec<PathBuf>>,
+    /// How many levels below each root are searched (default: 3)
+    pub max_depth: Option<usize>,
+    /// Glob patterns of directories to skip, e.g. "archive" or "forks/*"
+    pub exclude: Option<Vec<String>>,
+    /// Name service containers `<project>-<service>` so projects defining the
+    /// same service don't share a container (default: false)
+    pub namespace_services: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct AppConfig {
     #[serde(default)]
     pub paths: PathsConfig,
@@ -60,13 +147,36 @@ pub struct AppConfig {
     #[serde(default)]
     pub container: ContainerConfig,
     #[serde(default)]
+    pub runtime: RuntimeConfig,
+    #[serde(default)]
     pub dependencies: DependenciesConfig,
+    #[serde(default)]
+    pub projects: ProjectsConfig,
     /// Services defined inline as [services.NAME]
     #[serde(default)]
-    pub services: Option<HashMap<String, Service>>,
+    pub services: Option<HashMap<String, ServiceConfig>>,
 }
 
 impl AppConfig {
+    /// Whether the local devobox.toml declares its own dev image
+    pub fn has_project_image(&self) -> bool {
+        self.build.containerfile.is_some() || self.build.image.is_some()
+    }
+
+    /// Containerfile to build the dev image from; `None` runs the project's
+    /// `[build] image` as is
+    pub fn dev_containerfile(&self, config_dir: &Path) -> Option<PathBuf> {
+        match (&self.build.containerfile, &self.build.image) {
+            (Some(containerfile), _) => Some(containerfile.clone()),
+            (None, Some(_)) => None,
+            (None, None) => self
+                .paths
+                .containerfile
+                .as_ref()
+                .map(|containerfile| config_dir.join(containerfile)),
+        }
+    }
+
     /// Merges another AppConfig into self.
     /// Values from `other` overwrite values in `self` if present.
     pub fn merge(&mut self, other: AppConfig) {
@@ -82,12 +192,54 @@ impl AppConfig {
         if let Some(name) = other.build.image_name {
             self.build.image_name = Some(name);
         }
+        if let Some(cf) = other.build.containerfile {
+            self.build.containerfile = Some(cf);
+        }
+        if let Some(image) = other.build.image {
+            self.build.image = Some(image);
+        }
+        if let Some(keep) = other.build.keep_images {
+            self.build.keep_images = Some(keep);
+        }
+        if let Some(args) = other.build.args {
+            self.build.args.get_or_insert_default().extend(args);
+        }
+        if let Some(target) = other.build.target {
+            self.build.target = Some(target);
+        }
+        if let Some(secrets) = other.build.secrets {
+            self.build.secrets.get_or_insert_default().extend(secrets);
+        }
+        if let Some(platform) = other.build.platform {
+            self.build.platform = Some(platform);
+        }
         if let Some(name) = other.container.name {
             self.container.name = Some(name);
         }
         if let Some(wd) = other.container.workdir {
             self.container.workdir = Some(wd);
         }
+        if let Some(multiplexer) = other.container.multiplexer {
+            self.container.multiplexer = Some(multiplexer);
+        }
+        if let Some(engine) = other.runtime.engine {
+            self.runtime.engine = Some(engine);
+        }
+        if let Some(api) = other.runtime.api {
+            self.runtime.api = Some(api);
+        }
+        if let Some(roots) = other.projects.roots {
+            self.projects.roots = Some(roots);
+        }
+        if let Some(depth) = other.projects.max_depth {
+            self.projects.max_depth = Some(depth);
+        }
+        if let Some(exclude) = other.projects.exclude {
+            self.projects.exclude = Some(exclude);
+        }
+        if let Some(namespace) = other.projects.namespace_services {
+            self.projects.namespace_services = Some(namespace);
+        }
         if let Some(deps) = other.dependencies.include_projects {
             // Merge dependencies: append unique ones or overwrite?
             // Appending seems safer to gather all deps.
@@ -104,132 +256,58 @@ impl AppConfig {
             self.dependencies.include_projects = Some(current);
         }
 
-        // Merge services
+        // Services with the same name are merged field by field
         if let Some(other_services) = other.services {
-            match &mut self.services {
-                Some(existing) => {
-                    // Services with same name in 'other' overwrite existing
-                    for (name, service) in other_services {
-                        existing.insert(name, service);
-                    }
-                }
-                None => {
-                    self.services = Some(other_services);
-                }
-            }
+            merge_service_configs(
+                self.services.get_or_insert_with(HashMap::new),
+                other_services,
+            );
         }
     }
 }
 
-/// Converts services HashMap to Vec<Service> with validation
-fn services_from_hashmap(services_map: &HashMap<String, Service>) -> Result<Vec<Service>> {
-    let mut services = Vec::new();
-
-    for (name, service) in services_map {
-        // Validate service name
-        if name.trim().is_empty() {
-            bail!("Nome de serviço vazio encontrado");
-        }
-
-        // Validate name format (container name restrictions)
-        let first_char = name.chars().next().unwrap();
-        if !first_char.is_alphanumeric() {
-            bail!(
-                "Nome de serviço '{}' deve começar com letra ou número",
-                name
-            );
-        }
-
-        for c in name.chars() {
-            if !c.is_alphanumeric() && c != '_' && c != '.' && c != '-' {
-                bail!(
-                    "Nome de serviço '{}' contém caractere inválido '{}'",
-                    name,
-                    c
-                );
-            }
-        }
-
-        // Validate image
-        if service.image.trim().is_empty() {
-            bail!("Serviço '{}' sem campo 'image'", name);
-        }
+/// Checks a service name against container naming rules, describing the first problem
+pub(crate) fn service_name_problem(name: &str) -> Option<String> {
+    if name.trim().is_empty() {
+        return Some("Nome de serviço vazio encontrado".to_string());
+    }
 
-        services.push(service.clone().with_name(name.clone()));
+    // Container name restrictions
+    if !name.starts_with(|c: char| c.is_alphanumeric()) {
+        return Some(format!(
+            "Nome de serviço '{}' deve começar com letra ou número",
+            name
+        ));
     }
 
-    Ok(services)
+    name.chars()
+        .find(|c| !c.is_alphanumeric() && *c != '_' && *c != '.' && *c != '-')
+        .map(|c| {
+            format!(
+                "Nome de serviço '{}' contém caractere inválido '{}'",
+                name, c
+            )
+        })
 }
 
-pub fn resolve_all_services(start_dir: &Path, start_config: &AppConfig) -> Result<Vec<Service>> {
-    let mut all_services = Vec::new();
-    let mut service_names = HashSet::new();
-    let mut visited_paths = HashSet::new();
-
-    visited_paths.insert(fs::canonicalize(start_dir).unwrap_or(start_dir.to_path_buf()));
-
-    // Helper to add services with duplicate detection
-    let mut add_services = |services: Vec<Service>| -> Result<()> {
-        for service in services {
-            if !service_names.insert(service.name.clone()) {
-                warn!("  Serviço duplicado ignorado: {}", service.name);
-                continue;
-            }
-            all_services.push(service);
+/// Resolves the `[services]` tables (extends, overrides, `enabled`) into services, with validation
+pub(crate) fn services_from_hashmap(
+    services_map: &HashMap<String, ServiceConfig>,
+) -> Result<Vec<Service>> {
+    for name in services_map.keys() {
+        if let Some(problem) = service_name_problem(name) {
+            bail!(problem);
         }
-        Ok(())
-    };
-
-    // 1. Load services from current config
-    if let Some(services_map) = &start_config.services {
-        info!(
-            "  Carregando {} serviço(s) da configuração atual...",
-            services_map.len()
-        );
-        let services = services_from_hashmap(services_map)?;
-        add_services(services)?;
     }
 
-    // 2. Load services from dependencies
-    if let Some(deps) = &start_config.dependencies.include_projects {
-        for relative_path in deps {
-            let project_path = start_dir.join(relative_path);
-            let canonical_path = match fs::canonicalize(&project_path) {
-                Ok(p) => p,
-                Err(_) => {
-                    warn!("  Caminho de dependência inválido: {:?}", project_path);
-                    continue;
-                }
-            };
+    let services = resolve_service_configs(services_map)?;
 
-            if !visited_paths.insert(canonical_path.clone()) {
-                continue;
-            }
-
-            let dep_config = match load_app_config(&canonical_path) {
-                Ok(cfg) => cfg,
-                Err(e) => {
-                    warn!(
-                        "  Erro ao carregar config de dependência em {:?}: {}",
-                        canonical_path, e
-                    );
-                    continue;
-                }
-            };
-
-            if let Some(dep_services_map) = &dep_config.services {
-                info!(
-                    "  Carregando {} serviço(s) de dependência {:?}...",
-                    dep_services_map.len(),
-                    canonical_path
-                );
-                let services = services_from_hashmap(dep_services_map)?;
-                add_services(services)?;
-            }
-        }
+    // Validate image
+    if let Some(service) = services.iter().find(|svc| svc.image.trim().is_empty()) {
+        bail!("Serviço '{}' sem campo 'image'", service.name);
     }
 
-    Ok(all_services)
+    Ok(services)
 }
 
 pub fn install_default_config(target_dir: &Path) -> Result<()> {
@@ -285,24 +363,70 @@ pub fn read_containerfile_content(config_dir: &Path) -> Result<String> {
 }
 
 pub fn load_app_config(config_dir: &Path) -> Result<AppConfig> {
+    // The local config is the devobox.toml in the current working directory
+    load_app_config_from(config_dir, Path::new("./"))
+}
+
+/// The global devobox.toml alone: no local config merged, no defaults filled
+pub fn load_global_config(config_dir: &Path) -> Result<AppConfig> {
+    let path = config_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    if !path.exists() {
+        return Ok(AppConfig::default());
+    }
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("lendo config global em {:?}", path))?;
+    toml::from_str(&content).with_context(|| format!("parse de config global em {:?}", path))
+}
+
+/// Loads the global config from `config_dir`, merges `local_dir/devobox.toml`
+/// over it and fills in defaults
+pub fn load_app_config_from(config_dir: &Path, local_dir: &Path) -> Result<AppConfig> {
     let global_config_path = config_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
     let mut app_config = AppConfig::default();
 
     if global_config_path.exists() {
         let content = fs::read_to_string(&global_config_path)
             .with_context(|| format!("lendo config global em {:?}", global_config_path))?;
-        let global_app_config: AppConfig = toml::from_str(&content)
+        let mut global_app_config: AppConfig = toml::from_str(&content)
             .with_context(|| format!("parse de config global em {:?}", global_config_path))?;
+        global_app_config.build.resolve_secrets(config_dir);
         app_config = global_app_config;
     }
 
-    let local_config_path = PathBuf::from("./").join(DEFAULT_DEVOBOX_TOML_NAME); // Check current working directory
+    let local_config_path = local_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
     if local_config_path.exists() {
         let content = fs::read_to_string(&local_config_path)
             .with_context(|| format!("lendo config local em {:?}", local_config_path))?;
-        let local_app_config: AppConfig = toml::from_str(&content)
+        let mut local_app_config: AppConfig = toml::from_str(&content)
             .with_context(|| format!("parse de config local em {:?}", local_config_path))?;
+        let named_container = local_app_config.container.name.is_some();
+        let dir = fs::canonicalize(local_dir).unwrap_or_else(|_| local_dir.to_path_buf());
+        if let Some(cf) = local_app_config.build.containerfile.take() {
+            local_app_config.build.containerfile = Some(dir.join(cf));
+        }
+        local_app_config.build.resolve_secrets(&dir);
         app_config.merge(local_app_config);
+
+        // A project with its own image gets its own dev container
+        if app_config.has_project_image() {
+            let project_config: ProjectConfig = toml::from_str(&content)
+                .with_context(|| format!("parse de config local em {:?}", local_config_path))?;
+            let project = Project::new(dir, project_config);
+
+            // Engines reject uppercase and spaces in these, so use the slug
+            let container_name = format!("devobox-{}", project.slug());
+            app_config.build.image_name = Some(
+                app_config
+                    .build
+                    .image
+                    .clone()
+                    .unwrap_or_else(|| format!("{container_name}-img")),
+            );
+            if !named_container {
+                app_config.container.name = Some(container_name);
+            }
+        }
     }
 
     // Default values if not set in any config
@@ -319,7 +443,7 @@ pub fn load_app_config(config_dir: &Path) -> Result<AppConfig> {
         app_config.build.image_name = Some("devobox-img".to_string());
     }
     if app_config.container.name.is_none() {
-        app_config.container.name = Some("devobox".to_string());
+        app_config.container.name = Some(DEFAULT_CONTAINER_NAME.to_string());
     }
     if app_config.container.workdir.is_none() {
         app_config.container.workdir = Some(PathBuf::from("/home/dev"));
@@ -328,88 +452,6 @@ pub fn load_app_config(config_dir: &Path) -> Result<AppConfig> {
     Ok(app_config)
 }
 
-/// Resolves services for a specific project
-///
-/// Loads the project's own services in the devobox.toml and any services from project dependencies.
-/// This function is used when activating a project workspace to determine which
-/// services need to be started.
-///
-/// # Arguments
-/// * `project` - The project to resolve services for
-/// * `_config_dir` - The global config directory (currently unused but kept for future use)
-///
-/// # Returns
-/// * `Ok(Vec<Service>)` - List of all services for the project
-/// * `Err` - If there was an error loading services
-pub fn resolve_project_services(project: &Project, _config_dir: &Path) -> Result<Vec<Service>> {
-    let mut all_services = Vec::new();
-    let mut service_names = HashSet::new();
-    let mut visited_paths = HashSet::new();
-
-    visited_paths.insert(fs::canonicalize(&project.path).unwrap_or_else(|_| project.path.clone()));
-
-    // Helper to add services with duplicate detection
-    let mut add_services = |services: Vec<Service>| -> Result<()> {
-        for service in services {
-            if !service_names.insert(service.name.clone()) {
-                warn!("  Serviço duplicado ignorado: {}", service.name);
-                continue;
-            }
-            all_services.push(service);
-        }
-        Ok(())
-    };
-
-    // 1. Load project's own services
-    if let Some(services_map) = &project.config.services {
-        info!(
-            "  Carregando {} serviço(s) do projeto...",
-            services_map.len()
-        );
-        let services = services_from_hashmap(services_map)?;
-        add_services(services)?;
-    }
-
-    // 2. Load services from project dependencies
-    for relative_path in &project.config.dependencies.include_projects {
-        let dep_path = project.path.join(relative_path);
-        let canonical_path = match fs::canonicalize(&dep_path) {
-            Ok(p) => p,
-            Err(_) => {
-                warn!("  Caminho de dependência inválido: {:?}", dep_path);
-                continue;
-            }
-        };
-
-        if !visited_paths.insert(canonical_path.clone()) {
-            continue;
-        }
-
-        let dep_config_path = canonical_path.join("devobox.toml");
-        if dep_config_path.exists() {
-            match fs::read_to_string(&dep_config_path) {
-                Ok(content) => match toml::from_str::<ProjectConfig>(&content) {
-                    Ok(dep_config) => {
-                        if let Some(dep_services_map) = &dep_config.services {
-                            info!(
-                                "  Carregando {} serviço(s) de dependência: {:?}...",
-                                dep_services_map.len(),
-                                dep_config_path
-                            );
-                            let services = services_from_hashmap(dep_services_map)?;
-                            add_services(services)?;
-                        }
-                    }
-                    Err(e) => warn!("  Erro ao fazer parse de {:?}: {}", dep_config_path, e),
-                },
-                Err(e) => warn!("  Erro ao ler {:?}: {}", dep_config_path, e),
-            }
-        }
-    }
-
-    Ok(all_services)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -462,28 +504,21 @@ image = "minimal:latest"
 ports = ["5432:5432"]
 "#;
 
-        // Should fail at deserialization (image is required)
-        let result = toml::from_str::<AppConfig>(toml);
+        // A table without image parses (it may override another layer),
+        // but can't be resolved on its own
+        let config: AppConfig = toml::from_str(toml).unwrap();
+        let result = services_from_hashmap(config.services.as_ref().unwrap());
         assert!(result.is_err());
     }
 
     #[test]
     fn validates_service_name() {
-        use crate::domain::ServiceKind;
         let mut services_map = HashMap::new();
         services_map.insert(
             "".to_string(),
-            Service {
-                name: String::new(),
-                image: "test".to_string(),
-                kind: ServiceKind::default(),
-                ports: vec![],
-                env: vec![],
-                volumes: vec![],
-                healthcheck_command: None,
-                healthcheck_interval: None,
-                healthcheck_timeout: None,
-                healthcheck_retries: None,
+            ServiceConfig {
+                image: Some("test".to_string()),
+                ..Default::default()
             },
         );
 
@@ -493,51 +528,171 @@ ports = ["5432:5432"]
 
     #[test]
     fn merges_services_from_configs() {
-        use crate::domain::ServiceKind;
-        let mut base = AppConfig::default();
-        let mut base_services = HashMap::new();
-        base_services.insert(
-            "pg".to_string(),
-            Service {
-                name: String::new(),
-                image: "postgres:15".to_string(),
-                kind: ServiceKind::Database,
-                ports: vec![],
-                env: vec![],
-                volumes: vec![],
-                healthcheck_command: None,
-                healthcheck_interval: None,
-                healthcheck_timeout: None,
-                healthcheck_retries: None,
-            },
+        let mut base: AppConfig = toml::from_str(
+            r#"
+[services.pg]
+image = "postgres:15"
+type = "database"
+ports = ["5432:5432"]
+"#,
+        )
+        .unwrap();
+
+        let override_config: AppConfig = toml::from_str(
+            r#"
+[services.pg]
+ports = ["5433:5432"]
+
+[services.redis]
+image = "redis:7"
+"#,
+        )
+        .unwrap();
+
+        base.merge(override_config);
+
+        let services = services_from_hashmap(base.services.as_ref().unwrap()).unwrap();
+        assert_eq!(services.len(), 2);
+
+        // Only the port changed; the rest comes from the base definition
+        let pg = services.iter().find(|s| s.name == "pg").unwrap();
+        assert_eq!(pg.image, "postgres:15");
+        assert_eq!(pg.kind, crate::domain::ServiceKind::Database);
+        assert_eq!(pg.ports, vec!["5433:5432"]);
+        assert!(services.iter().any(|s| s.name == "redis"));
+    }
+
+    #[test]
+    fn parses_and_merges_runtime_engine() {
+        let mut base: AppConfig = toml::from_str("[runtime]\nengine = \"podman\"\n").unwrap();
+        assert_eq!(base.runtime.engine, Some(EngineKind::Podman));
+
+        base.merge(toml::from_str("[runtime]\nengine = \"docker\"\n").unwrap());
+        assert_eq!(base.runtime.engine, Some(EngineKind::Docker));
+
+        // A config without [runtime] keeps the previous choice
+        base.merge(AppConfig::default());
+        assert_eq!(base.runtime.engine, Some(EngineKind::Docker));
+
+        assert!(toml::from_str::<AppConfig>("[runtime]\nengine = \"lxc\"\n").is_err());
+
+        base.merge(toml::from_str("[runtime]\napi = false\n").unwrap());
+        assert_eq!(base.runtime.api, Some(false));
+        assert_eq!(base.runtime.engine, Some(EngineKind::Docker));
+    }
+
+    #[test]
+    fn parses_and_merges_multiplexer() {
+        let mut base: AppConfig = toml::from_str("[container]\nmultiplexer = \"tmux\"\n").unwrap();
+        assert_eq!(base.container.multiplexer, Some(MultiplexerKind::Tmux));
+
+        base.merge(toml::from_str("[container]\nname = \"dev\"\n").unwrap());
+        assert_eq!(base.container.multiplexer, Some(MultiplexerKind::Tmux));
+
+        base.merge(toml::from_str("[container]\nmultiplexer = \"none\"\n").unwrap());
+        assert_eq!(base.container.multiplexer, Some(MultiplexerKind::None));
+
+        assert!(toml::from_str::<AppConfig>("[container]\nmultiplexer = \"screen\"\n").is_err());
+    }
+
+    #[test]
+    fn project_image_gets_its_own_dev_container() {
+        let global = tempfile::tempdir().unwrap();
+        let code = tempfile::tempdir().unwrap();
+        let api = code.path().join("api");
+        let firmware = code.path().join("firmware");
+        fs::create_dir_all(&api).unwrap();
+        fs::create_dir_all(&firmware).unwrap();
+        fs::write(
+            api.join(DEFAULT_DEVOBOX_TOML_NAME),
+            "[build]\ncontainerfile = \"Containerfile.dev\"\n\n[container]\nmultiplexer = \"tmux\"\n",
+        )
+        .unwrap();
+        fs::write(
+            firmware.join(DEFAULT_DEVOBOX_TOML_NAME),
+            "[build]\nimage = \"ghcr.io/acme/rust-embedded:1\"\n",
+        )
+        .unwrap();
+
+        let config = load_app_config_from(global.path(), &api).unwrap();
+        assert_eq!(config.container.name.as_deref(), Some("devobox-api"));
+        assert_eq!(config.build.image_name.as_deref(), Some("devobox-api-img"));
+        assert_eq!(config.container.multiplexer, Some(MultiplexerKind::Tmux));
+        assert_eq!(
+            config.dev_containerfile(global.path()),
+            Some(fs::canonicalize(&api).unwrap().join("Containerfile.dev"))
         );
-        base.services = Some(base_services);
-
-        let mut override_config = AppConfig::default();
-        let mut override_services = HashMap::new();
-        override_services.insert(
-            "redis".to_string(),
-            Service {
-                name: String::new(),
-                image: "redis:7".to_string(),
-                kind: ServiceKind::Database,
-                ports: vec![],
-                env: vec![],
-                volumes: vec![],
-                healthcheck_command: None,
-                healthcheck_interval: None,
-                healthcheck_timeout: None,
-                healthcheck_retries: None,
-            },
+
+        // A prebuilt image is run as is
+        let config = load_app_config_from(global.path(), &firmware).unwrap();
+        assert_eq!(config.container.name.as_deref(), Some("devobox-firmware"));
+        assert_eq!(
+            config.build.image_name.as_deref(),
+            Some("ghcr.io/acme/rust-embedded:1")
+        );
+        assert_eq!(config.dev_containerfile(global.path()), None);
+
+        // Names come from the project's slug: image repositories are lowercase
+        let my_app = code.path().join("My App");
+        fs::create_dir_all(&my_app).unwrap();
+        fs::write(
+            my_app.join(DEFAULT_DEVOBOX_TOML_NAME),
+            "[build]\ncontainerfile = \"Containerfile.dev\"\n",
+        )
+        .unwrap();
+        let config = load_app_config_from(global.path(), &my_app).unwrap();
+        assert_eq!(config.container.name.as_deref(), Some("devobox-my-app"));
+        assert_eq!(
+            config.build.image_name.as_deref(),
+            Some("devobox-my-app-img")
         );
-        override_config.services = Some(override_services);
 
-        base.merge(override_config);
+        // Projects without [build] share the global dev container
+        let config = load_app_config_from(global.path(), code.path()).unwrap();
+        assert_eq!(config.container.name.as_deref(), Some("devobox"));
+        assert_eq!(
+            config.dev_containerfile(global.path()),
+            Some(global.path().join("Containerfile"))
+        );
+    }
+
+    #[test]
+    fn build_args_and_secrets_merge_by_key() {
+        let global = tempfile::tempdir().unwrap();
+        let local = tempfile::tempdir().unwrap();
+        fs::write(
+            global.path().join(DEFAULT_DEVOBOX_TOML_NAME),
+            "[build]\ntarget = \"dev\"\nargs = { NODE_VERSION = \"20\", RUST_VERSION = \"1.85\" }\nsecrets = { npmrc = \"npmrc\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            local.path().join(DEFAULT_DEVOBOX_TOML_NAME),
+            "[build]\nplatform = \"linux/arm64\"\nargs = { NODE_VERSION = \"22\" }\nsecrets = { pip = \"pip.conf\" }\n",
+        )
+        .unwrap();
+
+        let build = load_app_config_from(global.path(), local.path())
+            .unwrap()
+            .build;
+        assert_eq!(build.target.as_deref(), Some("dev"));
+        assert_eq!(build.platform.as_deref(), Some("linux/arm64"));
+        assert_eq!(
+            build.args.unwrap(),
+            BTreeMap::from([
+                ("NODE_VERSION".to_string(), "22".to_string()),
+                ("RUST_VERSION".to_string(), "1.85".to_string()),
+            ])
+        );
 
-        let services_map = base.services.unwrap();
-        assert_eq!(services_map.len(), 2);
-        assert!(services_map.contains_key("pg"));
-        assert!(services_map.contains_key("redis"));
+        // Secret files are relative to the file declaring them
+        let local_dir = fs::canonicalize(local.path()).unwrap();
+        assert_eq!(
+            build.secrets.unwrap(),
+            BTreeMap::from([
+                ("npmrc".to_string(), global.path().join("npmrc")),
+                ("pip".to_string(), local_dir.join("pip.conf")),
+            ])
+        );
     }
 
     #[test]
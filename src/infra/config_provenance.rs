@@ -0,0 +1,299 @@
+use crate::infra::config::{
+    AppConfig, DEFAULT_DEVOBOX_TOML_NAME, load_app_config_from, services_from_hashmap,
+};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Where an effective configuration value was defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// Built-in default, no file sets it
+    Default,
+    Global(PathBuf),
+    Local(PathBuf),
+    /// A project listed in `include_projects`
+    Dependency(PathBuf),
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(f, "padrão"),
+            ConfigOrigin::Global(path) => write!(f, "global: {}", path.display()),
+            ConfigOrigin::Local(path) => write!(f, "local: {}", path.display()),
+            ConfigOrigin::Dependency(path) => write!(f, "dependência: {}", path.display()),
+        }
+    }
+}
+
+/// One value of the merged configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveEntry {
+    /// Table the value lives in, e.g. `build` or `services.pg`
+    pub section: String,
+    pub key: String,
+    pub value: Value,
+    pub origin: ConfigOrigin,
+}
+
+struct Layer {
+    table: Table,
+    origin: ConfigOrigin,
+}
+
+impl Layer {
+    fn load(path: PathBuf, origin: fn(PathBuf) -> ConfigOrigin) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path).with_context(|| format!("lendo {:?}", path))?;
+        let table: Table =
+            toml::from_str(&content).with_context(|| format!("parse de {:?}", path))?;
+
+        Ok(Some(Self {
+            table,
+            origin: origin(path),
+        }))
+    }
+
+    fn defines(&self, section: &str, key: &str) -> bool {
+        self.table
+            .get(section)
+            .and_then(Value::as_table)
+            .is_some_and(|table| table.contains_key(key))
+    }
+
+    fn defines_service_field(&self, service: &str, key: &str) -> bool {
+        self.table
+            .get("services")
+            .and_then(|services| services.get(service))
+            .and_then(Value::as_table)
+            .is_some_and(|table| table.contains_key(key))
+    }
+
+    fn lists(&self, section: &str, key: &str, item: &Value) -> bool {
+        self.table
+            .get(section)
+            .and_then(|table| table.get(key))
+            .and_then(Value::as_array)
+            .is_some_and(|items| items.contains(item))
+    }
+}
+
+/// Merges the global, local and dependency configs exactly like the loader does,
+/// remembering which file each value came from
+pub fn effective_config(config_dir: &Path, local_dir: &Path) -> Result<Vec<EffectiveEntry>> {
+    let app = load_app_config_from(config_dir, local_dir)?;
+
+    let global_path = config_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    let local_path = local_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    let same_file = fs::canonicalize(&global_path).ok() == fs::canonicalize(&local_path).ok()
+        && global_path.exists();
+
+    let mut layers = Vec::new();
+    layers.extend(Layer::load(global_path, ConfigOrigin::Global)?);
+    if !same_file {
+        layers.extend(Layer::load(local_path, ConfigOrigin::Local)?);
+    }
+
+    // Later layers win, so search from the end
+    let origin_of = |section: &str, key: &str| {
+        layers
+            .iter()
+            .rev()
+            .find(|layer| layer.defines(section, key))
+            .map(|layer| layer.origin.clone())
+            .unwrap_or(ConfigOrigin::Default)
+    };
+
+    let mut entries = Vec::new();
+    let mut settings = Table::try_from(&app).context("serializando configuração efetiva")?;
+    // Services are reported separately, together with those of dependencies
+    settings.remove("services");
+
+    for (section, table) in &settings {
+        let Some(table) = table.as_table() else {
+            continue;
+        };
+
+        for (key, value) in table {
+            if let Some(items) = value.as_array() {
+                // include_projects is appended across files: attribute each item
+                for item in items {
+                    let origin = layers
+                        .iter()
+                        .find(|layer| layer.lists(section, key, item))
+                        .map(|layer| layer.origin.clone())
+                        .unwrap_or(ConfigOrigin::Default);
+                    entries.push(EffectiveEntry {
+                        section: section.clone(),
+                        key: key.clone(),
+                        value: item.clone(),
+                        origin,
+                    });
+                }
+                continue;
+            }
+
+            entries.push(EffectiveEntry {
+                section: section.clone(),
+                key: key.clone(),
+                value: value.clone(),
+                origin: origin_of(section, key),
+            });
+        }
+    }
+
+    let configs = app.services.clone().unwrap_or_default();
+
+    // A field comes from the last file setting it on the service itself, or
+    // else on the service it extends
+    let field_origin = |service: &str, key: &str| {
+        let mut seen = HashSet::new();
+        let mut current = Some(service);
+        while let Some(name) = current.filter(|name| seen.insert(*name)) {
+            if let Some(layer) = layers
+                .iter()
+                .rev()
+                .find(|layer| layer.defines_service_field(name, key))
+            {
+                return layer.origin.clone();
+            }
+            current = configs.get(name).and_then(|c| c.extends.as_deref());
+        }
+        ConfigOrigin::Default
+    };
+
+    let mut services: Vec<(String, Table, Option<ConfigOrigin>)> = Vec::new();
+    for service in services_from_hashmap(&configs)? {
+        let table = Table::try_from(&service).context("serializando serviço")?;
+        services.push((service.name, table, None));
+    }
+
+    // Dependencies only contribute services whose name isn't taken (or disabled) yet
+    for relative in app.dependencies.include_projects.iter().flatten() {
+        let dep_file = config_dir.join(relative).join(DEFAULT_DEVOBOX_TOML_NAME);
+        let Ok(content) = fs::read_to_string(&dep_file) else {
+            continue;
+        };
+        let Ok(dep) = toml::from_str::<AppConfig>(&content) else {
+            continue;
+        };
+        let dep_file = fs::canonicalize(&dep_file).unwrap_or(dep_file);
+
+        let Ok(dep_services) = services_from_hashmap(&dep.services.unwrap_or_default()) else {
+            continue;
+        };
+
+        for service in dep_services {
+            let taken = services
+                .iter()
+                .any(|(existing, _, _)| *existing == service.name)
+                || configs.get(&service.name).is_some_and(|c| c.is_disabled());
+            if taken {
+                continue;
+            }
+            let table = Table::try_from(&service).context("serializando serviço")?;
+            services.push((
+                service.name,
+                table,
+                Some(ConfigOrigin::Dependency(dep_file.clone())),
+            ));
+        }
+    }
+
+    services.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, table, origin) in services {
+        for (key, value) in table {
+            if value.as_array().is_some_and(|items| items.is_empty()) {
+                continue;
+            }
+            let origin = origin.clone().unwrap_or_else(|| field_origin(&name, &key));
+            entries.push(EffectiveEntry {
+                section: format!("services.{name}"),
+                key,
+                value,
+                origin,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry<'a>(entries: &'a [EffectiveEntry], section: &str, key: &str) -> &'a EffectiveEntry {
+        entries
+            .iter()
+            .find(|e| e.section == section && e.key == key)
+            .unwrap_or_else(|| panic!("{section}.{key} ausente"))
+    }
+
+    #[test]
+    fn test_each_value_keeps_its_origin() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = temp.path().join("config");
+        let local_dir = temp.path().join("app");
+        let dep_dir = config_dir.join("backend");
+        for dir in [&config_dir, &local_dir, &dep_dir] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        fs::write(
+            config_dir.join("devobox.toml"),
+            "[build]\nimage_name = \"global-img\"\n\n[dependencies]\ninclude_projects = [\"backend\"]\n\n[services.pg]\nimage = \"postgres:15\"\nports = [\"5432:5432\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            local_dir.join("devobox.toml"),
+            "[container]\nname = \"local-box\"\n\n[services.pg]\nimage = \"postgres:16\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dep_dir.join("devobox.toml"),
+            "[services.pg]\nimage = \"postgres:12\"\n\n[services.redis]\nimage = \"redis:7\"\n",
+        )
+        .unwrap();
+
+        let entries = effective_config(&config_dir, &local_dir).unwrap();
+
+        let image = entry(&entries, "build", "image_name");
+        assert_eq!(image.value.as_str(), Some("global-img"));
+        assert!(matches!(image.origin, ConfigOrigin::Global(_)));
+
+        let name = entry(&entries, "container", "name");
+        assert_eq!(name.value.as_str(), Some("local-box"));
+        assert!(matches!(name.origin, ConfigOrigin::Local(_)));
+
+        assert_eq!(
+            entry(&entries, "container", "workdir").origin,
+            ConfigOrigin::Default
+        );
+
+        // Local override wins over global; the dependency's pg is ignored
+        let pg = entry(&entries, "services.pg", "image");
+        assert_eq!(pg.value.as_str(), Some("postgres:16"));
+        assert!(matches!(pg.origin, ConfigOrigin::Local(_)));
+        let ports = entry(&entries, "services.pg", "ports");
+        assert!(matches!(ports.origin, ConfigOrigin::Global(_)));
+        assert_eq!(
+            entry(&entries, "services.pg", "type").origin,
+            ConfigOrigin::Default
+        );
+
+        let redis = entry(&entries, "services.redis", "image");
+        assert!(matches!(redis.origin, ConfigOrigin::Dependency(_)));
+
+        let include = entry(&entries, "dependencies", "include_projects");
+        assert_eq!(include.value.as_str(), Some("backend"));
+        assert!(matches!(include.origin, ConfigOrigin::Global(_)));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::domain::ProjectConfig;
+use crate::infra::config::AppConfig;
+use schemars::generate::SchemaSettings;
+use schemars::transform::RecursiveTransform;
+use schemars::{JsonSchema, Schema};
+use serde_json::{Value, json};
+
+// Never built, it only drives the derive.
+/// Any devobox.toml: the global settings plus the sections only project files use
+#[derive(JsonSchema)]
+#[schemars(title = "devobox.toml")]
+#[allow(dead_code)]
+struct DevoboxToml {
+    #[serde(flatten)]
+    app: AppConfig,
+    #[serde(flatten)]
+    project: ProjectConfig,
+}
+
+/// JSON Schema for devobox.toml, derived from the serde config types
+///
+/// Draft 7 is used because it is what TOML editors (taplo, Even Better TOML)
+/// support most widely.
+pub fn config_schema() -> Value {
+    let generator = SchemaSettings::draft07()
+        .with_transform(RecursiveTransform(drop_nulls))
+        .into_generator();
+    generator.into_root_schema_for::<DevoboxToml>().to_value()
+}
+
+/// TOML has no null: an unset `Option` is simply a missing key, so editors
+/// must not suggest `null` as a value or default
+fn drop_nulls(schema: &mut Schema) {
+    if let Some(Value::Array(types)) = schema.get_mut("type") {
+        types.retain(|t| t != "null");
+        if types.len() == 1 {
+            let single = types.remove(0);
+            schema.insert("type".to_string(), single);
+        }
+    }
+
+    if let Some(Value::Array(variants)) = schema.get_mut("anyOf") {
+        variants.retain(|v| *v != json!({ "type": "null" }));
+        if variants.len() == 1 {
+            let variants = schema.remove("anyOf").unwrap_or_default();
+            schema.insert("allOf".to_string(), variants);
+        }
+    }
+
+    if schema.get("default").is_some_and(has_null) {
+        schema.remove("default");
+    }
+}
+
+fn has_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.iter().any(has_null),
+        Value::Object(map) => map.values().any(has_null),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_covers_config_types() {
+        let schema = config_schema();
+        let properties = &schema["properties"];
+
+        for section in [
+            "paths",
+            "build",
+            "container",
+            "runtime",
+            "dependencies",
+            "projects",
+            "services",
+            "project",
+        ] {
+            assert!(properties.get(section).is_some(), "{section} ausente");
+        }
+
+        let definitions = &schema["definitions"];
+        // Every service field is optional: a layer may override a single one
+        let service = &definitions["ServiceConfig"];
+        assert!(service.get("required").is_none());
+        for field in ["extends", "enabled", "image", "type", "ports"] {
+            assert!(
+                service["properties"].get(field).is_some(),
+                "{field} ausente"
+            );
+        }
+        assert!(definitions["ListPatch"]["anyOf"].is_array());
+
+        let kind = serde_json::to_string(&definitions["ServiceKind"]).unwrap();
+        assert!(kind.contains("\"generic\"") && kind.contains("\"database\""));
+        assert!(kind.contains("devobox db"));
+    }
+
+    #[test]
+    fn test_schema_has_no_nulls() {
+        let schema = config_schema();
+
+        assert!(!serde_json::to_string(&schema).unwrap().contains("null"));
+        assert_eq!(
+            schema["definitions"]["BuildConfig"]["properties"]["image_name"]["type"],
+            "string"
+        );
+    }
+}
@@ -0,0 +1,1110 @@
+use crate::domain::{ProjectConfig, parse_duration};
+use crate::infra::config::{
+    AppConfig, DEFAULT_DEVOBOX_TOML_NAME, load_app_config_from, service_name_problem,
+};
+use crate::infra::service_resolver::ServiceResolver;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+use toml::de::{DeTable, DeValue};
+
+/// A single problem found in a devobox.toml
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file.display(), line, self.message),
+            None => write!(f, "{}: {}", self.file.display(), self.message),
+        }
+    }
+}
+
+/// Which sections a file may contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigScope {
+    /// ~/.config/devobox/devobox.toml
+    Global,
+    /// A project's devobox.toml (also the local ./devobox.toml)
+    Project,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Str,
+    Bool,
+    UInt,
+    StrList,
+    /// A list, or `{ append = [...] }` / `{ replace = [...] }`
+    StrListPatch,
+    /// Array of tables, walked by its own check
+    Tables,
+    /// Table of string values
+    StrMap,
+}
+
+type Check = fn(&str) -> Option<String>;
+
+struct Field {
+    key: &'static str,
+    kind: Kind,
+    check: Option<Check>,
+}
+
+const fn field(key: &'static str, kind: Kind, check: Option<Check>) -> Field {
+    Field { key, kind, check }
+}
+
+const PATHS_FIELDS: &[Field] = &[
+    field("containerfile", Kind::Str, None),
+    field("mise_toml", Kind::Str, None),
+    field("starship_toml", Kind::Str, None),
+];
+
+const BUILD_FIELDS: &[Field] = &[
+    field("image_name", Kind::Str, Some(image_problem)),
+    field("keep_images", Kind::UInt, None),
+    field("args", Kind::StrMap, None),
+    field("target", Kind::Str, None),
+    field("secrets", Kind::StrMap, None),
+    field("platform", Kind::Str, None),
+];
+
+/// `[build]` of a project file: the global keys plus the project's own image
+const PROJECT_BUILD_FIELDS: &[Field] = &[
+    field("image_name", Kind::Str, Some(image_problem)),
+    field("keep_images", Kind::UInt, None),
+    field("args", Kind::StrMap, None),
+    field("target", Kind::Str, None),
+    field("secrets", Kind::StrMap, None),
+    field("platform", Kind::Str, None),
+    field("containerfile", Kind::Str, None),
+    field("image", Kind::Str, Some(image_problem)),
+];
+
+const CONTAINER_FIELDS: &[Field] = &[
+    field("name", Kind::Str, Some(service_name_problem)),
+    field("workdir", Kind::Str, Some(absolute_path_problem)),
+    field("multiplexer", Kind::Str, Some(multiplexer_problem)),
+];
+
+const RUNTIME_FIELDS: &[Field] = &[
+    field("engine", Kind::Str, Some(engine_problem)),
+    field("api", Kind::Bool, None),
+];
+
+const DEPENDENCIES_FIELDS: &[Field] = &[field("include_projects", Kind::StrList, None)];
+
+const PROJECTS_FIELDS: &[Field] = &[
+    field("roots", Kind::StrList, None),
+    field("max_depth", Kind::UInt, None),
+    field("exclude", Kind::StrList, None),
+    field("namespace_services", Kind::Bool, None),
+];
+
+const PROJECT_FIELDS: &[Field] = &[
+    field("name", Kind::Str, None),
+    field("env", Kind::StrList, Some(env_problem)),
+    field("env_file", Kind::Str, None),
+    field("shell", Kind::Str, Some(shell_problem)),
+    field("startup_command", Kind::Str, None),
+];
+
+const LAYOUT_FIELDS: &[Field] = &[
+    field("template", Kind::Str, None),
+    field("tabs", Kind::Tables, None),
+];
+
+const LAYOUT_TAB_FIELDS: &[Field] = &[
+    field("name", Kind::Str, None),
+    field("focus", Kind::Bool, None),
+    field("split", Kind::Str, Some(split_problem)),
+    field("cwd", Kind::Str, None),
+    field("panes", Kind::Tables, None),
+];
+
+const LAYOUT_PANE_FIELDS: &[Field] = &[
+    field("name", Kind::Str, None),
+    field("command", Kind::Str, None),
+    field("cwd", Kind::Str, None),
+    field("size", Kind::Str, Some(size_problem)),
+    field("focus", Kind::Bool, None),
+    field("split", Kind::Str, Some(split_problem)),
+    field("panes", Kind::Tables, None),
+];
+
+const SERVICE_FIELDS: &[Field] = &[
+    field("extends", Kind::Str, None),
+    field("enabled", Kind::Bool, None),
+    field("override", Kind::Bool, None),
+    field("shared", Kind::Bool, None),
+    field("image", Kind::Str, Some(image_problem)),
+    field("type", Kind::Str, Some(service_type_problem)),
+    field("ports", Kind::StrListPatch, Some(port_problem)),
+    field("env", Kind::StrListPatch, Some(env_problem)),
+    field("volumes", Kind::StrListPatch, Some(volume_problem)),
+    field("healthcheck_command", Kind::Str, None),
+    field("healthcheck_interval", Kind::Str, Some(duration_problem)),
+    field("healthcheck_timeout", Kind::Str, Some(duration_problem)),
+    field("healthcheck_retries", Kind::UInt, None),
+];
+
+/// Validates the global config, the local ./devobox.toml and every project they
+/// include (transitively), collecting all problems instead of stopping at the first
+pub fn validate_config(config_dir: &Path, local_dir: &Path) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = Vec::new();
+
+    let global = config_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    let local = local_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+
+    // Project files may override global services without repeating their image
+    let global_services = global_service_names(&global);
+    let no_services = HashSet::new();
+
+    if global.exists() {
+        pending.push((global.clone(), ConfigScope::Global));
+    }
+    if local.exists() {
+        pending.push((local.clone(), ConfigScope::Project));
+    }
+
+    while let Some((path, scope)) = pending.pop() {
+        if !visited.insert(fs::canonicalize(&path).unwrap_or_else(|_| path.clone())) {
+            continue;
+        }
+
+        let inherited = match scope {
+            ConfigScope::Global => &no_services,
+            ConfigScope::Project => &global_services,
+        };
+        let report = validate_config_file(&path, scope, inherited);
+        issues.extend(report.issues);
+        pending.extend(
+            report
+                .includes
+                .into_iter()
+                .map(|dir| (dir.join(DEFAULT_DEVOBOX_TOML_NAME), ConfigScope::Project)),
+        );
+    }
+
+    // Per-file checks passed; resolve the merged services to catch what only
+    // shows up across files (e.g. an `extends` cycle through the global config,
+    // and a service name claimed by two projects)
+    if issues.is_empty()
+        && let Err(e) = load_app_config_from(config_dir, local_dir)
+            .and_then(|app| ServiceResolver::new(config_dir)?.resolve_workspace(&app, local_dir))
+    {
+        issues.push(ConfigIssue {
+            file: if local.exists() { local } else { global },
+            line: None,
+            message: e.to_string(),
+        });
+    }
+
+    issues.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    issues
+}
+
+fn global_service_names(path: &Path) -> HashSet<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| toml::from_str::<AppConfig>(&content).ok())
+        .and_then(|app| app.services)
+        .map(|services| services.into_keys().collect())
+        .unwrap_or_default()
+}
+
+/// Result of checking one file
+#[derive(Debug, Default)]
+pub struct FileReport {
+    pub issues: Vec<ConfigIssue>,
+    /// Existing project directories listed in `include_projects`
+    pub includes: Vec<PathBuf>,
+}
+
+/// Checks one file; `inherited` names the services defined by earlier config
+/// layers, which the file may override partially
+pub fn validate_config_file(
+    path: &Path,
+    scope: ConfigScope,
+    inherited: &HashSet<String>,
+) -> FileReport {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            return FileReport {
+                issues: vec![ConfigIssue {
+                    file: path.to_path_buf(),
+                    line: None,
+                    message: format!("não foi possível ler o arquivo: {e}"),
+                }],
+                includes: Vec::new(),
+            };
+        }
+    };
+
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let mut checker = FileChecker {
+        file: path,
+        content: &content,
+        base_dir,
+        inherited,
+        report: FileReport::default(),
+    };
+    checker.check(scope);
+    checker.report
+}
+
+struct FileChecker<'a> {
+    file: &'a Path,
+    content: &'a str,
+    base_dir: &'a Path,
+    inherited: &'a HashSet<String>,
+    report: FileReport,
+}
+
+impl FileChecker<'_> {
+    fn issue(&mut self, span: Option<Range<usize>>, message: String) {
+        let line = span.map(|span| line_of(self.content, span.start));
+        self.report.issues.push(ConfigIssue {
+            file: self.file.to_path_buf(),
+            line,
+            message,
+        });
+    }
+
+    fn check(&mut self, scope: ConfigScope) {
+        let root = match DeTable::parse(self.content) {
+            Ok(root) => root,
+            Err(e) => {
+                self.issue(e.span(), format!("TOML inválido: {}", e.message()));
+                return;
+            }
+        };
+
+        for (key, value) in root.get_ref().iter() {
+            let fields = match (key.get_ref().as_ref(), scope) {
+                ("paths", _) => PATHS_FIELDS,
+                ("build", ConfigScope::Global) => BUILD_FIELDS,
+                ("build", ConfigScope::Project) => PROJECT_BUILD_FIELDS,
+                ("container", _) => CONTAINER_FIELDS,
+                ("runtime", _) => RUNTIME_FIELDS,
+                ("dependencies", _) => DEPENDENCIES_FIELDS,
+                ("projects", _) => PROJECTS_FIELDS,
+                ("project", ConfigScope::Project) => PROJECT_FIELDS,
+                ("services", _) => {
+                    self.check_services(value);
+                    continue;
+                }
+                ("layout", ConfigScope::Project) => {
+                    self.check_layout(value);
+                    continue;
+                }
+                (section @ ("project" | "layout"), ConfigScope::Global) => {
+                    self.issue(
+                        Some(key.span()),
+                        format!("seção [{section}] só vale no devobox.toml de um projeto"),
+                    );
+                    continue;
+                }
+                (other, _) => {
+                    self.issue(Some(key.span()), format!("chave desconhecida '{other}'"));
+                    continue;
+                }
+            };
+
+            let section = key.get_ref().to_string();
+            if let Some(table) = self.expect_table(&section, value) {
+                self.check_fields(&section, table, fields);
+            }
+        }
+
+        // Types are checked above; this catches anything the walk doesn't model
+        // so the report never passes a file the loader would reject.
+        if self.report.issues.is_empty() {
+            self.check_deserialize(scope);
+        }
+    }
+
+    fn check_deserialize(&mut self, scope: ConfigScope) {
+        // Project files are also read as AppConfig when used as the local config
+        let result = match scope {
+            ConfigScope::Global => toml::from_str::<AppConfig>(self.content).map(|_| ()),
+            ConfigScope::Project => toml::from_str::<ProjectConfig>(self.content)
+                .and_then(|_| toml::from_str::<AppConfig>(self.content))
+                .map(|_| ()),
+        };
+
+        if let Err(e) = result {
+            self.issue(e.span(), e.message().to_string());
+        }
+    }
+
+    fn expect_table<'v, 'i>(
+        &mut self,
+        path: &str,
+        value: &'v Spanned<DeValue<'i>>,
+    ) -> Option<&'v DeTable<'i>> {
+        match value.get_ref() {
+            DeValue::Table(table) => Some(table),
+            other => {
+                self.issue(
+                    Some(value.span()),
+                    format!(
+                        "'{path}' deve ser uma tabela, encontrado {}",
+                        other.type_str()
+                    ),
+                );
+                None
+            }
+        }
+    }
+
+    fn check_fields(&mut self, section: &str, table: &DeTable<'_>, fields: &[Field]) {
+        for (key, value) in table.iter() {
+            let name = key.get_ref().as_ref();
+            let path = format!("{section}.{name}");
+
+            let Some(field) = fields.iter().find(|f| f.key == name) else {
+                self.issue(Some(key.span()), format!("chave desconhecida '{path}'"));
+                continue;
+            };
+
+            match (field.kind, value.get_ref()) {
+                (Kind::Str, DeValue::String(s)) => {
+                    if let Some(problem) = field.check.and_then(|check| check(s)) {
+                        self.issue(Some(value.span()), format!("{path}: {problem}"));
+                    }
+                }
+                (Kind::Bool, DeValue::Boolean(_)) => {}
+                (Kind::Tables, DeValue::Array(_)) => {}
+                (Kind::UInt, DeValue::Integer(i)) => {
+                    if i.as_str().starts_with('-') {
+                        self.issue(Some(value.span()), format!("{path}: deve ser positivo"));
+                    }
+                }
+                (Kind::StrMap, DeValue::Table(entries)) => {
+                    for (entry, value) in entries.iter() {
+                        let entry_path = format!("{path}.{}", entry.get_ref());
+                        match value.get_ref() {
+                            DeValue::String(s) => {
+                                if let Some(problem) = field.check.and_then(|check| check(s)) {
+                                    self.issue(
+                                        Some(value.span()),
+                                        format!("{entry_path}: {problem}"),
+                                    );
+                                }
+                            }
+                            other => self.issue(
+                                Some(value.span()),
+                                format!(
+                                    "{entry_path}: esperado string, encontrado {}",
+                                    other.type_str()
+                                ),
+                            ),
+                        }
+                    }
+                }
+                (Kind::StrList | Kind::StrListPatch, DeValue::Array(items)) => {
+                    self.check_items(&path, field, items);
+                }
+                (Kind::StrListPatch, DeValue::Table(markers)) => {
+                    for (marker, value) in markers.iter() {
+                        let marker_path = format!("{path}.{}", marker.get_ref());
+                        match (marker.get_ref().as_ref(), value.get_ref()) {
+                            ("append" | "replace", DeValue::Array(items)) => {
+                                self.check_items(&marker_path, field, items);
+                            }
+                            ("append" | "replace", other) => self.issue(
+                                Some(value.span()),
+                                format!(
+                                    "{marker_path}: esperado lista de strings, encontrado {}",
+                                    other.type_str()
+                                ),
+                            ),
+                            (other, _) => self.issue(
+                                Some(marker.span()),
+                                format!(
+                                    "{path}: marcador '{other}' inválido (use append ou replace)"
+                                ),
+                            ),
+                        }
+                    }
+                }
+                (kind, other) => self.issue(
+                    Some(value.span()),
+                    format!(
+                        "{path}: esperado {}, encontrado {}",
+                        kind_name(kind),
+                        other.type_str()
+                    ),
+                ),
+            }
+        }
+    }
+
+    fn check_items(&mut self, path: &str, field: &Field, items: &[Spanned<DeValue<'_>>]) {
+        for item in items {
+            match item.get_ref() {
+                DeValue::String(s) => {
+                    if field.key == "include_projects" {
+                        self.check_include(s, item.span());
+                    } else if let Some(problem) = field.check.and_then(|check| check(s)) {
+                        self.issue(Some(item.span()), format!("{path}: {problem}"));
+                    }
+                }
+                other => self.issue(
+                    Some(item.span()),
+                    format!(
+                        "{path}: itens devem ser strings, encontrado {}",
+                        other.type_str()
+                    ),
+                ),
+            }
+        }
+    }
+
+    fn check_services(&mut self, value: &Spanned<DeValue<'_>>) {
+        let Some(services) = self.expect_table("services", value) else {
+            return;
+        };
+
+        // Service name -> the service it extends, to check targets and cycles
+        let mut extends: HashMap<&str, Option<&str>> = HashMap::new();
+        for (name, service) in services.iter() {
+            let base = match service.get_ref() {
+                DeValue::Table(table) => table.iter().find_map(|(key, value)| {
+                    match (key.get_ref().as_ref(), value.get_ref()) {
+                        ("extends", DeValue::String(base)) => Some(base.as_ref()),
+                        _ => None,
+                    }
+                }),
+                _ => None,
+            };
+            extends.insert(name.get_ref().as_ref(), base);
+        }
+
+        for (name, service) in services.iter() {
+            let service_name = name.get_ref().as_ref();
+            if let Some(problem) = service_name_problem(service_name) {
+                self.issue(Some(name.span()), problem);
+            }
+
+            let section = format!("services.{service_name}");
+            let Some(table) = self.expect_table(&section, service) else {
+                continue;
+            };
+
+            let has = |field: &str| table.iter().any(|(key, _)| key.get_ref() == field);
+            let disabled = table.iter().any(|(key, value)| {
+                key.get_ref() == "enabled" && matches!(value.get_ref(), DeValue::Boolean(false))
+            });
+            let base = extends[service_name];
+
+            // Without an image the service must get one from somewhere else
+            if !has("image")
+                && base.is_none()
+                && !disabled
+                && !self.inherited.contains(service_name)
+            {
+                self.issue(
+                    Some(name.span()),
+                    format!("Serviço '{service_name}' sem campo 'image'"),
+                );
+            }
+
+            if let Some(base) = base {
+                if !extends.contains_key(base) && !self.inherited.contains(base) {
+                    self.issue(
+                        Some(name.span()),
+                        format!("services.{service_name}.extends: serviço '{base}' não existe"),
+                    );
+                } else if let Some(cycle) = extends_cycle(service_name, &extends) {
+                    self.issue(
+                        Some(name.span()),
+                        format!("Ciclo em 'extends': {}", cycle.join(" -> ")),
+                    );
+                }
+            }
+
+            self.check_fields(&section, table, SERVICE_FIELDS);
+        }
+    }
+
+    fn check_layout(&mut self, value: &Spanned<DeValue<'_>>) {
+        let Some(layout) = self.expect_table("layout", value) else {
+            return;
+        };
+        self.check_fields("layout", layout, LAYOUT_FIELDS);
+
+        let mut template = None;
+        let mut tabs = None;
+        for (key, value) in layout.iter() {
+            match (key.get_ref().as_ref(), value.get_ref()) {
+                ("template", DeValue::String(path)) => template = Some((path, value.span())),
+                ("tabs", DeValue::Array(items)) => tabs = Some((items, key.span())),
+                _ => {}
+            }
+        }
+
+        if let (Some(_), Some((_, span))) = (&template, &tabs) {
+            self.issue(
+                Some(span.clone()),
+                "layout: use `template` ou `tabs`, não os dois".to_string(),
+            );
+        }
+        if let Some((path, span)) = template {
+            let file = self.base_dir.join(path.as_ref());
+            if !file.is_file() {
+                self.issue(
+                    Some(span),
+                    format!(
+                        "layout.template: arquivo não encontrado: {}",
+                        file.display()
+                    ),
+                );
+            }
+        }
+        if let Some((items, _)) = tabs {
+            self.check_layout_tables("layout.tabs", items, LAYOUT_TAB_FIELDS);
+        }
+    }
+
+    /// Tabs and panes; panes nest through their own `panes`
+    fn check_layout_tables(
+        &mut self,
+        path: &str,
+        items: &[Spanned<DeValue<'_>>],
+        fields: &[Field],
+    ) {
+        for (i, item) in items.iter().enumerate() {
+            let item_path = format!("{path}[{i}]");
+            let Some(table) = self.expect_table(&item_path, item) else {
+                continue;
+            };
+            self.check_fields(&item_path, table, fields);
+
+            for (key, value) in table.iter() {
+                if let ("panes", DeValue::Array(panes)) = (key.get_ref().as_ref(), value.get_ref())
+                {
+                    self.check_layout_tables(
+                        &format!("{item_path}.panes"),
+                        panes,
+                        LAYOUT_PANE_FIELDS,
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_include(&mut self, relative: &str, span: Range<usize>) {
+        let dir = self.base_dir.join(relative);
+
+        if !dir.is_dir() {
+            self.issue(
+                Some(span),
+                format!(
+                    "dependência '{relative}' não encontrada em {}",
+                    dir.display()
+                ),
+            );
+        } else if !dir.join(DEFAULT_DEVOBOX_TOML_NAME).exists() {
+            self.issue(
+                Some(span),
+                format!("dependência '{relative}' não tem {DEFAULT_DEVOBOX_TOML_NAME}"),
+            );
+        } else {
+            self.report
+                .includes
+                .push(fs::canonicalize(&dir).unwrap_or(dir));
+        }
+    }
+}
+
+fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Str => "string",
+        Kind::Bool => "boolean",
+        Kind::UInt => "inteiro",
+        Kind::StrList => "lista de strings",
+        Kind::StrListPatch => "lista de strings ou { append/replace = [...] }",
+        Kind::Tables => "lista de tabelas",
+        Kind::StrMap => "tabela de strings",
+    }
+}
+
+/// The `extends` chain starting at `name`, if it loops back onto itself
+fn extends_cycle<'a>(
+    name: &'a str,
+    extends: &HashMap<&'a str, Option<&'a str>>,
+) -> Option<Vec<&'a str>> {
+    let mut chain = vec![name];
+    let mut current = name;
+
+    while let Some(Some(base)) = extends.get(current) {
+        chain.push(base);
+        if *base == name {
+            return Some(chain);
+        }
+        if chain[..chain.len() - 1].contains(base) {
+            // Loops, but not through `name`; reported on the services in the loop
+            return None;
+        }
+        current = base;
+    }
+    None
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+fn engine_problem(value: &str) -> Option<String> {
+    match value {
+        "auto" | "podman" | "docker" => None,
+        other => Some(format!(
+            "engine '{other}' inválida (use auto, podman ou docker)"
+        )),
+    }
+}
+
+fn shell_problem(value: &str) -> Option<String> {
+    match value {
+        "bash" | "zsh" | "fish" => None,
+        other => Some(format!("shell '{other}' inválido (use bash, zsh ou fish)")),
+    }
+}
+
+fn multiplexer_problem(value: &str) -> Option<String> {
+    match value {
+        "zellij" | "tmux" | "none" => None,
+        other => Some(format!(
+            "multiplexer '{other}' inválido (use zellij, tmux ou none)"
+        )),
+    }
+}
+
+fn service_type_problem(value: &str) -> Option<String> {
+    match value {
+        "generic" | "database" => None,
+        other => Some(format!("tipo '{other}' inválido (use generic ou database)")),
+    }
+}
+
+fn split_problem(value: &str) -> Option<String> {
+    match value {
+        "vertical" | "horizontal" => None,
+        other => Some(format!(
+            "split '{other}' inválido (use vertical ou horizontal)"
+        )),
+    }
+}
+
+/// Pane sizes are a percentage ("60%") or a fixed number of lines/columns ("10")
+fn size_problem(value: &str) -> Option<String> {
+    let number = value.strip_suffix('%').unwrap_or(value);
+    match number.parse::<u32>() {
+        Ok(n) if n > 0 && (!value.ends_with('%') || n <= 100) => None,
+        _ => Some(format!("tamanho '{value}' inválido (ex.: 60% ou 10)")),
+    }
+}
+
+fn duration_problem(value: &str) -> Option<String> {
+    parse_duration(value)
+        .err()
+        .map(|_| format!("duração '{value}' inválida (ex.: 500ms, 5s, 2m)"))
+}
+
+fn absolute_path_problem(value: &str) -> Option<String> {
+    (!value.starts_with('/')).then(|| format!("'{value}' deve ser um caminho absoluto"))
+}
+
+fn image_problem(image: &str) -> Option<String> {
+    if image.trim().is_empty() {
+        return Some("imagem vazia".to_string());
+    }
+    if image.chars().any(char::is_whitespace) {
+        return Some(format!("imagem '{image}' contém espaços"));
+    }
+
+    // The repository part must be lowercase; tags may not be
+    let without_digest = image.split('@').next().unwrap_or(image);
+    let repository = match without_digest.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => repo,
+        _ => without_digest,
+    };
+    if repository.chars().any(|c| c.is_ascii_uppercase()) {
+        return Some(format!(
+            "imagem '{image}' deve ter o repositório em minúsculas"
+        ));
+    }
+
+    None
+}
+
+fn env_problem(entry: &str) -> Option<String> {
+    let key = entry.split_once('=').map_or(entry, |(key, _)| key);
+    if key.is_empty() || key.chars().any(char::is_whitespace) {
+        return Some(format!("variável '{entry}' inválida (use CHAVE=valor)"));
+    }
+    None
+}
+
+fn volume_problem(volume: &str) -> Option<String> {
+    let mut parts = volume.split(':');
+    let source = parts.next().unwrap_or_default();
+    let target = parts.next();
+
+    match target {
+        _ if source.is_empty() => Some(format!("volume '{volume}' sem origem")),
+        None => Some(format!("volume '{volume}' deve ser origem:destino")),
+        Some(target) if !target.starts_with('/') => Some(format!(
+            "volume '{volume}': destino '{target}' deve ser absoluto"
+        )),
+        Some(_) => None,
+    }
+}
+
+/// Accepts `[ip:][host:]container[/proto]`, where ports may be ranges (`8000-8010`)
+fn port_problem(spec: &str) -> Option<String> {
+    let invalid = || {
+        Some(format!(
+            "porta '{spec}' inválida (ex.: 5432:5432 ou 8080/udp)"
+        ))
+    };
+
+    let (mapping, proto) = match spec.rsplit_once('/') {
+        Some((mapping, proto)) => (mapping, Some(proto)),
+        None => (spec, None),
+    };
+    if proto.is_some_and(|p| !matches!(p, "tcp" | "udp" | "sctp")) {
+        return invalid();
+    }
+
+    // Strip a bracketed IPv6 address before splitting on ':'
+    let mapping = match mapping.strip_prefix('[') {
+        Some(rest) => match rest.split_once("]:") {
+            Some((_, ports)) => ports,
+            None => return invalid(),
+        },
+        None => mapping,
+    };
+
+    let parts: Vec<&str> = mapping.split(':').collect();
+    let (host, container) = match parts.as_slice() {
+        [container] => (None, *container),
+        [host, container] => (Some(*host), *container),
+        // ip:host:container, host may be empty to pick a random port
+        [_ip, host, container] => ((!host.is_empty()).then_some(*host), *container),
+        _ => return invalid(),
+    };
+
+    let valid_range = |range: &str| {
+        let mut bounds = range.splitn(2, '-');
+        bounds.all(|port| port.parse::<u16>().is_ok_and(|p| p > 0))
+    };
+
+    if !valid_range(container) || host.is_some_and(|host| !valid_range(host)) {
+        return invalid();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, content: &str) -> PathBuf {
+        let path = dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_reports_every_problem_with_line() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(
+            temp.path(),
+            r#"[container]
+name = "devobox"
+workdir = "home/dev"
+
+[runtime]
+engine = "lxc"
+colour = true
+
+[services.-pg]
+image = "postgres:16"
+ports = ["5432:5432", "99999:5432"]
+
+[services.cache]
+ports = ["6379"]
+healthcheck_interval = "5 seconds"
+"#,
+        );
+
+        let report = validate_config_file(&path, ConfigScope::Global, &HashSet::new());
+        let lines: Vec<(Option<usize>, &str)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.line, issue.message.as_str()))
+            .collect();
+
+        assert_eq!(report.issues.len(), 7, "{lines:#?}");
+        assert!(lines.contains(&(
+            Some(3),
+            "container.workdir: 'home/dev' deve ser um caminho absoluto"
+        )));
+        assert!(
+            lines
+                .iter()
+                .any(|(line, msg)| *line == Some(6) && msg.contains("engine 'lxc'"))
+        );
+        assert!(lines.contains(&(Some(7), "chave desconhecida 'runtime.colour'")));
+        assert!(
+            lines
+                .iter()
+                .any(|(line, msg)| *line == Some(9) && msg.contains("'-pg'"))
+        );
+        assert!(
+            lines
+                .iter()
+                .any(|(line, msg)| *line == Some(11) && msg.contains("99999:5432"))
+        );
+        assert!(lines.contains(&(Some(13), "Serviço 'cache' sem campo 'image'")));
+        assert!(
+            lines
+                .iter()
+                .any(|(line, msg)| *line == Some(15) && msg.contains("5 seconds"))
+        );
+    }
+
+    #[test]
+    fn test_reports_syntax_errors() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(temp.path(), "[build]\nimage_name = \n");
+
+        let report = validate_config_file(&path, ConfigScope::Global, &HashSet::new());
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].line, Some(2));
+        assert!(report.issues[0].message.starts_with("TOML inválido"));
+    }
+
+    #[test]
+    fn test_type_mismatches() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(
+            temp.path(),
+            "[runtime]\napi = \"yes\"\n\n[build]\nargs = { NODE_VERSION = 20 }\nsecrets = [\"npmrc\"]\n\n[services.pg]\nimage = \"postgres\"\nports = [5432]\n",
+        );
+
+        let report = validate_config_file(&path, ConfigScope::Global, &HashSet::new());
+        let messages: Vec<&str> = report.issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "build.args.NODE_VERSION: esperado string, encontrado integer",
+                "build.secrets: esperado tabela de strings, encontrado array",
+                "runtime.api: esperado boolean, encontrado string",
+                "services.pg.ports: itens devem ser strings, encontrado integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project_section_only_in_projects() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(temp.path(), "[project]\nshell = \"zsh\"\n");
+
+        assert_eq!(
+            validate_config_file(&path, ConfigScope::Global, &HashSet::new())
+                .issues
+                .len(),
+            1
+        );
+        assert!(
+            validate_config_file(&path, ConfigScope::Project, &HashSet::new())
+                .issues
+                .is_empty()
+        );
+
+        // A dev image of its own only makes sense for a project
+        let path = write(temp.path(), "[build]\ncontainerfile = \"Containerfile\"\n");
+        assert_eq!(
+            validate_config_file(&path, ConfigScope::Global, &HashSet::new())
+                .issues
+                .len(),
+            1
+        );
+        assert!(
+            validate_config_file(&path, ConfigScope::Project, &HashSet::new())
+                .issues
+                .is_empty()
+        );
+
+        let path = write(temp.path(), "[project]\nshell = \"tcsh\"\n");
+        let issues = validate_config_file(&path, ConfigScope::Project, &HashSet::new()).issues;
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("shell 'tcsh'"));
+    }
+
+    #[test]
+    fn test_layout_section() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(
+            temp.path(),
+            r#"
+[[layout.tabs]]
+name = "dev"
+split = "diagonal"
+
+[[layout.tabs.panes]]
+command = "npm run dev"
+size = "150%"
+
+[[layout.tabs.panes.panes]]
+colour = "red"
+"#,
+        );
+
+        let messages: Vec<String> =
+            validate_config_file(&path, ConfigScope::Project, &HashSet::new())
+                .issues
+                .into_iter()
+                .map(|issue| issue.message)
+                .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "layout.tabs[0].split: split 'diagonal' inválido (use vertical ou horizontal)",
+                "layout.tabs[0].panes[0].size: tamanho '150%' inválido (ex.: 60% ou 10)",
+                "chave desconhecida 'layout.tabs[0].panes[0].panes[0].colour'",
+            ]
+        );
+
+        let path = write(temp.path(), "[layout]\ntemplate = \"missing.kdl\"\n");
+        let issues = validate_config_file(&path, ConfigScope::Project, &HashSet::new()).issues;
+        assert_eq!(issues.len(), 1);
+        assert!(
+            issues[0]
+                .message
+                .starts_with("layout.template: arquivo não encontrado")
+        );
+
+        fs::write(temp.path().join("missing.kdl"), "").unwrap();
+        assert!(
+            validate_config_file(&path, ConfigScope::Project, &HashSet::new())
+                .issues
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_follows_includes_and_reports_missing_ones() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = temp.path().join("config");
+        let app = temp.path().join("app");
+        let backend = temp.path().join("backend");
+        for dir in [&config_dir, &app, &backend] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        write(&config_dir, "[build]\nimage_name = \"devobox-img\"\n");
+        write(
+            &app,
+            "[dependencies]\ninclude_projects = [\"../backend\", \"../missing\"]\n",
+        );
+        let backend_file = write(&backend, "[services.pg]\nimage = \"Postgres\"\n");
+
+        let issues = validate_config(&config_dir, &app);
+
+        assert_eq!(issues.len(), 2, "{issues:#?}");
+        assert!(issues.iter().any(|i| i.message.contains("'../missing'")));
+        let backend_file = fs::canonicalize(backend_file).unwrap();
+        let image_issue = issues.iter().find(|i| i.file == backend_file).unwrap();
+        assert_eq!(image_issue.line, Some(2));
+    }
+
+    #[test]
+    fn test_service_overrides_extends_and_markers() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = temp.path().join("config");
+        let app = temp.path().join("app");
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::create_dir_all(&app).unwrap();
+
+        write(&config_dir, "[services.pg]\nimage = \"postgres:16\"\n");
+        let local = write(
+            &app,
+            r#"[services.pg]
+ports = { append = ["5433:5432"], prepend = ["x"] }
+
+[services.pg-test]
+extends = "pg"
+
+[services.cache]
+extends = "redis"
+
+[services.a]
+extends = "b"
+[services.b]
+extends = "a"
+"#,
+        );
+
+        let issues = validate_config(&config_dir, &app);
+        let lines: Vec<(Option<usize>, &str)> = issues
+            .iter()
+            .map(|issue| (issue.line, issue.message.as_str()))
+            .collect();
+
+        assert!(issues.iter().all(|i| i.file == local), "{lines:#?}");
+        assert_eq!(issues.len(), 4, "{lines:#?}");
+        assert!(lines.contains(&(
+            Some(2),
+            "services.pg.ports: marcador 'prepend' inválido (use append ou replace)"
+        )));
+        assert!(lines.contains(&(
+            Some(7),
+            "services.cache.extends: serviço 'redis' não existe"
+        )));
+        assert!(lines.contains(&(Some(10), "Ciclo em 'extends': a -> b -> a")));
+        assert!(lines.contains(&(Some(12), "Ciclo em 'extends': b -> a -> b")));
+
+        // The same file passes once the mistakes are fixed
+        write(
+            &app,
+            "[services.pg]\nports = { append = [\"5433:5432\"] }\n\n[services.pg-test]\nextends = \"pg\"\nenabled = true\n",
+        );
+        assert!(validate_config(&config_dir, &app).is_empty());
+    }
+
+    #[test]
+    fn test_port_formats() {
+        for ok in [
+            "5432",
+            "5432:5432",
+            "127.0.0.1:5432:5432",
+            "127.0.0.1::5432",
+            "8000-8010:8000-8010",
+            "53:53/udp",
+            "[::1]:8080:80",
+        ] {
+            assert_eq!(port_problem(ok), None, "{ok}");
+        }
+        for bad in ["", "abc", "0:80", "80:80/icmp", "1:2:3:4", "70000"] {
+            assert!(port_problem(bad).is_some(), "{bad}");
+        }
+    }
+}
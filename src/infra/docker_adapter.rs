@@ -0,0 +1,444 @@
+use crate::domain::traits::ContainerHealthStatus;
+use crate::domain::{
+    BuildOptions, Container, ContainerFilter, ContainerInfo, ContainerRuntime, ContainerSpec,
+    ContainerState, ImageInfo,
+};
+use crate::infra::engine_cli::{
+    binary_available, build_args, container_state, create_args, engine, health_from_status,
+    list_images, parse_label_list, prune_images_args,
+};
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::{info, warn};
+
+/// `ContainerRuntime` backed by the docker CLI (Docker Desktop or rootful Docker)
+#[derive(Debug)]
+pub struct DockerAdapter;
+
+impl DockerAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DockerAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContainerRuntime for DockerAdapter {
+    fn get_container(&self, name: &str) -> Result<Container> {
+        let output = Command::new("docker")
+            .args([
+                "container",
+                "inspect",
+                name,
+                "--format",
+                "{{.State.Running}}",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+            .with_context(|| format!("checando estado do container {name}"))?;
+
+        // docker inspect fails for unknown containers, so one call answers both questions
+        let state = if !output.status.success() {
+            ContainerState::NotCreated
+        } else if String::from_utf8_lossy(&output.stdout).trim() == "true" {
+            ContainerState::Running
+        } else {
+            ContainerState::Stopped
+        };
+
+        Ok(Container::new(name.to_string(), state))
+    }
+
+    fn list_containers(&self, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>> {
+        let output = Command::new("docker")
+            .args(["ps", "-a", "--no-trunc", "--format", "{{json .}}"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .context("listando containers")?;
+
+        if !output.status.success() {
+            bail!(
+                "docker ps retornou status {:?}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let mut infos = parse_docker_ps(&String::from_utf8_lossy(&output.stdout))?;
+        infos.retain(|info| filter.matches(info));
+        Ok(infos)
+    }
+
+    fn get_container_health(&self, name: &str) -> Result<ContainerHealthStatus> {
+        // Unlike podman, docker errors on .State.Health.Status when no healthcheck is set
+        let output = Command::new("docker")
+            .args([
+                "inspect",
+                name,
+                "--format",
+                "{{if .State.Health}}{{.State.Health.Status}}{{end}}",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("checando health de {name}"))?;
+
+        if !output.status.success() {
+            return Ok(ContainerHealthStatus::Unknown);
+        }
+
+        match String::from_utf8_lossy(&output.stdout).trim() {
+            "healthy" => Ok(ContainerHealthStatus::Healthy),
+            "unhealthy" => Ok(ContainerHealthStatus::Unhealthy),
+            "starting" => Ok(ContainerHealthStatus::Starting),
+            "" => Ok(ContainerHealthStatus::NotApplicable),
+            _ => Ok(ContainerHealthStatus::Unknown),
+        }
+    }
+
+    fn start_container(&self, name: &str) -> Result<()> {
+        docker(
+            ["start", name],
+            &format!("iniciando container {name}"),
+            true,
+        )
+    }
+
+    fn stop_container(&self, name: &str) -> Result<()> {
+        docker(["stop", name], &format!("parando container {name}"), true)
+    }
+
+    fn create_container(&self, spec: &ContainerSpec) -> Result<()> {
+        // `--userns keep-id` is podman-only. With docker the image already runs as
+        // the `dev` user built with the host UID/GID, which gives the same ownership.
+        let spec = ContainerSpec {
+            userns: spec.userns.filter(|userns| *userns != "keep-id"),
+            ..spec.clone()
+        };
+
+        docker(
+            create_args(&spec),
+            &format!("criando container {}", spec.name),
+            true,
+        )
+    }
+
+    fn remove_container(&self, name: &str) -> Result<()> {
+        if docker(
+            ["rm", "-f", name],
+            &format!("removendo container {name}"),
+            true,
+        )
+        .is_err()
+        {
+            warn!("  Não foi possível remover {name} (pode não existir)");
+        }
+
+        Ok(())
+    }
+
+    fn exec_command(
+        &self,
+        container: &str,
+        workdir: Option<&Path>,
+        env: &[String],
+        command: &[&str],
+    ) -> Result<()> {
+        let mut cmd = Command::new("docker");
+        cmd.args(["exec", "-it"]);
+
+        if let Some(dir) = workdir {
+            cmd.args(["-w", dir.to_string_lossy().as_ref()]);
+        }
+        for entry in env {
+            cmd.args(["-e", entry]);
+        }
+
+        let status = cmd
+            .arg(container)
+            .args(command)
+            .status()
+            .with_context(|| format!("executando {:?} em {container}", command))?;
+
+        if !status.success() {
+            bail!("comando retornou status {:?}", status);
+        }
+
+        Ok(())
+    }
+
+    fn exec_output(&self, container: &str, command: &[&str]) -> Result<String> {
+        let output = Command::new("docker")
+            .args(["exec", container])
+            .args(command)
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("executando {:?} em {container}", command))?;
+
+        if !output.status.success() {
+            bail!(
+                "comando retornou status {:?}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn container_logs(&self, name: &str, tail: usize) -> Result<String> {
+        let output = Command::new("docker")
+            .args(["logs", "--tail", &tail.to_string(), name])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("lendo logs de {name}"))?;
+
+        if !output.status.success() {
+            bail!(
+                "docker logs retornou status {:?}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let mut logs = String::from_utf8_lossy(&output.stdout).to_string();
+        logs.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok(logs)
+    }
+
+    fn is_command_available(&self, _cmd: &str) -> bool {
+        static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        *AVAILABLE.get_or_init(|| binary_available("docker"))
+    }
+
+    fn build_image(
+        &self,
+        tag: &str,
+        containerfile: &Path,
+        context_dir: &Path,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        let args = build_args(tag, containerfile, context_dir, options);
+
+        // The default Containerfile uses cache mounts, which need BuildKit
+        let status = Command::new("docker")
+            .args(&args)
+            .env("DOCKER_BUILDKIT", "1")
+            .status()
+            .with_context(|| format!("construindo imagem {tag} a partir de {:?}", containerfile))?;
+
+        if !status.success() {
+            bail!(
+                "docker retornou status {:?} (construindo imagem {tag})\nErro: Verifique o output acima",
+                status
+            );
+        }
+
+        Ok(())
+    }
+
+    fn prune_containers(&self) -> Result<()> {
+        docker(
+            ["container", "prune", "-f"],
+            "removendo containers parados",
+            false,
+        )
+    }
+
+    fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        list_images("docker", repository)
+    }
+
+    fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        docker(
+            ["tag", source, target],
+            &format!("marcando {source} como {target}"),
+            true,
+        )
+    }
+
+    fn remove_image(&self, reference: &str) -> Result<()> {
+        docker(
+            ["rmi", reference],
+            &format!("removendo imagem {reference}"),
+            true,
+        )
+    }
+
+    fn prune_images(&self) -> Result<()> {
+        docker(
+            prune_images_args(),
+            "removendo imagens não utilizadas",
+            false,
+        )
+    }
+
+    fn prune_volumes(&self) -> Result<()> {
+        // Since Docker 23 `volume prune` only touches anonymous volumes without --all
+        docker(
+            ["volume", "prune", "--all", "-f"],
+            "removendo volumes órfãos",
+            false,
+        )
+    }
+
+    fn prune_build_cache(&self) -> Result<()> {
+        docker(["builder", "prune", "-af"], "limpando cache de build", true)
+    }
+
+    fn nuke_system(&self) -> Result<()> {
+        info!(" Executando limpeza agressiva (Nuke)...");
+        docker(
+            ["system", "prune", "-a", "--volumes", "-f"],
+            "removendo tudo (imagens, containers, volumes)",
+            false,
+        )?;
+        docker(
+            ["builder", "prune", "-a", "-f"],
+            "limpando cache de build",
+            false,
+        )?;
+        info!(" Limpeza agressiva concluída!");
+
+        Ok(())
+    }
+
+    fn reset_system(&self) -> Result<()> {
+        warn!("  ATENÇÃO: System reset irá DELETAR TUDO!");
+        warn!("   - Todos containers (rodando ou parados)");
+        warn!("   - Todas imagens");
+        warn!("   - Todos volumes (incluindo dados persistentes)");
+        warn!("   - Todo o cache de build");
+        info!("");
+        info!(" Executando system reset...");
+
+        // Docker has no `system reset`: remove every container, then prune the rest
+        let output = Command::new("docker")
+            .args(["ps", "-aq"])
+            .output()
+            .context("listando containers")?;
+        let ids: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+
+        if !ids.is_empty() {
+            let mut args = vec!["rm".to_string(), "-f".to_string()];
+            args.extend(ids);
+            docker(args, "removendo todos os containers", false)?;
+        }
+
+        docker(
+            ["system", "prune", "-a", "--volumes", "-f"],
+            "removendo imagens, redes e volumes",
+            false,
+        )?;
+        docker(
+            ["volume", "prune", "--all", "-f"],
+            "removendo volumes nomeados",
+            false,
+        )?;
+        docker(
+            ["builder", "prune", "-a", "-f"],
+            "limpando cache de build",
+            false,
+        )?;
+
+        info!(" System reset concluído!");
+        info!("   O Docker foi limpo por completo.");
+
+        Ok(())
+    }
+}
+
+fn docker<I, S>(args: I, context: &str, quiet: bool) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    engine("docker", args, context, quiet)
+}
+
+/// Parses `docker ps --format '{{json .}}'`, one object per line with
+/// everything rendered as strings
+fn parse_docker_ps(output: &str) -> Result<Vec<ContainerInfo>> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let entry: Value =
+                serde_json::from_str(line).context("parse da lista de containers do docker")?;
+            let field = |key: &str| entry[key].as_str().unwrap_or_default().to_string();
+
+            let state = container_state(&field("State"));
+            let status = field("Status");
+
+            Ok(ContainerInfo {
+                id: field("ID"),
+                // Docker joins multiple names with commas; the first is the primary one
+                name: field("Names")
+                    .split(',')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                image: field("Image"),
+                health: health_from_status(&state, &status),
+                exit_code: exit_code_from_status(&status),
+                state,
+                ports: field("Ports")
+                    .split(", ")
+                    .filter(|port| !port.is_empty())
+                    .map(String::from)
+                    .collect(),
+                labels: parse_label_list(&field("Labels")),
+                // CreatedAt is a human date ("2024-01-02 10:00:00 +0000 UTC")
+                created: None,
+            })
+        })
+        .collect()
+}
+
+/// Docker only exposes the exit code through `Exited (137) 5 minutes ago`
+fn exit_code_from_status(status: &str) -> Option<i32> {
+    let rest = status.strip_prefix("Exited (")?;
+    rest.split(')').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_docker_ps() {
+        let output = concat!(
+            r#"{"ID":"abc","Names":"pg","Image":"postgres:16","State":"running","Status":"Up 2 minutes (healthy)","Ports":"0.0.0.0:5432->5432/tcp, :::5432->5432/tcp","Labels":"devobox.project=api,other="}"#,
+            "\n",
+            r#"{"ID":"def","Names":"redis","Image":"redis:7","State":"exited","Status":"Exited (137) 5 minutes ago","Ports":"","Labels":""}"#,
+            "\n"
+        );
+
+        let infos = parse_docker_ps(output).unwrap();
+        assert_eq!(infos.len(), 2);
+
+        assert_eq!(infos[0].name, "pg");
+        assert_eq!(infos[0].state, ContainerState::Running);
+        assert_eq!(infos[0].health, ContainerHealthStatus::Healthy);
+        assert_eq!(infos[0].ports.len(), 2);
+        assert_eq!(infos[0].labels.get("devobox.project").unwrap(), "api");
+        assert_eq!(infos[0].exit_code, None);
+
+        assert_eq!(infos[1].state, ContainerState::Stopped);
+        assert_eq!(infos[1].exit_code, Some(137));
+        assert!(infos[1].labels.is_empty());
+    }
+}
@@ -0,0 +1,473 @@
+use crate::domain::traits::ContainerHealthStatus;
+use crate::domain::{
+    BuildOptions, Container, ContainerFilter, ContainerInfo, ContainerRuntime, ContainerSpec,
+    ContainerState, ImageInfo,
+};
+use crate::infra::config::EngineKind;
+use crate::infra::engine_cli::{build_args, create_args, prune_images_args};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// `ContainerRuntime` used by `--dry-run`
+///
+/// Queries go to the real engine; every operation that would change containers,
+/// images or volumes is printed as the equivalent engine command line instead.
+/// Planned state changes are remembered so later queries in the same run see
+/// them (a service "created" here is reported as existing afterwards, an image
+/// "built" is listed).
+#[derive(Debug)]
+pub struct DryRunAdapter {
+    inner: Arc<dyn ContainerRuntime>,
+    program: &'static str,
+    planned: Mutex<Vec<String>>,
+    overlay: Mutex<HashMap<String, ContainerState>>,
+    /// Planned image references: the id they'd point at, `None` once removed
+    images: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl DryRunAdapter {
+    pub fn new(inner: Arc<dyn ContainerRuntime>, engine: EngineKind) -> Self {
+        Self {
+            inner,
+            program: match engine {
+                EngineKind::Docker => "docker",
+                EngineKind::Podman | EngineKind::Auto => "podman",
+            },
+            planned: Mutex::new(Vec::new()),
+            overlay: Mutex::new(HashMap::new()),
+            images: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Command lines planned so far, in the order they were requested
+    pub fn planned(&self) -> Vec<String> {
+        self.planned.lock().unwrap().clone()
+    }
+
+    fn plan<I, S>(&self, args: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut line = self.program.to_string();
+        for arg in args {
+            line.push(' ');
+            line.push_str(&shell_quote(arg.as_ref()));
+        }
+
+        println!("[dry-run] {line}");
+        self.planned.lock().unwrap().push(line);
+    }
+
+    fn set_state(&self, name: &str, state: ContainerState) {
+        self.overlay.lock().unwrap().insert(name.to_string(), state);
+    }
+
+    fn set_image(&self, reference: &str, id: Option<String>) {
+        self.images
+            .lock()
+            .unwrap()
+            .insert(reference.to_string(), id);
+    }
+
+    fn planned_state(&self, name: &str) -> Option<ContainerState> {
+        self.overlay.lock().unwrap().get(name).cloned()
+    }
+
+    fn exec_args(
+        container: &str,
+        workdir: Option<&Path>,
+        env: &[String],
+        command: &[&str],
+    ) -> Vec<String> {
+        let mut args = vec!["exec".to_string(), "-it".to_string()];
+        if let Some(dir) = workdir {
+            args.push("-w".to_string());
+            args.push(dir.to_string_lossy().to_string());
+        }
+        for entry in env {
+            args.push("-e".to_string());
+            args.push(entry.clone());
+        }
+        args.push(container.to_string());
+        args.extend(command.iter().map(|arg| arg.to_string()));
+        args
+    }
+}
+
+impl ContainerRuntime for DryRunAdapter {
+    fn get_container(&self, name: &str) -> Result<Container> {
+        match self.planned_state(name) {
+            Some(state) => Ok(Container::new(name.to_string(), state)),
+            None => self.inner.get_container(name),
+        }
+    }
+
+    fn list_containers(&self, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>> {
+        let mut infos = self.inner.list_containers(filter)?;
+        let overlay = self.overlay.lock().unwrap().clone();
+
+        infos.retain(|info| overlay.get(&info.name) != Some(&ContainerState::NotCreated));
+        for info in infos.iter_mut() {
+            if let Some(state) = overlay.get(&info.name) {
+                info.state = state.clone();
+            }
+        }
+
+        for (name, state) in overlay {
+            if state == ContainerState::NotCreated || infos.iter().any(|i| i.name == name) {
+                continue;
+            }
+
+            let info = ContainerInfo {
+                id: String::new(),
+                name,
+                image: String::new(),
+                state,
+                health: ContainerHealthStatus::NotApplicable,
+                ports: Vec::new(),
+                labels: Default::default(),
+                created: None,
+                exit_code: None,
+            };
+            if filter.matches(&info) {
+                infos.push(info);
+            }
+        }
+
+        Ok(infos)
+    }
+
+    fn get_container_health(&self, name: &str) -> Result<ContainerHealthStatus> {
+        // A container we only pretended to start would never become healthy;
+        // report it healthy so callers don't wait for it.
+        match self.planned_state(name) {
+            Some(ContainerState::Running) => Ok(ContainerHealthStatus::Healthy),
+            Some(_) => Ok(ContainerHealthStatus::Unknown),
+            None => self.inner.get_container_health(name),
+        }
+    }
+
+    fn start_container(&self, name: &str) -> Result<()> {
+        self.plan(["start", name]);
+        self.set_state(name, ContainerState::Running);
+        Ok(())
+    }
+
+    fn stop_container(&self, name: &str) -> Result<()> {
+        self.plan(["stop", name]);
+        self.set_state(name, ContainerState::Stopped);
+        Ok(())
+    }
+
+    fn create_container(&self, spec: &ContainerSpec) -> Result<()> {
+        let spec = match self.program {
+            // Mirrors DockerAdapter, which drops the podman-only userns mode
+            "docker" => ContainerSpec {
+                userns: spec.userns.filter(|userns| *userns != "keep-id"),
+                ..spec.clone()
+            },
+            _ => spec.clone(),
+        };
+
+        self.plan(create_args(&spec));
+        self.set_state(spec.name, ContainerState::Stopped);
+        Ok(())
+    }
+
+    fn remove_container(&self, name: &str) -> Result<()> {
+        self.plan(["rm", "-f", name]);
+        self.set_state(name, ContainerState::NotCreated);
+        Ok(())
+    }
+
+    fn exec_command(
+        &self,
+        container: &str,
+        workdir: Option<&Path>,
+        env: &[String],
+        command: &[&str],
+    ) -> Result<()> {
+        self.plan(Self::exec_args(container, workdir, env, command));
+        Ok(())
+    }
+
+    fn exec_output(&self, container: &str, command: &[&str]) -> Result<String> {
+        self.inner.exec_output(container, command)
+    }
+
+    fn container_logs(&self, name: &str, tail: usize) -> Result<String> {
+        self.inner.container_logs(name, tail)
+    }
+
+    fn is_command_available(&self, cmd: &str) -> bool {
+        self.inner.is_command_available(cmd)
+    }
+
+    fn build_image(
+        &self,
+        tag: &str,
+        containerfile: &Path,
+        context_dir: &Path,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        self.plan(build_args(tag, containerfile, context_dir, options));
+        self.set_image(tag, Some(format!("dry-run-{tag}")));
+        Ok(())
+    }
+
+    fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        let planned = self.images.lock().unwrap().clone();
+        let mut images: Vec<ImageInfo> = self
+            .inner
+            .list_images(repository)?
+            .into_iter()
+            .filter(|image| !planned.contains_key(&image.reference()))
+            .collect();
+
+        for (reference, id) in planned {
+            if let (Some((repo, tag)), Some(id)) = (reference.rsplit_once(':'), id)
+                && repo == repository
+            {
+                images.push(ImageInfo {
+                    repository: repo.to_string(),
+                    tag: tag.to_string(),
+                    id,
+                });
+            }
+        }
+        Ok(images)
+    }
+
+    fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        self.plan(["tag", source, target]);
+        let repository = source.rsplit_once(':').map_or(source, |(repo, _)| repo);
+        let id = self
+            .list_images(repository)?
+            .into_iter()
+            .find(|image| image.reference() == source)
+            .map_or_else(|| format!("dry-run-{source}"), |image| image.id);
+        self.set_image(target, Some(id));
+        Ok(())
+    }
+
+    fn remove_image(&self, reference: &str) -> Result<()> {
+        self.plan(["rmi", reference]);
+        self.set_image(reference, None);
+        Ok(())
+    }
+
+    fn prune_containers(&self) -> Result<()> {
+        self.plan(["container", "prune", "-f"]);
+        Ok(())
+    }
+
+    fn prune_images(&self) -> Result<()> {
+        self.plan(prune_images_args());
+        Ok(())
+    }
+
+    fn prune_volumes(&self) -> Result<()> {
+        match self.program {
+            "docker" => self.plan(["volume", "prune", "--all", "-f"]),
+            _ => self.plan(["volume", "prune", "-f"]),
+        }
+        Ok(())
+    }
+
+    fn prune_build_cache(&self) -> Result<()> {
+        self.plan(["builder", "prune", "-af"]);
+        Ok(())
+    }
+
+    fn nuke_system(&self) -> Result<()> {
+        self.plan(["system", "prune", "-a", "--volumes", "-f"]);
+        self.plan(["builder", "prune", "-a", "-f"]);
+        Ok(())
+    }
+
+    fn reset_system(&self) -> Result<()> {
+        match self.program {
+            "docker" => {
+                self.plan(["rm", "-f", "$(docker ps -aq)"]);
+                self.plan(["system", "prune", "-a", "--volumes", "-f"]);
+                self.plan(["volume", "prune", "--all", "-f"]);
+                self.plan(["builder", "prune", "-a", "-f"]);
+            }
+            _ => self.plan(["system", "reset", "-f"]),
+        }
+        Ok(())
+    }
+}
+
+/// Quotes an argument for display when the shell would otherwise split it
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=,@%+$()".contains(c));
+
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockRuntime;
+
+    fn dry_run(mock: &Arc<MockRuntime>) -> DryRunAdapter {
+        DryRunAdapter::new(mock.clone(), EngineKind::Podman)
+    }
+
+    #[test]
+    fn test_mutations_are_recorded_not_executed() {
+        let mock = Arc::new(MockRuntime::new());
+        mock.add_container("pg", ContainerState::Running);
+        let adapter = dry_run(&mock);
+
+        adapter.stop_container("pg").unwrap();
+        adapter.remove_container("pg").unwrap();
+        adapter.prune_images().unwrap();
+
+        assert_eq!(
+            adapter.planned(),
+            vec![
+                "podman stop pg",
+                "podman rm -f pg",
+                "podman image prune -af --filter 'label!=devobox.dev-image'"
+            ]
+        );
+        // Nothing reached the real engine
+        assert_eq!(mock.get_state("pg"), Some(ContainerState::Running));
+        assert!(mock.get_commands().is_empty());
+    }
+
+    #[test]
+    fn test_build_passes_options() {
+        let mock = Arc::new(MockRuntime::new());
+        let adapter = dry_run(&mock);
+
+        let options = BuildOptions {
+            args: vec!["NODE_VERSION=22".to_string()],
+            target: Some("dev".to_string()),
+            secrets: vec![("npmrc".to_string(), "/home/me/.npmrc".into())],
+            platform: Some("linux/arm64".to_string()),
+            no_cache: true,
+            pull: true,
+        };
+        adapter
+            .build_image(
+                "devobox-img:20261018-143456",
+                Path::new("/cfg/Containerfile"),
+                Path::new("/cfg"),
+                &options,
+            )
+            .unwrap();
+
+        // The very command PodmanAdapter runs, USER_UID/USER_GID included
+        let planned = adapter.planned();
+        assert!(planned[0].starts_with(
+            "podman build --progress=plain -t devobox-img:20261018-143456 -f /cfg/Containerfile \
+             --label devobox.dev-image=true --build-arg USER_UID="
+        ));
+        assert!(planned[0].ends_with(
+            "--build-arg NODE_VERSION=22 --target dev --secret id=npmrc,src=/home/me/.npmrc \
+             --platform linux/arm64 --no-cache --pull /cfg"
+        ));
+    }
+
+    #[test]
+    fn test_exec_passes_env() {
+        let mock = Arc::new(MockRuntime::new());
+        let adapter = dry_run(&mock);
+
+        adapter
+            .exec_command(
+                "devobox",
+                Some(Path::new("/home/dev/code/app")),
+                &["DATABASE_URL=postgres://app@pg/app".to_string()],
+                &["cargo", "test"],
+            )
+            .unwrap();
+
+        assert_eq!(
+            adapter.planned(),
+            vec![
+                "podman exec -it -w /home/dev/code/app -e DATABASE_URL=postgres://app@pg/app devobox cargo test"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_queries_see_planned_state() {
+        let mock = Arc::new(MockRuntime::new());
+        mock.add_container("devobox", ContainerState::Stopped);
+        let adapter = dry_run(&mock);
+
+        assert_eq!(
+            adapter.get_container("devobox").unwrap().state,
+            ContainerState::Stopped
+        );
+        assert!(
+            mock.get_commands()
+                .contains(&"get_container:devobox".to_string())
+        );
+
+        let env = vec!["POSTGRES_PASSWORD=dev pass".to_string()];
+        let spec = ContainerSpec {
+            name: "pg",
+            image: "postgres:16",
+            ports: &[],
+            env: &env,
+            network: None,
+            userns: None,
+            security_opt: None,
+            workdir: None,
+            volumes: &[],
+            extra_args: &[],
+            healthcheck_command: None,
+            healthcheck_interval: None,
+            healthcheck_timeout: None,
+            healthcheck_retries: None,
+            project: None,
+        };
+        adapter.create_container(&spec).unwrap();
+        adapter.start_container("pg").unwrap();
+        adapter.remove_container("devobox").unwrap();
+
+        assert_eq!(
+            adapter.planned()[0],
+            "podman create --name pg -e 'POSTGRES_PASSWORD=dev pass' postgres:16"
+        );
+        assert_eq!(
+            adapter.get_container("pg").unwrap().state,
+            ContainerState::Running
+        );
+        assert_eq!(
+            adapter.get_container_health("pg").unwrap(),
+            ContainerHealthStatus::Healthy
+        );
+
+        let listed = adapter
+            .list_containers(&ContainerFilter::default())
+            .unwrap();
+        let names: Vec<&str> = listed.iter().map(|info| info.name.as_str()).collect();
+        assert_eq!(names, vec!["pg"]);
+    }
+
+    #[test]
+    fn test_docker_commands() {
+        let mock = Arc::new(MockRuntime::new());
+        let adapter = DryRunAdapter::new(mock, EngineKind::Docker);
+
+        adapter.prune_volumes().unwrap();
+
+        assert_eq!(adapter.planned(), vec!["docker volume prune --all -f"]);
+    }
+}
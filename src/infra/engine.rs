@@ -0,0 +1,116 @@
+use crate::domain::ContainerRuntime;
+use crate::infra::config::{EngineKind, RuntimeConfig};
+use crate::infra::dry_run::DryRunAdapter;
+use crate::infra::engine_cli::binary_available;
+use crate::infra::podman_api_adapter::detect_podman_socket;
+use crate::infra::{DockerAdapter, PodmanAdapter, PodmanApiAdapter};
+use anyhow::Result;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::debug;
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Makes every runtime created afterwards plan changes instead of applying them
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Resolves `EngineKind::Auto` to a concrete engine
+///
+/// Podman is preferred; Docker is only picked when podman is missing and docker
+/// answers. With neither installed we stay on Podman so errors mention it.
+pub fn detect_engine() -> EngineKind {
+    resolve_engine(
+        EngineKind::Auto,
+        binary_available("podman"),
+        binary_available("docker"),
+    )
+}
+
+fn resolve_engine(requested: EngineKind, has_podman: bool, has_docker: bool) -> EngineKind {
+    match requested {
+        EngineKind::Auto if !has_podman && has_docker => EngineKind::Docker,
+        EngineKind::Auto => EngineKind::Podman,
+        explicit => explicit,
+    }
+}
+
+/// Builds the container runtime selected by `[runtime] engine`
+pub fn create_runtime(config: &RuntimeConfig) -> Result<Arc<dyn ContainerRuntime>> {
+    let engine = match config.engine.unwrap_or_default() {
+        EngineKind::Auto => detect_engine(),
+        explicit => explicit,
+    };
+
+    debug!("Usando engine de containers: {:?}", engine);
+
+    let runtime: Arc<dyn ContainerRuntime> = match engine {
+        EngineKind::Docker => Arc::new(DockerAdapter::new()),
+        EngineKind::Podman | EngineKind::Auto => podman_runtime(config.api.unwrap_or(true)),
+    };
+
+    if is_dry_run() {
+        return Ok(Arc::new(DryRunAdapter::new(runtime, engine)));
+    }
+
+    Ok(runtime)
+}
+
+/// Prefers the REST API when the podman socket answers, falling back to the CLI
+fn podman_runtime(use_api: bool) -> Arc<dyn ContainerRuntime> {
+    let socket = if use_api {
+        detect_podman_socket()
+    } else {
+        None
+    };
+
+    if let Some(socket) = socket {
+        match PodmanApiAdapter::connect(&socket) {
+            Ok(adapter) => {
+                debug!("Usando API REST do Podman em {:?}", socket);
+                return Arc::new(adapter);
+            }
+            Err(e) => debug!("API do Podman indisponível, usando CLI: {:#}", e),
+        }
+    }
+
+    Arc::new(PodmanAdapter::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_prefers_podman() {
+        assert_eq!(
+            resolve_engine(EngineKind::Auto, true, true),
+            EngineKind::Podman
+        );
+        assert_eq!(
+            resolve_engine(EngineKind::Auto, false, true),
+            EngineKind::Docker
+        );
+        assert_eq!(
+            resolve_engine(EngineKind::Auto, false, false),
+            EngineKind::Podman
+        );
+    }
+
+    #[test]
+    fn test_explicit_engine_is_kept() {
+        assert_eq!(
+            resolve_engine(EngineKind::Docker, true, false),
+            EngineKind::Docker
+        );
+        assert_eq!(
+            resolve_engine(EngineKind::Podman, false, true),
+            EngineKind::Podman
+        );
+    }
+}
@@ -0,0 +1,570 @@
+use crate::domain::traits::ContainerHealthStatus;
+use crate::domain::{
+    BuildOptions, ContainerInfo, ContainerSpec, ContainerState, DEV_IMAGE_LABEL, ImageInfo,
+    PROJECT_LABEL,
+};
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use tracing::debug;
+
+/// Runs a container engine CLI (`podman`, `docker`) and captures stderr when `quiet`
+pub(crate) fn run_engine_cmd<I, S>(
+    program: &str,
+    args: I,
+    context: &str,
+    quiet: bool,
+) -> Result<(ExitStatus, Option<String>)>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut cmd = Command::new(program);
+    let args_vec: Vec<std::ffi::OsString> = args
+        .into_iter()
+        .map(|item| item.as_ref().to_os_string())
+        .collect();
+
+    debug!("Executando {} {:?}", program, args_vec);
+
+    cmd.args(&args_vec);
+
+    if quiet {
+        cmd.stdout(Stdio::null());
+        cmd.stderr(Stdio::piped());
+
+        let mut child = cmd.spawn().with_context(|| context.to_string())?;
+
+        let stderr_result = if let Some(stderr) = child.stderr.take() {
+            use std::io::Read;
+            // Limit to 32KB of stderr to prevent OOM on massive failure logs
+            let mut buffer = Vec::new();
+            let _ = stderr.take(32 * 1024).read_to_end(&mut buffer);
+            Some(String::from_utf8_lossy(&buffer).to_string())
+        } else {
+            None
+        };
+
+        let status = child.wait().with_context(|| context.to_string())?;
+
+        let stderr = if !status.success() {
+            stderr_result
+        } else {
+            None
+        };
+        Ok((status, stderr))
+    } else {
+        let status = cmd.status().with_context(|| context.to_string())?;
+        Ok((status, None))
+    }
+}
+
+/// Runs an engine command and turns a non-zero exit into an error
+pub(crate) fn engine<I, S>(program: &str, args: I, context: &str, quiet: bool) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let (status, stderr) = run_engine_cmd(program, args, context, quiet)?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        let error_msg = stderr.unwrap_or_else(|| "Verifique o output acima".to_string());
+        bail!(
+            "{} retornou status {:?} ({})\nErro: {}",
+            program,
+            status,
+            context,
+            error_msg.trim()
+        );
+    }
+}
+
+/// Builds the `create` arguments shared by podman and docker
+pub(crate) fn create_args(spec: &ContainerSpec) -> Vec<String> {
+    let mut args: Vec<String> = vec!["create".into(), "--name".into(), spec.name.into()];
+
+    if let Some(net) = spec.network {
+        args.push("--network".into());
+        args.push(net.into());
+    }
+    if let Some(userns) = spec.userns {
+        args.push("--userns".into());
+        args.push(userns.into());
+    }
+    if let Some(sec) = spec.security_opt {
+        args.push("--security-opt".into());
+        args.push(sec.into());
+    }
+    if let Some(wd) = spec.workdir {
+        args.push("-w".into());
+        args.push(wd.into());
+    }
+
+    for port in spec.ports {
+        args.push("-p".into());
+        args.push(port.clone());
+    }
+
+    for env in spec.env {
+        args.push("-e".into());
+        args.push(env.clone());
+    }
+
+    for volume in spec.volumes {
+        args.push("-v".into());
+        args.push(volume.clone());
+    }
+
+    if let Some(hc_cmd) = spec.healthcheck_command {
+        args.push("--health-cmd".into());
+        args.push(hc_cmd.into());
+    }
+    if let Some(hc_interval) = spec.healthcheck_interval {
+        args.push("--health-interval".into());
+        args.push(hc_interval.into());
+    }
+    if let Some(hc_timeout) = spec.healthcheck_timeout {
+        args.push("--health-timeout".into());
+        args.push(hc_timeout.into());
+    }
+    if let Some(hc_retries) = spec.healthcheck_retries {
+        args.push("--health-retries".into());
+        args.push(hc_retries.to_string());
+    }
+
+    if let Some(project) = spec.project {
+        args.push("--label".into());
+        args.push(format!("{PROJECT_LABEL}={project}"));
+    }
+
+    for extra in spec.extra_args {
+        args.push((*extra).into());
+    }
+
+    args.push(spec.image.into());
+    args
+}
+
+/// `--label` value marking a dev image at build time
+fn dev_image_label() -> String {
+    format!("{DEV_IMAGE_LABEL}=true")
+}
+
+/// `build` arguments of a dev image, shared by podman, docker and the dry run
+pub(crate) fn build_args(
+    tag: &str,
+    containerfile: &Path,
+    context_dir: &Path,
+    options: &BuildOptions,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "build".into(),
+        "--progress=plain".into(),
+        "-t".into(),
+        tag.into(),
+        "-f".into(),
+        containerfile.to_string_lossy().into_owned(),
+        "--label".into(),
+        dev_image_label(),
+    ];
+
+    if let Some((uid, gid)) = get_current_user_id() {
+        debug!("Usando UID={} GID={} para build", uid, gid);
+        args.push("--build-arg".into());
+        args.push(format!("USER_UID={}", uid));
+        args.push("--build-arg".into());
+        args.push(format!("USER_GID={}", gid));
+    }
+
+    // After USER_UID/USER_GID, so the config can override them
+    args.extend(build_options_args(options));
+    args.push(context_dir.to_string_lossy().into_owned());
+    args
+}
+
+fn build_options_args(options: &BuildOptions) -> Vec<String> {
+    let mut args = Vec::new();
+    for arg in &options.args {
+        args.extend(["--build-arg".to_string(), arg.clone()]);
+    }
+    if let Some(target) = &options.target {
+        args.extend(["--target".to_string(), target.clone()]);
+    }
+    for (id, src) in &options.secrets {
+        args.extend([
+            "--secret".to_string(),
+            format!("id={id},src={}", src.display()),
+        ]);
+    }
+    if let Some(platform) = &options.platform {
+        args.extend(["--platform".to_string(), platform.clone()]);
+    }
+    if options.no_cache {
+        args.push("--no-cache".to_string());
+    }
+    if options.pull {
+        args.push("--pull".to_string());
+    }
+    args
+}
+
+/// `image prune` arguments that spare dev images, whose versions are kept by
+/// the retention policy instead
+pub(crate) fn prune_images_args() -> Vec<String> {
+    vec![
+        "image".into(),
+        "prune".into(),
+        "-af".into(),
+        "--filter".into(),
+        format!("label!={DEV_IMAGE_LABEL}"),
+    ]
+}
+
+/// Lists the tags of `repository` with `<program> images`
+pub(crate) fn list_images(program: &str, repository: &str) -> Result<Vec<ImageInfo>> {
+    let output = Command::new(program)
+        .args([
+            "images",
+            "--format",
+            "{{.Repository}} {{.Tag}} {{.ID}}",
+            repository,
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("listando imagens de {repository}"))?;
+
+    if !output.status.success() {
+        bail!(
+            "{program} images retornou status {:?}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(parse_image_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parses `repository tag id` lines, skipping untagged images
+pub(crate) fn parse_image_list(output: &str) -> Vec<ImageInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (repository, tag, id) = (fields.next()?, fields.next()?, fields.next()?);
+            if tag == "<none>" {
+                return None;
+            }
+            Some(ImageInfo {
+                repository: repository
+                    .strip_prefix("localhost/")
+                    .unwrap_or(repository)
+                    .to_string(),
+                tag: tag.to_string(),
+                id: id.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Checks whether an engine binary answers `--version`
+pub(crate) fn binary_available(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Parses the libpod container list (`podman ps -a --format json` or the
+/// `/containers/json` endpoint, which share the same shape)
+pub(crate) fn parse_podman_ps(json: &[u8]) -> Result<Vec<ContainerInfo>> {
+    let entries: Vec<Value> =
+        serde_json::from_slice(json).context("parse da lista de containers do podman")?;
+
+    Ok(entries.iter().map(podman_ps_entry).collect())
+}
+
+fn podman_ps_entry(entry: &Value) -> ContainerInfo {
+    let state = container_state(entry["State"].as_str().unwrap_or(""));
+    let status = entry["Status"].as_str().unwrap_or("");
+
+    let ports = entry["Ports"]
+        .as_array()
+        .map(|ports| {
+            ports
+                .iter()
+                .map(|p| {
+                    format!(
+                        "{}:{}/{}",
+                        p["host_port"],
+                        p["container_port"],
+                        p["protocol"].as_str().unwrap_or("tcp")
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let labels = entry["Labels"]
+        .as_object()
+        .map(|labels| {
+            labels
+                .iter()
+                .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ContainerInfo {
+        id: entry["Id"].as_str().unwrap_or_default().to_string(),
+        name: entry["Names"][0].as_str().unwrap_or_default().to_string(),
+        image: entry["Image"].as_str().unwrap_or_default().to_string(),
+        health: podman_health(&state, status),
+        exit_code: if state == ContainerState::Running {
+            None
+        } else {
+            entry["ExitCode"].as_i64().map(|code| code as i32)
+        },
+        state,
+        ports,
+        labels,
+        created: entry["Created"].as_i64(),
+    }
+}
+
+/// Maps an engine state string (`running`, `exited`, `created`...) to our states
+pub(crate) fn container_state(state: &str) -> ContainerState {
+    if state.eq_ignore_ascii_case("running") {
+        ContainerState::Running
+    } else {
+        ContainerState::Stopped
+    }
+}
+
+/// Extracts the health from a human status like `Up 2 minutes (healthy)`
+pub(crate) fn health_from_status(state: &ContainerState, status: &str) -> ContainerHealthStatus {
+    if *state != ContainerState::Running {
+        return ContainerHealthStatus::NotApplicable;
+    }
+
+    // "(health: starting)" is docker's spelling, "(starting)" podman's
+    if status.contains("(unhealthy)") {
+        ContainerHealthStatus::Unhealthy
+    } else if status.contains("(healthy)") {
+        ContainerHealthStatus::Healthy
+    } else if status.contains("starting)") {
+        ContainerHealthStatus::Starting
+    } else {
+        ContainerHealthStatus::NotApplicable
+    }
+}
+
+/// Podman (`ps --format json` and libpod's `/containers/json`) reports the bare
+/// health in `Status`: `healthy`, `unhealthy`, `starting` or empty
+fn podman_health(state: &ContainerState, status: &str) -> ContainerHealthStatus {
+    if *state != ContainerState::Running {
+        return ContainerHealthStatus::NotApplicable;
+    }
+
+    match status {
+        "healthy" => ContainerHealthStatus::Healthy,
+        "unhealthy" => ContainerHealthStatus::Unhealthy,
+        "starting" => ContainerHealthStatus::Starting,
+        // Older releases printed the human status there
+        other => health_from_status(state, other),
+    }
+}
+
+/// Parses `key=value,key2=value2` label strings as printed by docker
+pub(crate) fn parse_label_list(labels: &str) -> BTreeMap<String, String> {
+    labels
+        .split(',')
+        .filter(|label| !label.is_empty())
+        .map(|label| match label.split_once('=') {
+            Some((k, v)) => (k.to_string(), v.to_string()),
+            None => (label.to_string(), String::new()),
+        })
+        .collect()
+}
+
+fn get_current_user_id() -> Option<(String, String)> {
+    let uid = Command::new("id")
+        .arg("-u")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())?;
+    let gid = Command::new("id")
+        .arg("-g")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())?;
+    Some((uid, gid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_args_order() {
+        let ports = vec!["5432:5432".to_string()];
+        let env = vec!["A=1".to_string()];
+        let spec = ContainerSpec {
+            name: "pg",
+            image: "postgres:16",
+            ports: &ports,
+            env: &env,
+            network: None,
+            userns: Some("keep-id"),
+            security_opt: None,
+            workdir: None,
+            volumes: &[],
+            extra_args: &["-it"],
+            healthcheck_command: Some("pg_isready"),
+            healthcheck_interval: None,
+            healthcheck_timeout: None,
+            healthcheck_retries: Some(3),
+            project: Some("api"),
+        };
+
+        let args = create_args(&spec);
+
+        assert_eq!(&args[..3], &["create", "--name", "pg"]);
+        assert!(args.windows(2).any(|w| w == ["--userns", "keep-id"]));
+        assert!(args.windows(2).any(|w| w == ["-p", "5432:5432"]));
+        assert!(args.windows(2).any(|w| w == ["--health-retries", "3"]));
+        assert!(
+            args.windows(2)
+                .any(|w| w == ["--label", "devobox.project=api"])
+        );
+        // Image must come last, after any extra args
+        assert_eq!(args.last().unwrap(), "postgres:16");
+        assert_eq!(args[args.len() - 2], "-it");
+    }
+
+    #[test]
+    fn test_parse_podman_ps() {
+        let json = br#"[
+            {
+                "AutoRemove": false,
+                "Command": ["postgres"],
+                "Created": 1700000000,
+                "CreatedAt": "2023-11-14 22:13:20.000000000 +0000 UTC",
+                "Exited": false,
+                "ExitedAt": -62135596800,
+                "ExitCode": 0,
+                "Id": "abc123",
+                "Image": "docker.io/library/postgres:16",
+                "ImageID": "0a1b2c",
+                "IsInfra": false,
+                "Labels": {"devobox.project": "api"},
+                "Mounts": [],
+                "Names": ["pg"],
+                "Namespaces": {},
+                "Networks": [],
+                "Pid": 4242,
+                "Pod": "",
+                "PodName": "",
+                "Ports": [{"host_ip": "", "container_port": 5432, "host_port": 5432, "range": 1, "protocol": "tcp"}],
+                "Size": null,
+                "StartedAt": 1700000005,
+                "State": "running",
+                "Status": "healthy"
+            },
+            {
+                "AutoRemove": false,
+                "Command": ["redis-server"],
+                "Created": 1700000100,
+                "CreatedAt": "2023-11-14 22:15:00.000000000 +0000 UTC",
+                "Exited": true,
+                "ExitedAt": 1700000400,
+                "ExitCode": 137,
+                "Id": "def456",
+                "Image": "docker.io/library/redis:7",
+                "ImageID": "3d4e5f",
+                "IsInfra": false,
+                "Labels": null,
+                "Mounts": [],
+                "Names": ["redis"],
+                "Namespaces": {},
+                "Networks": [],
+                "Pid": 0,
+                "Pod": "",
+                "PodName": "",
+                "Ports": null,
+                "Size": null,
+                "StartedAt": 1700000105,
+                "State": "exited",
+                "Status": ""
+            },
+            {
+                "Created": 1700000200,
+                "ExitCode": 0,
+                "Id": "ghi789",
+                "Image": "docker.io/library/rabbitmq:3",
+                "Labels": {},
+                "Names": ["mq"],
+                "Ports": null,
+                "State": "running",
+                "Status": "starting"
+            },
+            {
+                "Created": 1700000300,
+                "ExitCode": 0,
+                "Id": "jkl012",
+                "Image": "localhost/devobox-img:latest",
+                "Labels": {},
+                "Names": ["devobox"],
+                "Ports": null,
+                "State": "running",
+                "Status": ""
+            }
+        ]"#;
+
+        let infos = parse_podman_ps(json).unwrap();
+        assert_eq!(infos.len(), 4);
+
+        let pg = &infos[0];
+        assert_eq!(pg.name, "pg");
+        assert_eq!(pg.state, ContainerState::Running);
+        assert_eq!(pg.health, ContainerHealthStatus::Healthy);
+        assert_eq!(pg.ports, vec!["5432:5432/tcp"]);
+        assert_eq!(pg.labels.get("devobox.project").unwrap(), "api");
+        assert_eq!(pg.created, Some(1700000000));
+        assert_eq!(pg.exit_code, None);
+
+        let redis = &infos[1];
+        assert_eq!(redis.state, ContainerState::Stopped);
+        assert_eq!(redis.health, ContainerHealthStatus::NotApplicable);
+        assert!(redis.ports.is_empty());
+        assert_eq!(redis.exit_code, Some(137));
+
+        assert_eq!(infos[2].health, ContainerHealthStatus::Starting);
+        // No healthcheck
+        assert_eq!(infos[3].health, ContainerHealthStatus::NotApplicable);
+    }
+
+    #[test]
+    fn test_health_from_status() {
+        let running = ContainerState::Running;
+        assert_eq!(
+            health_from_status(&running, "Up 3 seconds (health: starting)"),
+            ContainerHealthStatus::Starting
+        );
+        assert_eq!(
+            health_from_status(&running, "Up 1 minute (unhealthy)"),
+            ContainerHealthStatus::Unhealthy
+        );
+        assert_eq!(
+            health_from_status(&running, "Up 1 minute"),
+            ContainerHealthStatus::NotApplicable
+        );
+    }
+}
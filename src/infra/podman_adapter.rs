@@ -1,10 +1,17 @@
 use crate::domain::traits::ContainerHealthStatus;
-use crate::domain::{Container, ContainerRuntime, ContainerSpec, ContainerState};
+use crate::domain::{
+    BuildOptions, Container, ContainerFilter, ContainerInfo, ContainerRuntime, ContainerSpec,
+    ContainerState, ImageInfo,
+};
+use crate::infra::engine_cli::{
+    binary_available, build_args, create_args, engine, list_images, parse_podman_ps,
+    prune_images_args,
+};
 use anyhow::{Context, Result, bail};
 use std::ffi::OsStr;
 use std::path::Path;
-use std::process::{Command, ExitStatus, Stdio};
-use tracing::{debug, info, warn};
+use std::process::{Command, Stdio};
+use tracing::{info, warn};
 
 #[derive(Debug)]
 pub struct PodmanAdapter;
@@ -27,6 +34,27 @@ impl ContainerRuntime for PodmanAdapter {
         Ok(Container::new(name.to_string(), state))
     }
 
+    fn list_containers(&self, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>> {
+        let output = Command::new("podman")
+            .args(["ps", "-a", "--format", "json"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .context("listando containers")?;
+
+        if !output.status.success() {
+            bail!(
+                "podman ps retornou status {:?}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let mut infos = parse_podman_ps(&output.stdout)?;
+        infos.retain(|info| filter.matches(info));
+        Ok(infos)
+    }
+
     fn get_container_health(&self, name: &str) -> Result<ContainerHealthStatus> {
         let output = Command::new("podman")
             .args(["inspect", name, "--format", "{{.State.Health.Status}}"])
@@ -73,64 +101,11 @@ impl ContainerRuntime for PodmanAdapter {
     }
 
     fn create_container(&self, spec: &ContainerSpec) -> Result<()> {
-        let mut args: Vec<String> = vec!["create".into(), "--name".into(), spec.name.into()];
-
-        if let Some(net) = spec.network {
-            args.push("--network".into());
-            args.push(net.into());
-        }
-        if let Some(userns) = spec.userns {
-            args.push("--userns".into());
-            args.push(userns.into());
-        }
-        if let Some(sec) = spec.security_opt {
-            args.push("--security-opt".into());
-            args.push(sec.into());
-        }
-        if let Some(wd) = spec.workdir {
-            args.push("-w".into());
-            args.push(wd.into());
-        }
-
-        for port in spec.ports {
-            args.push("-p".into());
-            args.push(port.clone());
-        }
-
-        for env in spec.env {
-            args.push("-e".into());
-            args.push(env.clone());
-        }
-
-        for volume in spec.volumes {
-            args.push("-v".into());
-            args.push(volume.clone());
-        }
-
-        if let Some(hc_cmd) = spec.healthcheck_command {
-            args.push("--health-cmd".into());
-            args.push(hc_cmd.into());
-        }
-        if let Some(hc_interval) = spec.healthcheck_interval {
-            args.push("--health-interval".into());
-            args.push(hc_interval.into());
-        }
-        if let Some(hc_timeout) = spec.healthcheck_timeout {
-            args.push("--health-timeout".into());
-            args.push(hc_timeout.into());
-        }
-        if let Some(hc_retries) = spec.healthcheck_retries {
-            args.push("--health-retries".into());
-            args.push(hc_retries.to_string());
-        }
-
-        for extra in spec.extra_args {
-            args.push((*extra).into());
-        }
-
-        args.push(spec.image.into());
-
-        podman(args, &format!("criando container {}", spec.name), true)
+        podman(
+            create_args(spec),
+            &format!("criando container {}", spec.name),
+            true,
+        )
     }
 
     fn remove_container(&self, name: &str) -> Result<()> {
@@ -147,11 +122,12 @@ impl ContainerRuntime for PodmanAdapter {
         Ok(())
     }
 
-    fn exec_shell(
+    fn exec_command(
         &self,
         container: &str,
         workdir: Option<&Path>,
-        session_name: Option<&str>,
+        env: &[String],
+        command: &[&str],
     ) -> Result<()> {
         let mut cmd = Command::new("podman");
         cmd.args(["exec", "-it"]);
@@ -159,56 +135,77 @@ impl ContainerRuntime for PodmanAdapter {
         if let Some(dir) = workdir {
             cmd.args(["-w", dir.to_string_lossy().as_ref()]);
         }
-
-        // Use provided session name or fallback to a default generic name
-        let zellij_session = session_name.unwrap_or("devobox-default");
-
-        cmd.arg(container)
-            .args(["zellij", "attach", "--create", zellij_session]);
+        for entry in env {
+            cmd.args(["-e", entry]);
+        }
 
         let status = cmd
+            .arg(container)
+            .args(command)
             .status()
-            .with_context(|| format!("abrindo shell em {container}"))?;
+            .with_context(|| format!("executando {:?} em {container}", command))?;
 
         if !status.success() {
-            bail!("shell retornou status {:?}", status);
+            bail!("comando retornou status {:?}", status);
         }
 
         Ok(())
     }
 
-    fn is_command_available(&self, _cmd: &str) -> bool {
-        static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
-        *AVAILABLE.get_or_init(|| {
-            Command::new("podman")
-                .arg("--version")
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .status()
-                .map(|s| s.success())
-                .unwrap_or(false)
-        })
+    fn exec_output(&self, container: &str, command: &[&str]) -> Result<String> {
+        let output = Command::new("podman")
+            .args(["exec", container])
+            .args(command)
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("executando {:?} em {container}", command))?;
+
+        if !output.status.success() {
+            bail!(
+                "comando retornou status {:?}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     }
 
-    fn build_image(&self, tag: &str, containerfile: &Path, context_dir: &Path) -> Result<()> {
-        let mut args: Vec<std::ffi::OsString> = vec![
-            "build".into(),
-            "--progress=plain".into(),
-            "-t".into(),
-            tag.into(),
-            "-f".into(),
-            containerfile.as_os_str().into(),
-        ];
-
-        if let Some((uid, gid)) = get_current_user_id() {
-            debug!("Usando UID={} GID={} para build", uid, gid);
-            args.push("--build-arg".into());
-            args.push(format!("USER_UID={}", uid).into());
-            args.push("--build-arg".into());
-            args.push(format!("USER_GID={}", gid).into());
+    fn container_logs(&self, name: &str, tail: usize) -> Result<String> {
+        let output = Command::new("podman")
+            .args(["logs", "--tail", &tail.to_string(), name])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("lendo logs de {name}"))?;
+
+        if !output.status.success() {
+            bail!(
+                "podman logs retornou status {:?}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
         }
 
-        args.push(context_dir.as_os_str().into());
+        // Containers write to both streams; podman splits them back apart
+        let mut logs = String::from_utf8_lossy(&output.stdout).to_string();
+        logs.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok(logs)
+    }
+
+    fn is_command_available(&self, _cmd: &str) -> bool {
+        static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        *AVAILABLE.get_or_init(|| binary_available("podman"))
+    }
+
+    fn build_image(
+        &self,
+        tag: &str,
+        containerfile: &Path,
+        context_dir: &Path,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        let args = build_args(tag, containerfile, context_dir, options);
 
         podman(
             args,
@@ -225,9 +222,29 @@ impl ContainerRuntime for PodmanAdapter {
         )
     }
 
+    fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        list_images("podman", repository)
+    }
+
+    fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        podman(
+            ["tag", source, target],
+            &format!("marcando {source} como {target}"),
+            true,
+        )
+    }
+
+    fn remove_image(&self, reference: &str) -> Result<()> {
+        podman(
+            ["rmi", reference],
+            &format!("removendo imagem {reference}"),
+            true,
+        )
+    }
+
     fn prune_images(&self) -> Result<()> {
         podman(
-            ["image", "prune", "-af"],
+            prune_images_args(),
             "removendo imagens não utilizadas",
             false,
         )
@@ -325,81 +342,10 @@ fn container_exists(name: &str) -> Result<bool> {
     Ok(result.is_ok())
 }
 
-fn run_podman_cmd<I, S>(args: I, context: &str, quiet: bool) -> Result<(ExitStatus, Option<String>)>
-where
-    I: IntoIterator<Item = S>,
-    S: AsRef<OsStr>,
-{
-    let mut cmd = Command::new("podman");
-    let args_vec: Vec<std::ffi::OsString> = args
-        .into_iter()
-        .map(|item| item.as_ref().to_os_string())
-        .collect();
-
-    debug!("Executando podman {:?}", args_vec);
-
-    cmd.args(&args_vec);
-
-    if quiet {
-        cmd.stdout(Stdio::null());
-        cmd.stderr(Stdio::piped());
-
-        let mut child = cmd.spawn().with_context(|| context.to_string())?;
-
-        let stderr_result = if let Some(stderr) = child.stderr.take() {
-            use std::io::Read;
-            // Limit to 32KB of stderr to prevent OOM on massive failure logs
-            let mut buffer = Vec::new();
-            let _ = stderr.take(32 * 1024).read_to_end(&mut buffer);
-            Some(String::from_utf8_lossy(&buffer).to_string())
-        } else {
-            None
-        };
-
-        let status = child.wait().with_context(|| context.to_string())?;
-
-        let stderr = if !status.success() {
-            stderr_result
-        } else {
-            None
-        };
-        Ok((status, stderr))
-    } else {
-        let status = cmd.status().with_context(|| context.to_string())?;
-        Ok((status, None))
-    }
-}
-
 fn podman<I, S>(args: I, context: &str, quiet: bool) -> Result<()>
 where
     I: IntoIterator<Item = S>,
     S: AsRef<OsStr>,
 {
-    let (status, stderr) = run_podman_cmd(args, context, quiet)?;
-
-    if status.success() {
-        Ok(())
-    } else {
-        let error_msg = stderr.unwrap_or_else(|| "Verifique o output acima".to_string());
-        bail!(
-            "podman retornou status {:?} ({})\nErro: {}",
-            status,
-            context,
-            error_msg.trim()
-        );
-    }
-}
-
-fn get_current_user_id() -> Option<(String, String)> {
-    let uid = Command::new("id")
-        .arg("-u")
-        .output()
-        .ok()
-        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())?;
-    let gid = Command::new("id")
-        .arg("-g")
-        .output()
-        .ok()
-        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())?;
-    Some((uid, gid))
+    engine("podman", args, context, quiet)
 }
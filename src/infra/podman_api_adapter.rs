@@ -0,0 +1,508 @@
+use crate::domain::traits::ContainerHealthStatus;
+use crate::domain::{
+    BuildOptions, Container, ContainerFilter, ContainerInfo, ContainerRuntime, ContainerSpec,
+    ContainerState, ImageInfo,
+};
+use crate::infra::PodmanAdapter;
+use crate::infra::engine_cli::parse_podman_ps;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const API_PREFIX: &str = "/v4.0.0/libpod";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+// `stop` waits for the container's stop timeout (10s by default) before answering
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Detects Podman socket strictly following Linux standards
+/// Priority: Env Var -> XDG Rootless -> UID Rootless -> System Rootful
+pub fn detect_podman_socket() -> Option<PathBuf> {
+    if let Ok(sock) = std::env::var("PODMAN_SOCK") {
+        let path = PathBuf::from(sock);
+        if path.exists()
+            && std::fs::metadata(&path)
+                .map(|m| m.file_type().is_socket())
+                .unwrap_or(false)
+        {
+            return Some(path);
+        }
+    }
+
+    let uid = std::fs::metadata("/proc/self").map(|m| m.uid()).ok()?;
+
+    let candidates = vec![
+        std::env::var("XDG_RUNTIME_DIR")
+            .ok()
+            .map(|dir| PathBuf::from(dir).join("podman/podman.sock")),
+        Some(PathBuf::from(format!(
+            "/run/user/{}/podman/podman.sock",
+            uid
+        ))),
+        Some(PathBuf::from("/run/podman/podman.sock")),
+    ];
+
+    candidates.into_iter().flatten().find(|path| {
+        std::fs::metadata(path)
+            .map(|m| m.file_type().is_socket())
+            .unwrap_or(false)
+    })
+}
+
+/// `ContainerRuntime` that queries and controls containers through the libpod
+/// REST API, avoiding one `podman` process per call.
+///
+/// Interactive and streaming operations (exec, build, prune) still go through
+/// the CLI adapter.
+#[derive(Debug)]
+pub struct PodmanApiAdapter {
+    socket: PathBuf,
+    cli: PodmanAdapter,
+}
+
+#[derive(Debug)]
+struct HttpResponse {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl PodmanApiAdapter {
+    /// Connects to the API socket, failing if the service doesn't answer `_ping`
+    pub fn connect(socket: &Path) -> Result<Self> {
+        let adapter = Self {
+            socket: socket.to_path_buf(),
+            cli: PodmanAdapter::new(),
+        };
+
+        let response = adapter.request("GET", "/_ping")?;
+        if response.status != 200 {
+            bail!(
+                "API do Podman em {:?} respondeu {} ao _ping",
+                socket,
+                response.status
+            );
+        }
+
+        Ok(adapter)
+    }
+
+    fn request(&self, method: &str, path: &str) -> Result<HttpResponse> {
+        debug!("API Podman: {} {}", method, path);
+
+        let mut stream = UnixStream::connect(&self.socket)
+            .with_context(|| format!("conectando ao socket do Podman {:?}", self.socket))?;
+        stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+        let request = format!(
+            "{method} {API_PREFIX}{path} HTTP/1.1\r\nHost: d\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        );
+        stream
+            .write_all(request.as_bytes())
+            .with_context(|| format!("enviando {method} {path}"))?;
+
+        let mut raw = Vec::new();
+        stream
+            .read_to_end(&mut raw)
+            .with_context(|| format!("lendo resposta de {method} {path}"))?;
+
+        parse_response(&raw)
+    }
+
+    fn inspect(&self, name: &str) -> Result<Option<Value>> {
+        let response = self.request("GET", &format!("/containers/{name}/json"))?;
+        match response.status {
+            200 => Ok(Some(
+                serde_json::from_slice(&response.body)
+                    .with_context(|| format!("parse do inspect de {name}"))?,
+            )),
+            404 => Ok(None),
+            status => bail!(
+                "API do Podman retornou {} ao inspecionar {}: {}",
+                status,
+                name,
+                error_message(&response.body)
+            ),
+        }
+    }
+
+    fn lifecycle(&self, action: &str, name: &str, context: &str) -> Result<()> {
+        let response = self.request("POST", &format!("/containers/{name}/{action}"))?;
+        match response.status {
+            // 304: already in the requested state
+            204 | 304 => Ok(()),
+            status => bail!(
+                "API do Podman retornou {} ({})\nErro: {}",
+                status,
+                context,
+                error_message(&response.body)
+            ),
+        }
+    }
+}
+
+impl ContainerRuntime for PodmanApiAdapter {
+    fn get_container(&self, name: &str) -> Result<Container> {
+        let state = match self.inspect(name)? {
+            None => ContainerState::NotCreated,
+            Some(inspect) => state_from_inspect(&inspect),
+        };
+        Ok(Container::new(name.to_string(), state))
+    }
+
+    fn list_containers(&self, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>> {
+        let response = self.request("GET", "/containers/json?all=true")?;
+        if response.status != 200 {
+            bail!(
+                "API do Podman retornou {} ao listar containers: {}",
+                response.status,
+                error_message(&response.body)
+            );
+        }
+
+        let mut infos = parse_podman_ps(&response.body)?;
+        infos.retain(|info| filter.matches(info));
+        Ok(infos)
+    }
+
+    fn get_container_health(&self, name: &str) -> Result<ContainerHealthStatus> {
+        Ok(match self.inspect(name)? {
+            None => ContainerHealthStatus::Unknown,
+            Some(inspect) => health_from_inspect(&inspect),
+        })
+    }
+
+    fn start_container(&self, name: &str) -> Result<()> {
+        self.lifecycle("start", name, &format!("iniciando container {name}"))
+    }
+
+    fn stop_container(&self, name: &str) -> Result<()> {
+        self.lifecycle("stop", name, &format!("parando container {name}"))
+    }
+
+    fn create_container(&self, spec: &ContainerSpec) -> Result<()> {
+        // The REST create endpoint takes a full SpecGenerator; the CLI already
+        // knows how to translate our flags, so reuse it.
+        self.cli.create_container(spec)
+    }
+
+    fn remove_container(&self, name: &str) -> Result<()> {
+        let result = self.request("DELETE", &format!("/containers/{name}?force=true"));
+
+        match result {
+            Ok(response) if matches!(response.status, 200 | 204) => {}
+            _ => warn!("  Não foi possível remover {name} (pode não existir)"),
+        }
+
+        Ok(())
+    }
+
+    fn exec_command(
+        &self,
+        container: &str,
+        workdir: Option<&Path>,
+        env: &[String],
+        command: &[&str],
+    ) -> Result<()> {
+        self.cli.exec_command(container, workdir, env, command)
+    }
+
+    fn exec_output(&self, container: &str, command: &[&str]) -> Result<String> {
+        self.cli.exec_output(container, command)
+    }
+
+    fn container_logs(&self, name: &str, tail: usize) -> Result<String> {
+        let response = self.request(
+            "GET",
+            &format!("/containers/{name}/logs?stdout=true&stderr=true&tail={tail}"),
+        )?;
+
+        if response.status != 200 {
+            bail!(
+                "API do Podman retornou {} ao ler logs de {}: {}",
+                response.status,
+                name,
+                error_message(&response.body)
+            );
+        }
+
+        Ok(demux_logs(&response.body))
+    }
+
+    fn is_command_available(&self, cmd: &str) -> bool {
+        self.cli.is_command_available(cmd)
+    }
+
+    fn build_image(
+        &self,
+        tag: &str,
+        containerfile: &Path,
+        context_dir: &Path,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        self.cli
+            .build_image(tag, containerfile, context_dir, options)
+    }
+
+    fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        self.cli.list_images(repository)
+    }
+
+    fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        self.cli.tag_image(source, target)
+    }
+
+    fn remove_image(&self, reference: &str) -> Result<()> {
+        self.cli.remove_image(reference)
+    }
+
+    fn prune_containers(&self) -> Result<()> {
+        self.cli.prune_containers()
+    }
+
+    fn prune_images(&self) -> Result<()> {
+        self.cli.prune_images()
+    }
+
+    fn prune_volumes(&self) -> Result<()> {
+        self.cli.prune_volumes()
+    }
+
+    fn prune_build_cache(&self) -> Result<()> {
+        self.cli.prune_build_cache()
+    }
+
+    fn nuke_system(&self) -> Result<()> {
+        self.cli.nuke_system()
+    }
+
+    fn reset_system(&self) -> Result<()> {
+        self.cli.reset_system()
+    }
+}
+
+fn state_from_inspect(inspect: &Value) -> ContainerState {
+    if inspect["State"]["Running"].as_bool().unwrap_or(false) {
+        ContainerState::Running
+    } else {
+        ContainerState::Stopped
+    }
+}
+
+fn health_from_inspect(inspect: &Value) -> ContainerHealthStatus {
+    let state = &inspect["State"];
+    // Podman < 4.3 reported the health under "Healthcheck"
+    let health = if state["Health"].is_object() {
+        &state["Health"]
+    } else {
+        &state["Healthcheck"]
+    };
+
+    match health["Status"].as_str().unwrap_or("") {
+        "healthy" => ContainerHealthStatus::Healthy,
+        "unhealthy" => ContainerHealthStatus::Unhealthy,
+        "starting" => ContainerHealthStatus::Starting,
+        "" => ContainerHealthStatus::NotApplicable,
+        _ => ContainerHealthStatus::Unknown,
+    }
+}
+
+fn error_message(body: &[u8]) -> String {
+    serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|v| v["message"].as_str().map(String::from))
+        .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string())
+}
+
+fn parse_response(raw: &[u8]) -> Result<HttpResponse> {
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .context("resposta HTTP sem fim de cabeçalho")?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let body = &raw[header_end + 4..];
+
+    let mut lines = head.lines();
+    let status_line = lines.next().context("resposta HTTP vazia")?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .with_context(|| format!("linha de status inválida: {status_line}"))?;
+
+    let chunked = lines.any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+
+    Ok(HttpResponse { status, body })
+}
+
+fn decode_chunked(mut data: &[u8]) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+
+    loop {
+        let line_end = data
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .context("chunk HTTP truncado")?;
+        let size_field = String::from_utf8_lossy(&data[..line_end]);
+        // Chunk extensions (";name=value") are allowed after the size
+        let size_hex = size_field.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .with_context(|| format!("tamanho de chunk inválido: {size_hex}"))?;
+        data = &data[line_end + 2..];
+
+        if size == 0 {
+            return Ok(body);
+        }
+        if data.len() < size {
+            bail!("chunk HTTP truncado");
+        }
+
+        body.extend_from_slice(&data[..size]);
+        data = data.get(size + 2..).unwrap_or_default();
+    }
+}
+
+/// Strips the 8-byte frame headers podman adds when the container has no TTY
+fn demux_logs(body: &[u8]) -> String {
+    let framed = body.len() >= 8 && body[0] <= 2 && body[1..4] == [0, 0, 0];
+    if !framed {
+        return String::from_utf8_lossy(body).to_string();
+    }
+
+    let mut out = Vec::with_capacity(body.len());
+    let mut rest = body;
+    while rest.len() >= 8 {
+        let size = u32::from_be_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let end = (8 + size).min(rest.len());
+        out.extend_from_slice(&rest[8..end]);
+        rest = &rest[end..];
+    }
+
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    /// Serves canned responses, one per accepted connection
+    fn fake_api(responses: Vec<&'static str>) -> (tempfile::TempDir, PathBuf) {
+        let temp = tempfile::tempdir().unwrap();
+        let socket = temp.path().join("podman.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (temp, socket)
+    }
+
+    #[test]
+    fn test_parse_plain_response() {
+        let raw = b"HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\r\n{\"message\":\"no such container\"}";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(error_message(&response.body), "no such container");
+    }
+
+    #[test]
+    fn test_parse_chunked_response() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"Wikipedia");
+    }
+
+    #[test]
+    fn test_inspect_state_and_health() {
+        let running: Value = serde_json::from_str(
+            r#"{"State": {"Running": true, "Health": {"Status": "healthy"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(state_from_inspect(&running), ContainerState::Running);
+        assert_eq!(
+            health_from_inspect(&running),
+            ContainerHealthStatus::Healthy
+        );
+
+        let legacy: Value = serde_json::from_str(
+            r#"{"State": {"Running": false, "Healthcheck": {"Status": "starting"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(state_from_inspect(&legacy), ContainerState::Stopped);
+        assert_eq!(
+            health_from_inspect(&legacy),
+            ContainerHealthStatus::Starting
+        );
+
+        let no_health: Value = serde_json::from_str(r#"{"State": {"Running": true}}"#).unwrap();
+        assert_eq!(
+            health_from_inspect(&no_health),
+            ContainerHealthStatus::NotApplicable
+        );
+    }
+
+    #[test]
+    fn test_demux_logs() {
+        let mut framed = vec![1, 0, 0, 0, 0, 0, 0, 6];
+        framed.extend_from_slice(b"hello\n");
+        framed.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 4]);
+        framed.extend_from_slice(b"err\n");
+        assert_eq!(demux_logs(&framed), "hello\nerr\n");
+
+        // TTY containers stream raw output
+        assert_eq!(demux_logs(b"plain output\n"), "plain output\n");
+    }
+
+    #[test]
+    fn test_get_container_over_socket() {
+        let (_temp, socket) = fake_api(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"State\":{\"Running\":true}}",
+            "HTTP/1.1 404 Not Found\r\n\r\n{\"message\":\"no such container\"}",
+        ]);
+
+        let adapter = PodmanApiAdapter::connect(&socket).unwrap();
+        assert_eq!(
+            adapter.get_container("pg").unwrap().state,
+            ContainerState::Running
+        );
+        assert_eq!(
+            adapter.get_container("missing").unwrap().state,
+            ContainerState::NotCreated
+        );
+    }
+
+    #[test]
+    fn test_connect_fails_without_socket() {
+        let temp = tempfile::tempdir().unwrap();
+        assert!(PodmanApiAdapter::connect(&temp.path().join("missing.sock")).is_err());
+    }
+}
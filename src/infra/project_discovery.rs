@@ -1,12 +1,54 @@
 use crate::domain::{Project, ProjectConfig};
-use anyhow::{Context, Result};
+use crate::infra::config::{DEFAULT_DEVOBOX_TOML_NAME, load_global_config};
+use anyhow::{Context, Result, bail};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tracing::{debug, info};
+use std::time::UNIX_EPOCH;
+use tracing::{debug, info, warn};
 
-/// Discovers projects in configured directory (default: ~/code)
+/// Per-directory file with glob patterns of paths discovery should skip
+pub const IGNORE_FILE_NAME: &str = ".devoboxignore";
+
+/// Dependency and build output directories, never worth descending into
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+
+/// How deep below a root projects are searched (1 = direct children only)
+const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Bumped whenever the index layout changes
+const INDEX_VERSION: u32 = 1;
+
+/// Discovers projects (directories with devobox.toml) below the configured roots
+///
+/// The roots default to ~/code. With an index path set, the result of a scan
+/// is kept on disk and reused until a scanned directory, ignore file or
+/// devobox.toml changes its mtime.
 pub struct ProjectDiscovery {
-    base_dir: PathBuf,
+    roots: Vec<PathBuf>,
+    max_depth: usize,
+    excludes: Vec<String>,
+    index_path: Option<PathBuf>,
+}
+
+/// A discovered project, as stored in the index
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct IndexEntry {
+    name: String,
+    path: PathBuf,
+    /// Path relative to its root, e.g. "company/api"
+    relative: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Index {
+    version: u32,
+    /// Roots, depth and excludes the index was built with
+    settings: String,
+    /// Every path whose change could alter the result, with its mtime
+    watched: Vec<(PathBuf, u128)>,
+    projects: Vec<IndexEntry>,
 }
 
 impl ProjectDiscovery {
@@ -15,10 +57,7 @@ impl ProjectDiscovery {
     /// # Arguments
     /// * `base_dir` - Optional base directory to scan for projects. Defaults to ~/code
     pub fn new(base_dir: Option<PathBuf>) -> Result<Self> {
-        let base_dir = base_dir.unwrap_or_else(|| {
-            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-            PathBuf::from(home).join("code")
-        });
+        let base_dir = base_dir.unwrap_or_else(default_root);
 
         if !base_dir.exists() {
             info!("Diretório de projetos não existe, criando: {:?}", base_dir);
@@ -26,51 +65,68 @@ impl ProjectDiscovery {
                 .with_context(|| format!("Criando diretório de projetos: {:?}", base_dir))?;
         }
 
-        Ok(Self { base_dir })
+        Ok(Self {
+            roots: vec![base_dir],
+            max_depth: DEFAULT_MAX_DEPTH,
+            excludes: Vec::new(),
+            index_path: None,
+        })
     }
 
-    /// Lists all projects (directories with devobox.toml)
-    ///
-    /// Scans the base directory for subdirectories containing a devobox.toml file.
-    /// Only direct children are scanned (no recursive search).
-    pub fn discover_all(&self) -> Result<Vec<Project>> {
-        let mut projects = Vec::new();
-
-        let entries = fs::read_dir(&self.base_dir)
-            .with_context(|| format!("Lendo diretório de projetos: {:?}", self.base_dir))?;
+    /// Uses the `[projects]` settings of the global config and its index
+    pub fn from_config(config_dir: &Path) -> Result<Self> {
+        let settings = load_global_config(config_dir)?.projects;
+
+        let discovery = match settings.roots {
+            Some(roots) if !roots.is_empty() => Self {
+                roots: roots.iter().map(|root| expand_home(root)).collect(),
+                max_depth: DEFAULT_MAX_DEPTH,
+                excludes: Vec::new(),
+                index_path: None,
+            },
+            _ => Self::new(None)?,
+        };
+
+        Ok(discovery
+            .with_max_depth(settings.max_depth.unwrap_or(DEFAULT_MAX_DEPTH))
+            .with_excludes(settings.exclude.unwrap_or_default())
+            .with_index(config_dir.join("cache").join("projects.json")))
+    }
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth.max(1);
+        self
+    }
 
-            if !path.is_dir() {
-                continue;
-            }
+    /// Glob patterns skipped below every root, like a root .devoboxignore
+    pub fn with_excludes(mut self, excludes: Vec<String>) -> Self {
+        self.excludes = excludes;
+        self
+    }
 
-            let config_path = path.join("devobox.toml");
-            if !config_path.exists() {
-                debug!("Ignorando {:?} - não possui devobox.toml", path.file_name());
-                continue;
-            }
+    pub fn with_index(mut self, index_path: PathBuf) -> Self {
+        self.index_path = Some(index_path);
+        self
+    }
 
-            let config_path = path.join("devobox.toml");
-            if !config_path.exists() {
-                debug!("Ignorando {:?} - não possui devobox.toml", path.file_name());
-                continue;
-            }
+    /// Lists all projects (directories with devobox.toml)
+    ///
+    /// Searches every root up to the maximum depth, skipping hidden directories,
+    /// node_modules, target and whatever the excludes and .devoboxignore files
+    /// name. A project may contain other projects (monorepos).
+    pub fn discover_all(&self) -> Result<Vec<Project>> {
+        let mut projects = Vec::new();
 
+        for entry in self.entries()? {
+            let config_path = entry.path.join(DEFAULT_DEVOBOX_TOML_NAME);
             match self.load_project_config(&config_path) {
                 Ok(config) => {
-                    let project = Project::new(path, config);
+                    let project = Project::new(entry.path, config);
                     debug!("Projeto encontrado: {}", project.name);
                     projects.push(project);
                 }
                 Err(e) => {
-                    let name = path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown");
-                    debug!("Erro ao carregar projeto {}: {}", name, e);
+                    debug!("Erro ao carregar projeto {}: {}", entry.name, e);
                     // Continue descobrindo outros projetos mesmo se um falhar
                 }
             }
@@ -82,15 +138,35 @@ impl ProjectDiscovery {
     /// Finds a specific project by name
     ///
     /// # Arguments
-    /// * `name` - The project name (directory name)
+    /// * `name` - The project name, or its path relative to a root (e.g. "company/api")
     ///
     /// # Returns
     /// * `Ok(Some(Project))` if project found
     /// * `Ok(None)` if project doesn't exist
-    /// * `Err` if there was an error scanning
+    /// * `Err` if there was an error scanning, or the name matches several projects
     pub fn find_project(&self, name: &str) -> Result<Option<Project>> {
-        let projects = self.discover_all()?;
-        Ok(projects.into_iter().find(|p| p.name == name))
+        let entries = self.entries()?;
+
+        let mut matches: Vec<&IndexEntry> = entries.iter().filter(|e| e.relative == name).collect();
+        if matches.is_empty() {
+            matches = entries.iter().filter(|e| e.name == name).collect();
+        }
+
+        let entry = match matches.as_slice() {
+            [] => return Ok(None),
+            [entry] => entry,
+            _ => {
+                let paths: Vec<&str> = matches.iter().map(|e| e.relative.as_str()).collect();
+                bail!(
+                    "Há mais de um projeto chamado '{}': {}. Use o caminho.",
+                    name,
+                    paths.join(", ")
+                );
+            }
+        };
+
+        let config = self.load_project_config(&entry.path.join(DEFAULT_DEVOBOX_TOML_NAME))?;
+        Ok(Some(Project::new(entry.path.clone(), config)))
     }
 
     /// Loads project configuration from a devobox.toml file
@@ -104,10 +180,222 @@ impl ProjectDiscovery {
         Ok(config)
     }
 
-    /// Returns the base directory being scanned
+    /// Returns the first directory being scanned
     pub fn base_dir(&self) -> &Path {
-        &self.base_dir
+        &self.roots[0]
+    }
+
+    /// Directories being scanned
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// The projects, from the index when it is still valid
+    fn entries(&self) -> Result<Vec<IndexEntry>> {
+        let settings = self.settings();
+
+        if let Some(index) = self.index_path.as_deref().and_then(read_index)
+            && index.settings == settings
+            && index
+                .watched
+                .iter()
+                .all(|(path, mtime)| mtime_of(path) == Some(*mtime))
+        {
+            debug!(
+                "Usando índice de projetos ({} projetos)",
+                index.projects.len()
+            );
+            return Ok(index.projects);
+        }
+
+        let mut scan = Scan::default();
+        for root in &self.roots {
+            if !root.is_dir() {
+                warn!("  Diretório de projetos não encontrado: {:?}", root);
+                continue;
+            }
+            let mut rules = vec![IgnoreRules::new(root, &self.excludes)?];
+            self.walk(root, root, 0, &mut rules, &mut scan)?;
+        }
+
+        if let Some(index_path) = &self.index_path {
+            let index = Index {
+                version: INDEX_VERSION,
+                settings,
+                watched: scan.watched,
+                projects: scan.projects.clone(),
+            };
+            if let Err(e) = write_index(index_path, &index) {
+                debug!("Não foi possível gravar o índice de projetos: {:#}", e);
+            }
+        }
+
+        Ok(scan.projects)
     }
+
+    fn settings(&self) -> String {
+        format!(
+            "{}|{:?}|{}|{:?}",
+            INDEX_VERSION, self.roots, self.max_depth, self.excludes
+        )
+    }
+
+    fn walk(
+        &self,
+        dir: &Path,
+        root: &Path,
+        depth: usize,
+        rules: &mut Vec<IgnoreRules>,
+        scan: &mut Scan,
+    ) -> Result<()> {
+        scan.watch(dir);
+
+        let ignore_file = dir.join(IGNORE_FILE_NAME);
+        let has_ignore_file = ignore_file.is_file();
+        if has_ignore_file {
+            scan.watch(&ignore_file);
+            let content = fs::read_to_string(&ignore_file)
+                .with_context(|| format!("Lendo {:?}", ignore_file))?;
+            let patterns: Vec<String> = content.lines().map(String::from).collect();
+            rules.push(IgnoreRules::new(dir, &patterns)?);
+        }
+
+        let config_path = dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+        if depth > 0 && config_path.is_file() {
+            scan.watch(&config_path);
+            match self.load_project_config(&config_path) {
+                Ok(config) => {
+                    let relative = dir.strip_prefix(root).unwrap_or(dir);
+                    scan.projects.push(IndexEntry {
+                        name: Project::new(dir.to_path_buf(), config).name,
+                        path: dir.to_path_buf(),
+                        relative: relative.to_string_lossy().into_owned(),
+                    });
+                }
+                Err(e) => debug!("Ignorando {:?}: {:#}", dir, e),
+            }
+        }
+
+        if depth < self.max_depth {
+            let mut children: Vec<PathBuf> = fs::read_dir(dir)
+                .with_context(|| format!("Lendo diretório de projetos: {:?}", dir))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir())
+                .collect();
+            children.sort();
+
+            for child in children {
+                let name = child.file_name().unwrap_or_default().to_string_lossy();
+                if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+                    continue;
+                }
+                if rules.iter().any(|rule| rule.matches(&child)) {
+                    debug!("Ignorando {:?} (exclusão)", child);
+                    continue;
+                }
+                // Unreadable directories (permissions) shouldn't stop the scan
+                if let Err(e) = self.walk(&child, root, depth + 1, rules, scan) {
+                    debug!("Ignorando {:?}: {:#}", child, e);
+                }
+            }
+        }
+
+        if has_ignore_file {
+            rules.pop();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Scan {
+    projects: Vec<IndexEntry>,
+    watched: Vec<(PathBuf, u128)>,
+}
+
+impl Scan {
+    fn watch(&mut self, path: &Path) {
+        if let Some(mtime) = mtime_of(path) {
+            self.watched.push((path.to_path_buf(), mtime));
+        }
+    }
+}
+
+/// Exclusion patterns anchored at the directory that declares them
+///
+/// Like .gitignore: a pattern without `/` matches a directory name at any depth
+/// below `base`; one with `/` matches the path relative to `base`.
+struct IgnoreRules {
+    base: PathBuf,
+    by_name: GlobSet,
+    by_path: GlobSet,
+}
+
+impl IgnoreRules {
+    fn new(base: &Path, patterns: &[String]) -> Result<Self> {
+        let mut by_name = GlobSetBuilder::new();
+        let mut by_path = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let pattern = pattern.trim().trim_end_matches('/');
+            if pattern.is_empty() || pattern.starts_with('#') {
+                continue;
+            }
+
+            let glob = |p: &str| {
+                Glob::new(p).with_context(|| format!("Padrão de exclusão inválido: '{}'", p))
+            };
+            if pattern.contains('/') {
+                by_path.add(glob(pattern.trim_start_matches('/'))?);
+            } else {
+                by_name.add(glob(pattern)?);
+            }
+        }
+
+        Ok(Self {
+            base: base.to_path_buf(),
+            by_name: by_name.build()?,
+            by_path: by_path.build()?,
+        })
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        path.file_name()
+            .is_some_and(|name| self.by_name.is_match(name))
+            || self.by_path.is_match(relative)
+    }
+}
+
+fn default_root() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join("code")
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(&path.to_string_lossy()).into_owned())
+}
+
+fn mtime_of(path: &Path) -> Option<u128> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_nanos())
+}
+
+fn read_index(path: &Path) -> Option<Index> {
+    let content = fs::read_to_string(path).ok()?;
+    let index: Index = serde_json::from_str(&content).ok()?;
+    (index.version == INDEX_VERSION).then_some(index)
+}
+
+fn write_index(path: &Path, index: &Index) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(index)?)?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -116,6 +404,24 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    fn names(discovery: &ProjectDiscovery) -> Vec<String> {
+        let mut names: Vec<String> = discovery
+            .discover_all()
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn make_project(root: &Path, relative: &str) -> PathBuf {
+        let dir = root.join(relative);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("devobox.toml"), "[project]\n").unwrap();
+        dir
+    }
+
     #[test]
     fn test_discovery_empty_directory() {
         let temp = TempDir::new().unwrap();
@@ -175,6 +481,84 @@ mod tests {
         assert!(projects.iter().any(|p| p.name == "project-c"));
     }
 
+    #[test]
+    fn test_discovery_is_recursive_up_to_max_depth() {
+        let temp = TempDir::new().unwrap();
+        make_project(temp.path(), "company/api");
+        make_project(temp.path(), "company/api/worker");
+        make_project(temp.path(), "a/b/c/too-deep");
+
+        let discovery = ProjectDiscovery::new(Some(temp.path().to_path_buf())).unwrap();
+        assert_eq!(names(&discovery), vec!["api", "worker"]);
+
+        let shallow = ProjectDiscovery::new(Some(temp.path().to_path_buf()))
+            .unwrap()
+            .with_max_depth(1);
+        assert!(names(&shallow).is_empty());
+    }
+
+    #[test]
+    fn test_discovery_skips_ignored_directories() {
+        let temp = TempDir::new().unwrap();
+        make_project(temp.path(), "app");
+        make_project(temp.path(), "app/node_modules/pkg");
+        make_project(temp.path(), "app/target/debug");
+        make_project(temp.path(), ".hidden");
+        make_project(temp.path(), "archive/old");
+        make_project(temp.path(), "forks/lib");
+        make_project(temp.path(), "forks/keep");
+        fs::write(temp.path().join(".devoboxignore"), "# antigos\narchive\n").unwrap();
+        fs::write(temp.path().join("forks/.devoboxignore"), "/lib\n").unwrap();
+
+        let discovery = ProjectDiscovery::new(Some(temp.path().to_path_buf()))
+            .unwrap()
+            .with_excludes(vec!["*-tmp".to_string()]);
+        make_project(temp.path(), "scratch-tmp");
+
+        assert_eq!(names(&discovery), vec!["app", "keep"]);
+    }
+
+    #[test]
+    fn test_find_project_by_path_when_names_clash() {
+        let temp = TempDir::new().unwrap();
+        make_project(temp.path(), "company/api");
+        make_project(temp.path(), "personal/api");
+
+        let discovery = ProjectDiscovery::new(Some(temp.path().to_path_buf())).unwrap();
+        let err = discovery.find_project("api").unwrap_err();
+        assert!(err.to_string().contains("company/api, personal/api"));
+
+        let project = discovery.find_project("personal/api").unwrap().unwrap();
+        assert_eq!(project.path, temp.path().join("personal/api"));
+    }
+
+    #[test]
+    fn test_index_is_reused_until_something_changes() {
+        let temp = TempDir::new().unwrap();
+        let code = temp.path().join("code");
+        make_project(&code, "api");
+        let index = temp.path().join("index.json");
+
+        let discovery = ProjectDiscovery::new(Some(code.clone()))
+            .unwrap()
+            .with_index(index.clone());
+        assert_eq!(names(&discovery), vec!["api"]);
+        assert!(index.exists());
+
+        // A stale entry in a still valid index is served as is
+        let mut stored: Index = serde_json::from_str(&fs::read_to_string(&index).unwrap()).unwrap();
+        stored.projects[0].name = "from-index".to_string();
+        stored.projects[0].relative = "from-index".to_string();
+        fs::write(&index, serde_json::to_string(&stored).unwrap()).unwrap();
+        assert!(discovery.find_project("from-index").unwrap().is_some());
+
+        // A new project changes the root's mtime and forces a rescan
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        make_project(&code, "web");
+        assert_eq!(names(&discovery), vec!["api", "web"]);
+        assert!(discovery.find_project("from-index").unwrap().is_none());
+    }
+
     #[test]
     fn test_find_project() {
         let temp = TempDir::new().unwrap();
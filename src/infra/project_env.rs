@@ -0,0 +1,149 @@
+use crate::domain::{Project, ProjectConfig};
+use crate::infra::config::DEFAULT_DEVOBOX_TOML_NAME;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+/// Environment of a project as `KEY=value` entries: its `env_file` first, then
+/// `[project] env`, later entries replacing earlier ones with the same key
+pub fn project_env(project: &Project) -> Result<Vec<String>> {
+    let mut env = Vec::new();
+
+    if let Some(file) = project.env_file() {
+        if file.exists() {
+            let content = fs::read_to_string(&file).with_context(|| format!("lendo {:?}", file))?;
+            for entry in parse_env_file(&content) {
+                set(&mut env, entry);
+            }
+        } else {
+            warn!(
+                "  env_file de {} não encontrado: {}",
+                project.name,
+                file.display()
+            );
+        }
+    }
+
+    for entry in project.env_vars() {
+        set(&mut env, entry.clone());
+    }
+
+    Ok(env)
+}
+
+/// The closest directory to `dir` (itself or an ancestor) with a devobox.toml
+pub fn nearest_project_dir(dir: &Path) -> Option<&Path> {
+    dir.ancestors()
+        .find(|dir| dir.join(DEFAULT_DEVOBOX_TOML_NAME).is_file())
+}
+
+/// The project whose devobox.toml is closest to `dir` (itself or an ancestor)
+pub fn nearest_project(dir: &Path) -> Result<Option<Project>> {
+    let Some(root) = nearest_project_dir(dir) else {
+        return Ok(None);
+    };
+
+    let path = root.join(DEFAULT_DEVOBOX_TOML_NAME);
+    let content = fs::read_to_string(&path).with_context(|| format!("lendo {:?}", path))?;
+    let config: ProjectConfig =
+        toml::from_str(&content).with_context(|| format!("parse de {:?}", path))?;
+    Ok(Some(Project::new(root.to_path_buf(), config)))
+}
+
+/// Dotenv lines (`KEY=value`, `export KEY="value"`), skipping comments and blanks
+pub fn parse_env_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = unquote(value, '"')
+                .or_else(|| unquote(value, '\''))
+                .unwrap_or(value);
+            Some(format!("{}={}", key.trim(), value))
+        })
+        .collect()
+}
+
+fn unquote(value: &str, quote: char) -> Option<&str> {
+    value
+        .strip_prefix(quote)
+        .and_then(|value| value.strip_suffix(quote))
+}
+
+fn set(env: &mut Vec<String>, entry: String) {
+    let key = entry.split_once('=').map_or(entry.as_str(), |(key, _)| key);
+    match env.iter_mut().find(|existing| {
+        existing
+            .split_once('=')
+            .map_or(existing.as_str(), |(k, _)| k)
+            == key
+    }) {
+        Some(existing) => *existing = entry,
+        None => env.push(entry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProjectSettings;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = "# comment\n\nA=1\nexport B=\"two words\"\nC='x=y'\nnot a pair\n";
+        assert_eq!(parse_env_file(content), vec!["A=1", "B=two words", "C=x=y"]);
+    }
+
+    #[test]
+    fn test_project_env_overrides_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".env"), "PORT=3000\nDB=local\n").unwrap();
+
+        let config = ProjectConfig {
+            project: Some(ProjectSettings {
+                env: vec!["PORT=4000".to_string(), "DEBUG=1".to_string()],
+                env_file: Some(".env".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let project = Project::new(dir.path().to_path_buf(), config);
+
+        assert_eq!(
+            project_env(&project).unwrap(),
+            vec!["PORT=4000", "DB=local", "DEBUG=1"]
+        );
+    }
+
+    #[test]
+    fn test_missing_env_file_is_not_fatal() {
+        let config = ProjectConfig {
+            project: Some(ProjectSettings {
+                env: vec!["A=1".to_string()],
+                env_file: Some(".env".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let project = Project::new(PathBuf::from("/nonexistent/app"), config);
+        assert_eq!(project_env(&project).unwrap(), vec!["A=1"]);
+    }
+
+    #[test]
+    fn test_nearest_project_walks_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("app");
+        fs::create_dir_all(app.join("src/nested")).unwrap();
+        fs::write(app.join(DEFAULT_DEVOBOX_TOML_NAME), "").unwrap();
+
+        let project = nearest_project(&app.join("src/nested")).unwrap().unwrap();
+        assert_eq!(project.path, app);
+        assert!(nearest_project(dir.path()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::domain::{Project, ProjectConfig};
+use crate::infra::config::DEFAULT_DEVOBOX_TOML_NAME;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// A project together with the projects it includes directly
+#[derive(Debug, Clone)]
+pub struct ProjectNode {
+    pub project: Project,
+    /// Canonical paths of the included projects that could be loaded
+    pub dependencies: Vec<PathBuf>,
+}
+
+/// Every project reachable through `include_projects`, each loaded once
+#[derive(Debug, Clone)]
+pub struct ProjectGraph {
+    pub root: ProjectNode,
+    /// Transitive dependencies in depth-first order, without the root
+    pub dependencies: Vec<ProjectNode>,
+}
+
+impl ProjectGraph {
+    /// Walks `include_projects` recursively starting at `root`
+    ///
+    /// The root's includes are relative to `include_base`, nested ones to the
+    /// directory of the project declaring them. A project reached twice (a
+    /// diamond) is loaded once; a project that includes itself through others
+    /// is an error.
+    pub fn build(root: Project, include_base: &Path) -> Result<Self> {
+        let root_path = fs::canonicalize(&root.path).unwrap_or_else(|_| root.path.clone());
+        let includes = root.config.dependencies.include_projects.clone();
+
+        let mut walker = Walker {
+            nodes: Vec::new(),
+            stack: vec![(root_path, root.name.clone())],
+        };
+        let dependencies = walker.visit(include_base, &includes)?;
+
+        Ok(Self {
+            root: ProjectNode {
+                project: root,
+                dependencies,
+            },
+            dependencies: walker.nodes,
+        })
+    }
+
+    /// Root first, then the dependencies in depth-first order
+    pub fn projects(&self) -> impl Iterator<Item = &ProjectNode> {
+        std::iter::once(&self.root).chain(&self.dependencies)
+    }
+
+    pub fn find(&self, path: &Path) -> Option<&ProjectNode> {
+        self.dependencies
+            .iter()
+            .find(|node| node.project.path == path)
+    }
+}
+
+struct Walker {
+    nodes: Vec<ProjectNode>,
+    /// Projects on the current path from the root, to spot cycles
+    stack: Vec<(PathBuf, String)>,
+}
+
+impl Walker {
+    fn visit(&mut self, owner_dir: &Path, includes: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let mut direct = Vec::with_capacity(includes.len());
+
+        for relative_path in includes {
+            let dep_path = owner_dir.join(relative_path);
+            let canonical_path = match fs::canonicalize(&dep_path) {
+                Ok(p) => p,
+                Err(_) => {
+                    warn!("  Caminho de dependência inválido: {:?}", dep_path);
+                    continue;
+                }
+            };
+
+            if let Some(start) = self.stack.iter().position(|(p, _)| *p == canonical_path) {
+                let mut cycle: Vec<&str> = self.stack[start..]
+                    .iter()
+                    .map(|(_, name)| name.as_str())
+                    .collect();
+                cycle.push(&self.stack[start].1);
+                bail!(
+                    "Ciclo de dependências entre projetos: {}",
+                    cycle.join(" -> ")
+                );
+            }
+
+            // Diamond: already loaded through another project
+            if self.nodes.iter().any(|n| n.project.path == canonical_path) {
+                direct.push(canonical_path);
+                continue;
+            }
+
+            let config = match load_config(&canonical_path) {
+                Ok(config) => config,
+                Err(e) => {
+                    warn!(
+                        "  Erro ao carregar config de dependência em {:?}: {:#}",
+                        canonical_path, e
+                    );
+                    continue;
+                }
+            };
+
+            let project = Project::new(canonical_path.clone(), config);
+            let nested = project.config.dependencies.include_projects.clone();
+            let index = self.nodes.len();
+            self.stack
+                .push((canonical_path.clone(), project.name.clone()));
+            self.nodes.push(ProjectNode {
+                project,
+                dependencies: Vec::new(),
+            });
+
+            let dependencies = self.visit(&canonical_path, &nested)?;
+            self.nodes[index].dependencies = dependencies;
+            self.stack.pop();
+
+            direct.push(canonical_path);
+        }
+
+        Ok(direct)
+    }
+}
+
+/// A directory without devobox.toml is a project without services or dependencies
+fn load_config(dir: &Path) -> Result<ProjectConfig> {
+    let path = dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    if !path.exists() {
+        return Ok(ProjectConfig::default());
+    }
+
+    let content = fs::read_to_string(&path).with_context(|| format!("Erro ao ler {:?}", path))?;
+    toml::from_str(&content).with_context(|| format!("Erro ao fazer parse de {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(root: &Path, name: &str, toml: &str) -> PathBuf {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(DEFAULT_DEVOBOX_TOML_NAME), toml).unwrap();
+        fs::canonicalize(dir).unwrap()
+    }
+
+    fn includes(names: &[&str]) -> String {
+        let list: Vec<String> = names.iter().map(|n| format!("\"../{n}\"")).collect();
+        format!("[dependencies]\ninclude_projects = [{}]\n", list.join(", "))
+    }
+
+    fn root(dir: &Path) -> Project {
+        let content = fs::read_to_string(dir.join(DEFAULT_DEVOBOX_TOML_NAME)).unwrap();
+        Project::new(dir.to_path_buf(), toml::from_str(&content).unwrap())
+    }
+
+    #[test]
+    fn test_follows_dependencies_transitively_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let app = project(temp.path(), "app", &includes(&["auth", "billing"]));
+        let auth = project(temp.path(), "auth", &includes(&["shared"]));
+        let billing = project(temp.path(), "billing", &includes(&["shared"]));
+        let shared = project(temp.path(), "shared", "");
+
+        let graph = ProjectGraph::build(root(&app), &app).unwrap();
+
+        let names: Vec<&str> = graph
+            .projects()
+            .map(|node| node.project.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["app", "auth", "shared", "billing"]);
+
+        assert_eq!(graph.root.dependencies, vec![auth.clone(), billing.clone()]);
+        assert_eq!(
+            graph.find(&auth).unwrap().dependencies,
+            vec![shared.clone()]
+        );
+        assert_eq!(graph.find(&billing).unwrap().dependencies, vec![shared]);
+    }
+
+    #[test]
+    fn test_reports_cycles() {
+        let temp = tempfile::tempdir().unwrap();
+        let app = project(temp.path(), "app", &includes(&["a"]));
+        project(temp.path(), "a", &includes(&["b"]));
+        project(temp.path(), "b", &includes(&["a"]));
+
+        let err = ProjectGraph::build(root(&app), &app).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ciclo de dependências entre projetos: a -> b -> a"
+        );
+    }
+
+    #[test]
+    fn test_skips_missing_dependencies() {
+        let temp = tempfile::tempdir().unwrap();
+        let app = project(temp.path(), "app", &includes(&["gone", "plain"]));
+        fs::create_dir_all(temp.path().join("plain")).unwrap();
+
+        let graph = ProjectGraph::build(root(&app), &app).unwrap();
+        assert_eq!(graph.dependencies.len(), 1);
+        assert_eq!(graph.dependencies[0].project.name, "plain");
+    }
+}
@@ -0,0 +1,619 @@
+use crate::domain::{
+    Project, ProjectConfig, ProjectDependencies, Service, ServiceConfig, merge_service_configs,
+};
+use crate::infra::config::{
+    AppConfig, DEFAULT_DEVOBOX_TOML_NAME, load_global_config, services_from_hashmap,
+};
+use crate::infra::project_graph::{ProjectGraph, ProjectNode};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// A service ready to run, with where its definition comes from
+#[derive(Debug, Clone)]
+pub struct ResolvedService {
+    pub service: Service,
+    /// devobox.toml holding the definition (the global one for inherited services)
+    pub source_file: PathBuf,
+    /// Included project that brought the service in; None for the starting config
+    pub via_project: Option<String>,
+    /// Marked `shared = true`: one container for every project defining it
+    pub shared: bool,
+}
+
+/// The same service name defined by two projects of one dependency graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceConflict {
+    pub name: String,
+    /// The definition reached first
+    pub first: PathBuf,
+    pub second: PathBuf,
+}
+
+/// Every duplicate found while resolving, reported together
+#[derive(Debug)]
+pub struct ServiceConflicts(pub Vec<ServiceConflict>);
+
+impl fmt::Display for ServiceConflicts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Serviço(s) definido(s) em mais de um projeto:")?;
+        for conflict in &self.0 {
+            writeln!(
+                f,
+                "  - '{}': {} e {}",
+                conflict.name,
+                conflict.first.display(),
+                conflict.second.display()
+            )?;
+        }
+        write!(
+            f,
+            "Renomeie um deles ou marque o que deve prevalecer com `override = true`"
+        )
+    }
+}
+
+impl std::error::Error for ServiceConflicts {}
+
+/// Services one project of a dependency graph adds
+#[derive(Debug)]
+pub struct ServiceContribution<'g> {
+    pub node: &'g ProjectNode,
+    pub services: Vec<ResolvedService>,
+    /// Services it defines that an earlier project took over with `override = true`
+    /// (or switched off with `enabled = false`, or shares with `shared = true`)
+    pub shadowed: Vec<String>,
+}
+
+/// Who owns a service name while walking the graph
+struct Owner {
+    source: PathBuf,
+    overrides: bool,
+    shared: bool,
+    /// Defined only by the global config: the first project tweaking it takes over
+    global: bool,
+}
+
+/// Decides which services run and where each one is defined
+///
+/// Global services serve as the base every project merges its own tables
+/// over, so an included project tweaking a global service refines it rather
+/// than colliding with it. Included projects are walked transitively; a name
+/// defined by two projects is an error unless the first one marks it
+/// `override = true`.
+///
+/// With `[projects] namespace_services = true` each project's containers are
+/// named `<project>-<service>` (with the project name reduced to what container
+/// names accept), so equal names no longer collide; services
+/// marked `shared = true` keep their plain name.
+#[derive(Clone)]
+pub struct ServiceResolver<'a> {
+    config_dir: &'a Path,
+    global: HashMap<String, ServiceConfig>,
+    namespace: bool,
+}
+
+impl<'a> ServiceResolver<'a> {
+    pub fn new(config_dir: &'a Path) -> Result<Self> {
+        let global = load_global_config(config_dir)?;
+        Ok(Self {
+            config_dir,
+            global: global.services.unwrap_or_default(),
+            namespace: global.projects.namespace_services.unwrap_or(false),
+        })
+    }
+
+    /// Services of the merged global/local config plus every project it
+    /// includes (what `devobox build` creates)
+    ///
+    /// The config's `include_projects` are relative to the global config dir.
+    pub fn resolve_workspace(
+        &self,
+        app_config: &AppConfig,
+        local_dir: &Path,
+    ) -> Result<Vec<ResolvedService>> {
+        // The local file may switch namespacing on for itself
+        let resolver = Self {
+            namespace: app_config
+                .projects
+                .namespace_services
+                .unwrap_or(self.namespace),
+            ..self.clone()
+        };
+        let global_file = self.config_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+        let local = local_project(local_dir, &global_file);
+
+        let mut root_services = Vec::new();
+        if let Some(services_map) = &app_config.services {
+            info!(
+                "  Carregando {} serviço(s) da configuração atual...",
+                services_map.len()
+            );
+            for service in services_from_hashmap(services_map)? {
+                let shared = services_map[&service.name].is_shared();
+                // Services only the global config defines belong to no project
+                let owner = local.as_ref().filter(|project| {
+                    project
+                        .config
+                        .services
+                        .as_ref()
+                        .is_some_and(|services| services.contains_key(&service.name))
+                });
+                let source_file = match owner {
+                    Some(project) => project.path.join(DEFAULT_DEVOBOX_TOML_NAME),
+                    None => global_file.clone(),
+                };
+                let resolved = ResolvedService {
+                    service,
+                    source_file,
+                    via_project: None,
+                    shared,
+                };
+                root_services.push(match owner {
+                    Some(project) => resolver.qualify(resolved, &project.name),
+                    None => resolved,
+                });
+            }
+        }
+
+        let root = Project::new(
+            self.config_dir.to_path_buf(),
+            ProjectConfig {
+                project: None,
+                dependencies: ProjectDependencies {
+                    include_projects: app_config
+                        .dependencies
+                        .include_projects
+                        .clone()
+                        .unwrap_or_default(),
+                },
+                services: app_config.services.clone(),
+                layout: None,
+            },
+        );
+        let graph = ProjectGraph::build(root, self.config_dir)?;
+
+        Ok(flatten(resolver.resolve_graph(&graph, root_services)?))
+    }
+
+    /// A project's services plus those of every project it includes (what
+    /// `devobox project up` starts)
+    pub fn resolve_project(&self, project: &Project) -> Result<Vec<ResolvedService>> {
+        let graph = ProjectGraph::build(project.clone(), &project.path)?;
+        let root_services = self.project_services(project, None)?;
+
+        Ok(flatten(self.resolve_graph(&graph, root_services)?))
+    }
+
+    /// Resolves every project in `graph`, root first
+    ///
+    /// `root_services` are the root's already resolved services; the other
+    /// projects are resolved from their own devobox.toml.
+    pub fn resolve_graph<'g>(
+        &self,
+        graph: &'g ProjectGraph,
+        root_services: Vec<ResolvedService>,
+    ) -> Result<Vec<ServiceContribution<'g>>> {
+        let root_file = graph.root.project.path.join(DEFAULT_DEVOBOX_TOML_NAME);
+        let global_file = self.config_dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+
+        // Names switched off in the root stay off, whoever else defines them
+        let mut owners: HashMap<String, Owner> =
+            disabled_services(graph.root.project.config.services.as_ref())
+                .into_iter()
+                .map(|name| {
+                    let owner = Owner {
+                        source: root_file.clone(),
+                        overrides: true,
+                        shared: false,
+                        global: false,
+                    };
+                    (name, owner)
+                })
+                .collect();
+
+        let mut conflicts = Vec::new();
+        let mut contributions: Vec<ServiceContribution> = Vec::new();
+        for node in graph.projects() {
+            let services = if std::ptr::eq(node, &graph.root) {
+                root_services.clone()
+            } else {
+                self.project_services(&node.project, Some(&node.project.name))?
+            };
+            let overriding = overriding_services(node.project.config.services.as_ref());
+            let project = &node.project.name;
+
+            let mut kept = Vec::new();
+            let mut shadowed = Vec::new();
+            for resolved in services {
+                let name = &resolved.service.name;
+                match owners.get(name) {
+                    None => {
+                        let owner = Owner {
+                            source: resolved.source_file.clone(),
+                            overrides: overriding.contains(resolved.service.short_name()),
+                            shared: resolved.shared,
+                            global: resolved.via_project.is_none()
+                                && resolved.source_file == global_file,
+                        };
+                        owners.insert(name.clone(), owner);
+                        kept.push(resolved);
+                    }
+                    // Already merged over the global table: it replaces the plain one
+                    Some(owner) if owner.global => {
+                        info!("  Serviço global '{}' ajustado por {}", name, project);
+                        for earlier in &mut contributions {
+                            earlier.services.retain(|r| r.service.name != *name);
+                        }
+                        let owner = Owner {
+                            source: resolved.source_file.clone(),
+                            overrides: overriding.contains(resolved.service.short_name()),
+                            shared: resolved.shared,
+                            global: false,
+                        };
+                        owners.insert(name.clone(), owner);
+                        kept.push(resolved);
+                    }
+                    Some(owner) if owner.overrides => {
+                        info!("  Serviço '{}' de {} substituído (override)", name, project);
+                        shadowed.push(name.clone());
+                    }
+                    Some(owner) if owner.shared && resolved.shared => {
+                        info!(
+                            "  Serviço compartilhado '{}' de {} já definido antes",
+                            name, project
+                        );
+                        shadowed.push(name.clone());
+                    }
+                    Some(owner) => conflicts.push(ServiceConflict {
+                        name: name.clone(),
+                        first: owner.source.clone(),
+                        second: resolved.source_file,
+                    }),
+                }
+            }
+
+            contributions.push(ServiceContribution {
+                node,
+                services: kept,
+                shadowed,
+            });
+        }
+
+        if !conflicts.is_empty() {
+            return Err(ServiceConflicts(conflicts).into());
+        }
+        Ok(contributions)
+    }
+
+    /// A project's own services, each merged over the global one of the same name
+    ///
+    /// Global-only services can still be named in `extends` but are not returned.
+    pub fn project_services(
+        &self,
+        project: &Project,
+        via_project: Option<&str>,
+    ) -> Result<Vec<ResolvedService>> {
+        let Some(services_map) = &project.config.services else {
+            return Ok(Vec::new());
+        };
+
+        match via_project {
+            Some(_) => info!(
+                "  Carregando {} serviço(s) de dependência {:?}...",
+                services_map.len(),
+                project.path
+            ),
+            None => info!(
+                "  Carregando {} serviço(s) do projeto...",
+                services_map.len()
+            ),
+        }
+
+        let mut merged = self.global.clone();
+        merge_service_configs(&mut merged, services_map.clone());
+
+        let source_file = project.path.join(DEFAULT_DEVOBOX_TOML_NAME);
+        Ok(services_from_hashmap(&merged)?
+            .into_iter()
+            .filter(|service| services_map.contains_key(&service.name))
+            .map(|service| {
+                let resolved = ResolvedService {
+                    shared: merged[&service.name].is_shared(),
+                    service,
+                    source_file: source_file.clone(),
+                    via_project: via_project.map(String::from),
+                };
+                self.qualify(resolved, &project.slug())
+            })
+            .collect())
+    }
+
+    /// Names the container after `project` when namespacing, unless it is shared
+    fn qualify(&self, mut resolved: ResolvedService, project: &str) -> ResolvedService {
+        if self.namespace && !resolved.shared {
+            resolved.service.namespace(project);
+        }
+        resolved
+    }
+}
+
+fn flatten(contributions: Vec<ServiceContribution>) -> Vec<ResolvedService> {
+    contributions
+        .into_iter()
+        .flat_map(|contribution| contribution.services)
+        .collect()
+}
+
+/// The project whose devobox.toml is the local config, if there is one besides
+/// the global file; unreadable files count as absent
+fn local_project(local_dir: &Path, global_file: &Path) -> Option<Project> {
+    let dir = fs::canonicalize(local_dir).ok()?;
+    let path = dir.join(DEFAULT_DEVOBOX_TOML_NAME);
+    if fs::canonicalize(global_file).is_ok_and(|global| global == path) {
+        return None;
+    }
+
+    let content = fs::read_to_string(&path).ok()?;
+    let config: ProjectConfig = toml::from_str(&content).ok()?;
+    Some(Project::new(dir, config))
+}
+
+/// Names of services switched off with `enabled = false`
+fn disabled_services(services_map: Option<&HashMap<String, ServiceConfig>>) -> Vec<String> {
+    services_map
+        .into_iter()
+        .flatten()
+        .filter(|(_, config)| config.is_disabled())
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Names of services marked `override = true`
+fn overriding_services(services_map: Option<&HashMap<String, ServiceConfig>>) -> HashSet<String> {
+    services_map
+        .into_iter()
+        .flatten()
+        .filter(|(_, config)| config.is_override())
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, name: &str, content: &str) -> PathBuf {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(DEFAULT_DEVOBOX_TOML_NAME), content).unwrap();
+        fs::canonicalize(dir).unwrap()
+    }
+
+    fn project(dir: &Path) -> Project {
+        let content = fs::read_to_string(dir.join(DEFAULT_DEVOBOX_TOML_NAME)).unwrap();
+        Project::new(dir.to_path_buf(), toml::from_str(&content).unwrap())
+    }
+
+    fn names(services: &[ResolvedService]) -> Vec<(&str, &str)> {
+        services
+            .iter()
+            .map(|r| (r.service.name.as_str(), r.service.image.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn project_services_override_global_ones() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = write(
+            temp.path(),
+            "config",
+            "[services.pg]\nimage = \"postgres:16\"\nenv = [\"POSTGRES_PASSWORD=dev\"]\n\n[services.redis]\nimage = \"redis:7\"\n",
+        );
+        let api = write(
+            temp.path(),
+            "api",
+            "[services.pg]\nenv = { append = [\"POSTGRES_DB=api\"] }\n\n[services.redis]\nenabled = false\n",
+        );
+
+        let resolver = ServiceResolver::new(&config_dir).unwrap();
+        let services = resolver.resolve_project(&project(&api)).unwrap();
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].service.image, "postgres:16");
+        assert_eq!(
+            services[0].service.env,
+            vec!["POSTGRES_PASSWORD=dev", "POSTGRES_DB=api"]
+        );
+        assert_eq!(services[0].source_file, api.join(DEFAULT_DEVOBOX_TOML_NAME));
+        assert_eq!(services[0].via_project, None);
+    }
+
+    #[test]
+    fn namespaced_containers_use_a_valid_project_name() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = write(
+            temp.path(),
+            "config",
+            "[projects]\nnamespace_services = true\n",
+        );
+        let app = write(
+            temp.path(),
+            "My App",
+            "[services.pg]\nimage = \"postgres:16\"\n",
+        );
+
+        let resolver = ServiceResolver::new(&config_dir).unwrap();
+        let services = resolver.resolve_project(&project(&app)).unwrap();
+        assert_eq!(names(&services), vec![("my-app-pg", "postgres:16")]);
+        assert_eq!(services[0].service.short_name(), "pg");
+    }
+
+    #[test]
+    fn resolves_dependencies_transitively() {
+        let temp = tempfile::tempdir().unwrap();
+
+        // a -> b -> c and a -> d -> c (diamond)
+        let a = write(
+            temp.path(),
+            "a",
+            "[dependencies]\ninclude_projects = [\"../b\", \"../d\"]\n\n[services.a-db]\nimage = \"postgres\"\n",
+        );
+        write(
+            temp.path(),
+            "b",
+            "[dependencies]\ninclude_projects = [\"../c\"]\n\n[services.b-cache]\nimage = \"redis\"\n",
+        );
+        let c = write(temp.path(), "c", "[services.c-mq]\nimage = \"rabbitmq\"\n");
+        write(
+            temp.path(),
+            "d",
+            "[dependencies]\ninclude_projects = [\"../c\"]\n",
+        );
+
+        let config_dir = temp.path().join("config");
+        let resolver = ServiceResolver::new(&config_dir).unwrap();
+        let services = resolver.resolve_project(&project(&a)).unwrap();
+
+        assert_eq!(
+            names(&services),
+            vec![
+                ("a-db", "postgres"),
+                ("b-cache", "redis"),
+                ("c-mq", "rabbitmq")
+            ]
+        );
+        assert_eq!(services[2].via_project.as_deref(), Some("c"));
+        assert_eq!(services[2].source_file, c.join(DEFAULT_DEVOBOX_TOML_NAME));
+    }
+
+    #[test]
+    fn reports_duplicates_across_projects() {
+        let temp = tempfile::tempdir().unwrap();
+        let a = write(
+            temp.path(),
+            "a",
+            "[dependencies]\ninclude_projects = [\"../b\", \"../c\"]\n",
+        );
+        let b = write(temp.path(), "b", "[services.mq]\nimage = \"rabbitmq\"\n");
+        let c = write(temp.path(), "c", "[services.mq]\nimage = \"nats\"\n");
+
+        let config_dir = temp.path().join("config");
+        let resolver = ServiceResolver::new(&config_dir).unwrap();
+        let err = resolver.resolve_project(&project(&a)).unwrap_err();
+
+        let conflicts = err.downcast_ref::<ServiceConflicts>().unwrap();
+        assert_eq!(
+            conflicts.0,
+            vec![ServiceConflict {
+                name: "mq".to_string(),
+                first: b.join(DEFAULT_DEVOBOX_TOML_NAME),
+                second: c.join(DEFAULT_DEVOBOX_TOML_NAME),
+            }]
+        );
+    }
+
+    #[test]
+    fn override_takes_the_name_from_dependencies() {
+        let temp = tempfile::tempdir().unwrap();
+        let a = write(
+            temp.path(),
+            "a",
+            "[dependencies]\ninclude_projects = [\"../b\"]\n\n[services.mq]\nimage = \"nats\"\noverride = true\n",
+        );
+        write(temp.path(), "b", "[services.mq]\nimage = \"rabbitmq\"\n");
+
+        let config_dir = temp.path().join("config");
+        let resolver = ServiceResolver::new(&config_dir).unwrap();
+        let root = project(&a);
+        let graph = ProjectGraph::build(root.clone(), &root.path).unwrap();
+        let contributions = resolver
+            .resolve_graph(&graph, resolver.project_services(&root, None).unwrap())
+            .unwrap();
+
+        assert_eq!(names(&contributions[0].services), vec![("mq", "nats")]);
+        assert!(contributions[1].services.is_empty());
+        assert_eq!(contributions[1].shadowed, vec!["mq"]);
+    }
+
+    #[test]
+    fn included_project_refines_a_global_service() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = write(
+            temp.path(),
+            "config",
+            "[dependencies]\ninclude_projects = [\"../api\"]\n\n[services.pg]\nimage = \"postgres:16\"\nports = [\"5432:5432\"]\n",
+        );
+        let api = write(
+            temp.path(),
+            "api",
+            "[services.pg]\nports = [\"5433:5432\"]\n",
+        );
+
+        let app_config =
+            crate::infra::config::load_app_config_from(&config_dir, &config_dir).unwrap();
+        let resolver = ServiceResolver::new(&config_dir).unwrap();
+        let services = resolver
+            .resolve_workspace(&app_config, &config_dir)
+            .unwrap();
+
+        // One pg: the global table with the project's tweak merged over it
+        assert_eq!(names(&services), vec![("pg", "postgres:16")]);
+        assert_eq!(services[0].service.ports, vec!["5433:5432"]);
+        assert_eq!(services[0].source_file, api.join(DEFAULT_DEVOBOX_TOML_NAME));
+        assert_eq!(services[0].via_project.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn workspace_services_record_their_source() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = write(
+            temp.path(),
+            "config",
+            "[dependencies]\ninclude_projects = [\"../dep\"]\n\n[services.pg]\nimage = \"postgres\"\n",
+        );
+        let local = write(
+            temp.path(),
+            "local",
+            "[services.redis]\nimage = \"redis\"\n",
+        );
+        let dep = write(temp.path(), "dep", "[services.mq]\nimage = \"rabbitmq\"\n");
+
+        let app_config = crate::infra::config::load_app_config_from(&config_dir, &local).unwrap();
+        let resolver = ServiceResolver::new(&config_dir).unwrap();
+        let services = resolver.resolve_workspace(&app_config, &local).unwrap();
+
+        let sources: Vec<(&str, &Path, Option<&str>)> = services
+            .iter()
+            .map(|r| {
+                (
+                    r.service.name.as_str(),
+                    r.source_file.as_path(),
+                    r.via_project.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                (
+                    "pg",
+                    config_dir.join(DEFAULT_DEVOBOX_TOML_NAME).as_path(),
+                    None
+                ),
+                (
+                    "redis",
+                    local.join(DEFAULT_DEVOBOX_TOML_NAME).as_path(),
+                    None
+                ),
+                (
+                    "mq",
+                    dep.join(DEFAULT_DEVOBOX_TOML_NAME).as_path(),
+                    Some("dep")
+                ),
+            ]
+        );
+    }
+}
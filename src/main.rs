@@ -1,7 +1,10 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use devobox::cli;
-use devobox::domain::ServiceKind;
+use devobox::cli::hook::HookShell;
+use devobox::cli::project_graph::GraphFormat;
+use devobox::cli::{Placement, Route, RuntimeContext};
+use devobox::domain::{BuildOptions, ServiceKind};
 use devobox::services::CleanupOptions;
 use tracing::info;
 use tracing_subscriber::{EnvFilter, fmt};
@@ -24,10 +27,18 @@ struct Cli {
     #[arg(long)]
     auto_stop: bool,
 
+    /// Shell direto, sem multiplexador (apenas quando nenhum subcomando é fornecido)
+    #[arg(long)]
+    plain: bool,
+
     /// Habilita logs detalhados (debug level)
     #[arg(long, short = 'v', global = true)]
     verbose: bool,
 
+    /// Mostra os comandos podman/docker que seriam executados, sem alterar nada
+    #[arg(long, global = true)]
+    dry_run: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -48,12 +59,24 @@ enum Commands {
         /// Pular limpeza automática de recursos
         #[arg(long)]
         skip_cleanup: bool,
+        /// Ignorar o cache de camadas do build
+        #[arg(long)]
+        no_cache: bool,
+        /// Baixar versões novas das imagens base
+        #[arg(long)]
+        pull: bool,
+        /// Argumento de build CHAVE=valor (repetível; sobrepõe [build] args)
+        #[arg(long = "build-arg", value_name = "CHAVE=valor")]
+        build_args: Vec<String>,
     },
-    /// Reconstrói a imagem e recria containers (alias de 'build')
+    /// Reconstrói do zero: build sem cache e com as imagens base atualizadas
     Rebuild {
         /// Pular limpeza automática de recursos
         #[arg(long)]
         skip_cleanup: bool,
+        /// Argumento de build CHAVE=valor (repetível; sobrepõe [build] args)
+        #[arg(long = "build-arg", value_name = "CHAVE=valor")]
+        build_args: Vec<String>,
     },
     /// Abre um shell dentro do container devobox
     Shell {
@@ -63,6 +86,15 @@ enum Commands {
         /// Para todos os containers ao sair do shell
         #[arg(long)]
         auto_stop: bool,
+        /// Shell direto, sem Zellij/tmux (IDEs, SSH instável, multiplexador aninhado)
+        #[arg(long)]
+        plain: bool,
+    },
+    /// Executa um comando no container devobox com o env do projeto atual
+    Exec {
+        /// Comando e argumentos (ex.: devobox exec cargo test)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        command: Vec<String>,
     },
     /// Abre shell com bancos de dados (atalho para 'shell --with-dbs')
     Dev {
@@ -119,15 +151,64 @@ enum Commands {
         #[arg(long, conflicts_with_all = ["containers", "images", "volumes", "build_cache", "nuke", "all"])]
         reset: bool,
     },
+    /// Painel interativo com o estado de todos os containers
+    Dashboard,
+    /// Integração com o shell: ativa o projeto ao entrar no diretório dele
+    ///
+    /// Adicione ao rc do shell, ex.: eval "$(devobox hook bash)"
+    Hook {
+        /// Shell alvo
+        #[arg(value_enum)]
+        shell: HookShell,
+        /// Imprime as variáveis a exportar para o diretório atual (usado pelo hook)
+        #[arg(long, hide = true)]
+        export: bool,
+    },
     /// Gerenciamento de projetos
     Project {
         #[command(subcommand)]
         action: ProjectAction,
     },
+    /// Sessões do multiplexador (Zellij/tmux) no container
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+    /// Versões da imagem de desenvolvimento
+    Image {
+        #[command(subcommand)]
+        action: ImageAction,
+    },
+    /// Inspeção e validação do devobox.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
     /// Atualiza o devobox para a versão mais recente disponível no GitHub
     Update,
 }
 
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Valida devobox.toml (global, local e dependências)
+    Validate,
+    /// Mostra a configuração efetiva com a origem de cada valor
+    Show,
+    /// Imprime o JSON Schema do devobox.toml (para taplo / Even Better TOML)
+    Schema,
+}
+
+#[derive(Subcommand)]
+enum ImageAction {
+    /// Lista as versões construídas, marcando a atual
+    List,
+    /// Volta o container de desenvolvimento para uma versão anterior
+    Rollback {
+        /// Versão (tag) a usar (default: a anterior à atual)
+        tag: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum ServiceAction {
     /// Inicia serviço(s)
@@ -174,13 +255,73 @@ enum DbAction {
 enum ProjectAction {
     /// Lista projetos disponíveis em ~/code
     List,
-    /// Ativa workspace de um projeto (apenas dentro do container)
+    /// Ativa workspace de um projeto (do host, sobe o container e entra na sessão)
     Up {
         /// Nome do projeto
         name: String,
+        /// Só abre a sessão; os serviços já foram iniciados pelo host
+        #[arg(long, hide = true)]
+        session_only: bool,
     },
     /// Mostra informações do contexto atual
     Info,
+    /// Mostra a árvore de dependências do projeto e os serviços de cada um
+    Graph {
+        /// Nome do projeto (default: projeto do diretório atual)
+        name: Option<String>,
+        /// Formato de saída
+        #[arg(long, value_enum, default_value_t = GraphFormat::Text)]
+        format: GraphFormat,
+    },
+    /// Gera um devobox.toml a partir da stack detectada (Cargo.toml, package.json, ...)
+    Init {
+        /// Diretório do projeto (default: diretório atual)
+        path: Option<std::path::PathBuf>,
+        /// Grava sem pedir confirmação
+        #[arg(long, short = 'y')]
+        yes: bool,
+        /// Sobrescreve um devobox.toml existente
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionAction {
+    /// Lista as sessões com projeto e idade
+    List,
+    /// Anexa a uma sessão (nome da sessão ou do projeto)
+    Attach {
+        /// Nome da sessão ou do projeto
+        name: String,
+    },
+    /// Encerra uma sessão
+    Kill {
+        /// Nome da sessão ou do projeto
+        name: String,
+    },
+    /// Encerra sessões cujo projeto não existe mais
+    Prune {
+        /// Encerra sem pedir confirmação
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+}
+
+impl Commands {
+    /// Where the command has to run; the rest handle both sides themselves
+    fn placement(&self) -> Placement {
+        match self {
+            Commands::Build { .. }
+            | Commands::Rebuild { .. }
+            | Commands::Cleanup { .. }
+            | Commands::Image { .. } => Placement::Host,
+            Commands::Project {
+                action: ProjectAction::Info,
+            } => Placement::Container,
+            _ => Placement::Anywhere,
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -191,27 +332,68 @@ fn main() -> Result<()> {
     let env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_level));
 
-    fmt()
+    let subscriber = fmt()
         .with_env_filter(env_filter)
         .with_target(false) // Hide module path for cleaner CLI output by default
         .with_thread_ids(false)
         .with_thread_names(false)
         .with_file(false)
         .with_line_number(false)
-        .with_level(false) // Cleaner output, relies on color for level
-        .init();
+        .with_level(false); // Cleaner output, relies on color for level
+
+    // The dashboard owns the terminal; log lines would corrupt the screen
+    let machine_output = matches!(
+        cli.command,
+        Some(
+            Commands::Config {
+                action: ConfigAction::Schema
+            } | Commands::Hook { .. }
+                | Commands::Project {
+                    action: ProjectAction::Graph {
+                        format: GraphFormat::Dot,
+                        ..
+                    }
+                }
+        )
+    );
+    if matches!(cli.command, Some(Commands::Dashboard)) {
+        subscriber.with_writer(std::io::sink).init();
+    } else if machine_output {
+        // Keep stdout parseable (JSON, DOT); logs still reach the terminal
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
+
+    devobox::infra::engine::set_dry_run(cli.dry_run);
+    if cli.dry_run {
+        info!(" Modo dry-run: comandos que alteram containers serão apenas exibidos");
+    }
+
+    let placement = cli
+        .command
+        .as_ref()
+        .map_or(Placement::Anywhere, Commands::placement);
+    match RuntimeContext::detect().route(placement) {
+        Route::Local => {}
+        Route::ToHost => cli::forward::to_host()?,
+        Route::ToContainer => {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            return cli::forward::to_container(&cli.config_dir, &args);
+        }
+    }
 
     match cli.command {
         None => {
             // Default behavior: open shell
-            cli::runtime::shell(&cli.config_dir, cli.with_dbs, cli.auto_stop)
+            cli::runtime::shell(&cli.config_dir, cli.with_dbs, cli.auto_stop, cli.plain)
         }
         Some(Commands::Init { skip_cleanup }) => {
             info!(" Passo 1/2: Instalando configurações...");
             cli::setup::install(&cli.config_dir)?;
 
             info!("\n Passo 2/2: Construindo ambiente...");
-            cli::builder::build(&cli.config_dir, skip_cleanup)?;
+            cli::builder::build(&cli.config_dir, skip_cleanup, &BuildOptions::default())?;
 
             info!("\n Setup completo! Use 'devobox' para abrir o shell.");
             Ok(())
@@ -222,14 +404,41 @@ fn main() -> Result<()> {
             info!(" Dica: Edite os arquivos e depois rode 'devobox build'");
             Ok(())
         }
-        Some(Commands::Build { skip_cleanup } | Commands::Rebuild { skip_cleanup }) => {
-            cli::builder::build(&cli.config_dir, skip_cleanup)
+        Some(Commands::Build {
+            skip_cleanup,
+            no_cache,
+            pull,
+            build_args,
+        }) => {
+            let flags = BuildOptions {
+                args: build_args,
+                no_cache,
+                pull,
+                ..Default::default()
+            };
+            cli::builder::build(&cli.config_dir, skip_cleanup, &flags)
+        }
+        Some(Commands::Rebuild {
+            skip_cleanup,
+            build_args,
+        }) => {
+            let flags = BuildOptions {
+                args: build_args,
+                no_cache: true,
+                pull: true,
+                ..Default::default()
+            };
+            cli::builder::build(&cli.config_dir, skip_cleanup, &flags)
         }
         Some(Commands::Shell {
             with_dbs,
             auto_stop,
-        }) => cli::runtime::shell(&cli.config_dir, with_dbs, auto_stop),
-        Some(Commands::Dev { auto_stop }) => cli::runtime::shell(&cli.config_dir, true, auto_stop),
+            plain,
+        }) => cli::runtime::shell(&cli.config_dir, with_dbs, auto_stop, plain),
+        Some(Commands::Exec { command }) => cli::runtime::exec_cmd(&cli.config_dir, command),
+        Some(Commands::Dev { auto_stop }) => {
+            cli::runtime::shell(&cli.config_dir, true, auto_stop, false)
+        }
         Some(Commands::Up {
             dbs_only,
             services_only,
@@ -302,11 +511,74 @@ fn main() -> Result<()> {
             };
             cli::runtime::cleanup(&cli.config_dir, &options)
         }
+        Some(Commands::Dashboard) => cli::dashboard::dashboard(&cli.config_dir),
+        Some(Commands::Hook { shell, export }) => {
+            if export {
+                cli::hook::hook_export(&cli.config_dir, shell)
+            } else {
+                cli::hook::hook_init(shell)
+            }
+        }
+        Some(Commands::Session { action }) => match action {
+            SessionAction::List => cli::session::session_list(&cli.config_dir),
+            SessionAction::Attach { name } => cli::session::session_attach(&cli.config_dir, &name),
+            SessionAction::Kill { name } => cli::session::session_kill(&cli.config_dir, &name),
+            SessionAction::Prune { yes } => cli::session::session_prune(&cli.config_dir, yes),
+        },
         Some(Commands::Project { action }) => match action {
             ProjectAction::List => cli::runtime::project_list(&cli.config_dir),
-            ProjectAction::Up { name } => cli::runtime::project_up(&cli.config_dir, &name),
-            ProjectAction::Info => cli::runtime::project_info(),
+            ProjectAction::Up { name, session_only } => {
+                cli::runtime::project_up(&cli.config_dir, &name, session_only)
+            }
+            ProjectAction::Info => cli::runtime::project_info(&cli.config_dir),
+            ProjectAction::Graph { name, format } => {
+                cli::project_graph::project_graph(&cli.config_dir, name.as_deref(), format)
+            }
+            ProjectAction::Init { path, yes, force } => {
+                cli::project_init::project_init(path.as_deref(), yes, force)
+            }
+        },
+        Some(Commands::Image { action }) => match action {
+            ImageAction::List => cli::image::image_list(&cli.config_dir),
+            ImageAction::Rollback { tag } => {
+                cli::image::image_rollback(&cli.config_dir, tag.as_deref())
+            }
+        },
+        Some(Commands::Config { action }) => match action {
+            ConfigAction::Validate => cli::config::validate(&cli.config_dir),
+            ConfigAction::Show => cli::config::show(&cli.config_dir),
+            ConfigAction::Schema => cli::config::schema(),
         },
         Some(Commands::Update) => cli::update::update(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(context: RuntimeContext, args: &[&str]) -> Route {
+        let cli =
+            Cli::try_parse_from(std::iter::once("devobox").chain(args.iter().copied())).unwrap();
+        let placement = cli
+            .command
+            .as_ref()
+            .map_or(Placement::Anywhere, Commands::placement);
+        context.route(placement)
+    }
+
+    #[test]
+    fn test_commands_route_to_where_they_run() {
+        let host = RuntimeContext::Host;
+        let container = RuntimeContext::Container;
+
+        assert_eq!(route(host, &["project", "info"]), Route::ToContainer);
+        assert_eq!(route(container, &["project", "info"]), Route::Local);
+        assert_eq!(route(container, &["build"]), Route::ToHost);
+        assert_eq!(route(container, &["cleanup", "--images"]), Route::ToHost);
+        assert_eq!(route(host, &["build"]), Route::Local);
+        assert_eq!(route(host, &["project", "list"]), Route::Local);
+        assert_eq!(route(container, &["status"]), Route::Local);
+        assert_eq!(route(host, &[]), Route::Local);
+    }
+}
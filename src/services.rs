@@ -1,9 +1,16 @@
 mod container_service;
+mod image_history;
+mod multiplexer;
 mod orchestrator;
 mod system_service;
+mod tmux_service;
+mod zellij_layout;
 mod zellij_service;
 
 pub use container_service::ContainerService;
+pub use image_history::{DEFAULT_KEEP_IMAGES, ImageHistory, ImageVersion};
+pub use multiplexer::{Multiplexer, NoMultiplexer, ProjectLayoutInfo, SessionInfo};
 pub use orchestrator::{CleanupOptions, Orchestrator};
 pub use system_service::SystemService;
-pub use zellij_service::{ProjectLayoutInfo, ZellijService};
+pub use tmux_service::TmuxService;
+pub use zellij_service::ZellijService;
@@ -1,6 +1,9 @@
+use super::multiplexer::Multiplexer;
 use crate::domain::traits::ContainerHealthStatus;
-use crate::domain::{ContainerRuntime, ContainerSpec, ContainerState};
-use anyhow::{Result, bail};
+use crate::domain::{
+    ContainerFilter, ContainerInfo, ContainerRuntime, ContainerSpec, ContainerState,
+};
+use anyhow::{Context, Result, bail};
 use std::path::Path;
 use std::sync::Arc;
 use tracing::{info, warn};
@@ -19,6 +22,11 @@ impl ContainerService {
         self.runtime.get_container(name)
     }
 
+    /// Snapshot of every container matching the filter, from a single runtime query
+    pub fn list(&self, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>> {
+        self.runtime.list_containers(filter)
+    }
+
     pub fn ensure_running(&self, name: &str) -> Result<()> {
         let container = self.runtime.get_container(name)?;
 
@@ -68,18 +76,50 @@ impl ContainerService {
         }
     }
 
+    /// Stops a container already known to be running, skipping the state query
+    pub fn stop_running(&self, name: &str) -> Result<()> {
+        info!(" Parando {name}...");
+        self.runtime.stop_container(name)
+    }
+
     pub fn recreate(&self, spec: &ContainerSpec) -> Result<()> {
         self.runtime.remove_container(spec.name)?;
         self.runtime.create_container(spec)
     }
 
+    /// Opens a shell in the container inside `multiplexer`'s session
+    /// (default: `devobox-default`)
     pub fn exec_shell(
         &self,
         container: &str,
         workdir: Option<&Path>,
+        env: &[String],
+        multiplexer: &dyn Multiplexer,
         session_name: Option<&str>,
     ) -> Result<()> {
-        self.runtime.exec_shell(container, workdir, session_name)
+        let command = multiplexer.attach_command(session_name.unwrap_or("devobox-default"));
+        let command: Vec<&str> = command.iter().map(String::as_str).collect();
+        self.runtime
+            .exec_command(container, workdir, env, &command)
+            .with_context(|| format!("abrindo shell em {container}"))
+    }
+
+    pub fn exec_command(
+        &self,
+        container: &str,
+        workdir: Option<&Path>,
+        env: &[String],
+        command: &[&str],
+    ) -> Result<()> {
+        self.runtime.exec_command(container, workdir, env, command)
+    }
+
+    pub fn exec_output(&self, container: &str, command: &[&str]) -> Result<String> {
+        self.runtime.exec_output(container, command)
+    }
+
+    pub fn logs(&self, name: &str, tail: usize) -> Result<String> {
+        self.runtime.container_logs(name, tail)
     }
 
     pub fn is_command_available(&self, cmd: &str) -> bool {
@@ -108,6 +148,7 @@ mod tests {
             healthcheck_interval: Some("1s".to_string()),
             healthcheck_timeout: Some("1s".to_string()),
             healthcheck_retries: Some(1),
+            project: None,
         };
 
         let spec = svc.to_spec();
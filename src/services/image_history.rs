@@ -0,0 +1,239 @@
+use crate::services::SystemService;
+use anyhow::{Result, bail};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Previous dev image versions kept when `[build] keep_images` isn't set
+pub const DEFAULT_KEEP_IMAGES: usize = 3;
+
+/// One build of a dev image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageVersion {
+    /// Build time, `YYYYMMDD-HHMMSS` (UTC)
+    pub tag: String,
+    /// `repository:tag`
+    pub reference: String,
+    pub id: String,
+    /// The configured image tag points at this version
+    pub current: bool,
+}
+
+/// Versions of a dev image
+///
+/// Each build is tagged `<repository>:<timestamp>` and the configured image
+/// (`devobox-img`) is moved to it, so older builds stay around for a rollback
+/// until the retention policy drops them.
+#[derive(Debug, Clone)]
+pub struct ImageHistory {
+    system: Arc<SystemService>,
+    image: String,
+    keep: usize,
+}
+
+impl ImageHistory {
+    /// `image` is the configured tag; `keep` previous versions survive a build
+    pub fn new(system: Arc<SystemService>, image: &str, keep: usize) -> Self {
+        Self {
+            system,
+            image: image.to_string(),
+            keep,
+        }
+    }
+
+    /// The image repository, without its tag
+    pub fn repository(&self) -> &str {
+        repository(&self.image)
+    }
+
+    /// Reference of a build made at `time`
+    pub fn version_at(&self, time: SystemTime) -> String {
+        format!("{}:{}", self.repository(), timestamp(time))
+    }
+
+    /// Every version, newest first
+    pub fn versions(&self) -> Result<Vec<ImageVersion>> {
+        let images = self.system.list_images(self.repository())?;
+        let current = images
+            .iter()
+            .find(|image| image.reference() == self.reference())
+            .map(|image| image.id.clone());
+
+        let mut versions: Vec<ImageVersion> = images
+            .into_iter()
+            .filter(|image| is_version_tag(&image.tag))
+            .map(|image| ImageVersion {
+                reference: image.reference(),
+                current: current.as_ref() == Some(&image.id),
+                tag: image.tag,
+                id: image.id,
+            })
+            .collect();
+        versions.sort_by(|a, b| b.tag.cmp(&a.tag));
+        Ok(versions)
+    }
+
+    /// Points the configured image at `version` (a tag or a full reference)
+    pub fn activate(&self, version: &str) -> Result<ImageVersion> {
+        let tag = version.rsplit_once(':').map_or(version, |(_, tag)| tag);
+        let Some(found) = self.versions()?.into_iter().find(|v| v.tag == tag) else {
+            bail!(
+                "Versão '{}' de {} não encontrada. Veja 'devobox image list'",
+                version,
+                self.repository()
+            );
+        };
+
+        self.system.tag_image(&found.reference, &self.reference())?;
+        Ok(found)
+    }
+
+    /// The version before the current one
+    pub fn previous(&self) -> Result<ImageVersion> {
+        let versions = self.versions()?;
+        let current = versions.iter().position(|v| v.current);
+        match current.and_then(|i| versions.get(i + 1)) {
+            Some(previous) => Ok(previous.clone()),
+            None => bail!(
+                "Nenhuma versão anterior de {} para voltar",
+                self.repository()
+            ),
+        }
+    }
+
+    /// Removes versions beyond the newest `keep + 1`, never the current one.
+    /// Returns the removed references
+    pub fn apply_retention(&self) -> Result<Vec<String>> {
+        let mut removed = Vec::new();
+        for version in self.versions()?.into_iter().skip(self.keep + 1) {
+            if version.current {
+                continue;
+            }
+            // An image still used by a container can't go; the next build retries
+            match self.system.remove_image(&version.reference) {
+                Ok(()) => removed.push(version.reference),
+                Err(e) => warn!("  Falha ao remover {}: {}", version.reference, e),
+            }
+        }
+        Ok(removed)
+    }
+
+    fn reference(&self) -> String {
+        if self.image.len() > self.repository().len() {
+            self.image.clone()
+        } else {
+            format!("{}:latest", self.image)
+        }
+    }
+}
+
+/// `image` without its tag; a `:` before the last `/` is a registry port
+fn repository(image: &str) -> &str {
+    match image.rsplit_once(':') {
+        Some((repository, tag)) if !tag.contains('/') => repository,
+        _ => image,
+    }
+}
+
+fn is_version_tag(tag: &str) -> bool {
+    tag.len() == 15
+        && tag.char_indices().all(|(i, c)| match i {
+            8 => c == '-',
+            _ => c.is_ascii_digit(),
+        })
+}
+
+/// `YYYYMMDD-HHMMSS` in UTC
+fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        rest / 3_600,
+        rest % 3_600 / 60,
+        rest % 60
+    )
+}
+
+/// Gregorian date of a day count since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockRuntime;
+    use std::time::Duration;
+
+    fn history(mock: &Arc<MockRuntime>, keep: usize) -> ImageHistory {
+        ImageHistory::new(
+            Arc::new(SystemService::new(mock.clone())),
+            "devobox-img",
+            keep,
+        )
+    }
+
+    #[test]
+    fn test_version_tags() {
+        let mock = Arc::new(MockRuntime::new());
+        let time = UNIX_EPOCH + Duration::from_secs(1_792_334_096);
+        assert_eq!(
+            history(&mock, 3).version_at(time),
+            "devobox-img:20261018-143456"
+        );
+        assert_eq!(timestamp(UNIX_EPOCH), "19700101-000000");
+        assert!(is_version_tag("20261018-143456"));
+        assert!(!is_version_tag("latest"));
+
+        assert_eq!(repository("devobox:test"), "devobox");
+        assert_eq!(repository("registry:5000/devobox"), "registry:5000/devobox");
+    }
+
+    #[test]
+    fn test_retention_keeps_current_and_previous() {
+        let mock = Arc::new(MockRuntime::new());
+        for (i, tag) in ["20260101-000000", "20260201-000000", "20260301-000000"]
+            .iter()
+            .enumerate()
+        {
+            mock.add_image(&format!("devobox-img:{tag}"), &format!("id{i}"));
+        }
+        mock.add_image("devobox-img:latest", "id0");
+        let history = history(&mock, 1);
+
+        let versions = history.versions().unwrap();
+        assert_eq!(versions[0].tag, "20260301-000000");
+        assert!(versions[2].current);
+
+        // The oldest is current (rolled back to): it stays
+        assert!(history.apply_retention().unwrap().is_empty());
+
+        history.activate("20260301-000000").unwrap();
+        assert_eq!(
+            history.apply_retention().unwrap(),
+            vec!["devobox-img:20260101-000000"]
+        );
+        assert_eq!(history.previous().unwrap().tag, "20260201-000000");
+    }
+
+    #[test]
+    fn test_activate_unknown_version() {
+        let mock = Arc::new(MockRuntime::new());
+        let err = history(&mock, 3).activate("20200101-000000").unwrap_err();
+        assert!(err.to_string().contains("devobox image list"));
+    }
+}
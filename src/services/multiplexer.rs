@@ -0,0 +1,293 @@
+use crate::domain::{LayoutConfig, LayoutPane, LayoutTab};
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tracing::info;
+
+pub struct ProjectLayoutInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub startup_command: Option<String>,
+    /// `KEY=value` entries for the tab's pane
+    pub env: Vec<String>,
+    /// The project's `shell`, for panes without a command
+    pub shell: Option<String>,
+    /// Tabs declared in the project's `[layout]`
+    pub layout: Option<LayoutConfig>,
+}
+
+/// A session as reported by the multiplexer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub name: String,
+    /// Time since the session was created, when the multiplexer reports it
+    pub age: Option<Duration>,
+    /// The session ended but can be resurrected (Zellij)
+    pub exited: bool,
+}
+
+/// A terminal multiplexer holding one session per project (Zellij, tmux, ...)
+pub trait Multiplexer {
+    /// Name shown to the user
+    fn name(&self) -> &'static str;
+
+    /// Checks if the multiplexer is installed
+    fn is_available(&self) -> bool;
+
+    /// Attaches to `session_name`, creating it first with one tab per entry of
+    /// the main project's layout followed by each dependency's
+    fn create_with_layout(
+        &self,
+        session_name: &str,
+        main_project: &ProjectLayoutInfo,
+        dependencies: &[ProjectLayoutInfo],
+    ) -> Result<()>;
+
+    /// Attaches to `session_name`, creating a plain session in `workdir` if needed
+    fn attach_or_create(&self, session_name: &str, workdir: &Path) -> Result<()>;
+
+    /// Command printing the sessions for `parse_sessions`; succeeds even when
+    /// there are none. Empty when the backend keeps no sessions
+    fn list_command(&self) -> Vec<String>;
+
+    /// Reads the output of `list_command`
+    fn parse_sessions(&self, output: &str) -> Vec<SessionInfo>;
+
+    /// Command ending a session and everything running in it
+    fn kill_command(&self, session_name: &str) -> Vec<String>;
+
+    /// Sessions on this machine
+    fn list_sessions(&self) -> Result<Vec<SessionInfo>> {
+        let command = self.list_command();
+        if command.is_empty() || !self.is_available() {
+            return Ok(Vec::new());
+        }
+        Ok(self.parse_sessions(&run(&command)?))
+    }
+
+    /// Ends a session on this machine
+    fn kill_session(&self, session_name: &str) -> Result<()> {
+        let command = self.kill_command(session_name);
+        if command.is_empty() {
+            bail!(
+                "Sem multiplexador configurado: não há sessão {} para encerrar",
+                session_name
+            );
+        }
+        if !self.is_available() {
+            bail!("{} não está instalado", self.name());
+        }
+        run(&command)
+            .map(|_| ())
+            .with_context(|| format!("Falha ao encerrar sessão: {}", session_name))
+    }
+
+    /// Command that attaches to (or creates) `session_name`, run through
+    /// `podman exec` when the shell is opened from the host
+    fn attach_command(&self, session_name: &str) -> Vec<String>;
+}
+
+/// `multiplexer = "none"` or `shell --plain`: a login shell, no sessions to
+/// come back to
+pub struct NoMultiplexer {
+    /// The project's `shell` (default: `$SHELL`)
+    shell: Option<String>,
+}
+
+impl NoMultiplexer {
+    pub fn new() -> Self {
+        Self { shell: None }
+    }
+
+    pub fn with_shell(shell: Option<&str>) -> Self {
+        Self {
+            shell: shell.map(String::from),
+        }
+    }
+
+    fn shell(&self, workdir: &Path, env: &[String]) -> Result<()> {
+        let shell = self.shell.clone().unwrap_or_else(default_shell);
+        let mut cmd = Command::new(shell);
+        cmd.arg("-l").current_dir(workdir);
+        for entry in env {
+            if let Some((key, value)) = entry.split_once('=') {
+                cmd.env(key, value);
+            }
+        }
+
+        let status = cmd
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Falha ao abrir shell")?;
+
+        if !status.success() {
+            bail!("shell retornou status {:?}", status);
+        }
+        Ok(())
+    }
+}
+
+impl Multiplexer for NoMultiplexer {
+    fn name(&self) -> &'static str {
+        "shell"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn create_with_layout(
+        &self,
+        _session_name: &str,
+        main_project: &ProjectLayoutInfo,
+        dependencies: &[ProjectLayoutInfo],
+    ) -> Result<()> {
+        if !dependencies.is_empty() {
+            info!(
+                "  Sem multiplexador: abrindo apenas {} (dependências sem abas)",
+                main_project.name
+            );
+        }
+        self.shell(&main_project.path, &main_project.env)
+    }
+
+    fn attach_or_create(&self, _session_name: &str, workdir: &Path) -> Result<()> {
+        self.shell(workdir, &[])
+    }
+
+    fn list_command(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn parse_sessions(&self, _output: &str) -> Vec<SessionInfo> {
+        Vec::new()
+    }
+
+    fn kill_command(&self, _session_name: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn attach_command(&self, _session_name: &str) -> Vec<String> {
+        match &self.shell {
+            Some(shell) => vec![shell.clone(), "-l".to_string()],
+            None => ["sh", "-c", "exec \"${SHELL:-/bin/bash}\" -l"]
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Default for NoMultiplexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The tabs a project opens: its `[layout] tabs`, or one pane running its
+/// `startup_command`
+pub(crate) fn project_tabs(project: &ProjectLayoutInfo) -> Vec<LayoutTab> {
+    match &project.layout {
+        Some(layout) if !layout.tabs.is_empty() => layout.tabs.clone(),
+        _ => vec![LayoutTab {
+            panes: vec![LayoutPane {
+                command: project.startup_command.clone(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    }
+}
+
+/// Index of the tab to focus: the one marked `focus`, else the first
+pub(crate) fn focused_tab(tabs: &[LayoutTab]) -> usize {
+    tabs.iter().position(|tab| tab.focus).unwrap_or(0)
+}
+
+/// Entries of the project's env the session doesn't already carry
+pub(crate) fn extra_env<'a>(
+    project: &'a ProjectLayoutInfo,
+    session_env: &[String],
+) -> Vec<&'a str> {
+    project
+        .env
+        .iter()
+        .filter(|entry| !session_env.contains(entry))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Keys of the session env the project doesn't set, which its panes must
+/// unset so they don't run with the main project's values
+pub(crate) fn unset_env<'a>(
+    project: &ProjectLayoutInfo,
+    session_env: &'a [String],
+) -> Vec<&'a str> {
+    session_env
+        .iter()
+        .map(|entry| env_key(entry))
+        .filter(|key| !project.env.iter().any(|entry| env_key(entry) == *key))
+        .collect()
+}
+
+fn env_key(entry: &str) -> &str {
+    entry.split_once('=').map_or(entry, |(key, _)| key)
+}
+
+pub(crate) fn join(base: &Path, relative: Option<&Path>) -> PathBuf {
+    match relative {
+        Some(path) => base.join(path),
+        None => base.to_path_buf(),
+    }
+}
+
+/// `sh -c` wrapper, for list commands that must not fail without a server
+pub(crate) fn quiet(command: &str) -> Vec<String> {
+    vec![
+        "sh".to_string(),
+        "-c".to_string(),
+        format!("{command} 2>/dev/null || true"),
+    ]
+}
+
+/// Runs a command locally and returns its stdout
+fn run(command: &[String]) -> Result<String> {
+    let output = Command::new(&command[0])
+        .args(&command[1..])
+        .output()
+        .with_context(|| format!("executando {}", command.join(" ")))?;
+
+    if !output.status.success() {
+        bail!(
+            "{} falhou: {}",
+            command.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The user's shell, for panes and sessions without a command
+pub(crate) fn default_shell() -> String {
+    std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_shell_uses_project_shell() {
+        assert_eq!(
+            NoMultiplexer::with_shell(Some("zsh")).attach_command("devobox-app"),
+            vec!["zsh", "-l"]
+        );
+        assert_eq!(
+            NoMultiplexer::new().attach_command("devobox-app"),
+            vec!["sh", "-c", "exec \"${SHELL:-/bin/bash}\" -l"]
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use crate::domain::Service;
 use crate::domain::traits::ContainerHealthStatus;
+use crate::domain::{ContainerFilter, ContainerState, Service, parse_duration};
 use crate::services::{ContainerService, SystemService};
 use anyhow::Result;
 use std::sync::Arc;
@@ -53,6 +53,8 @@ impl Orchestrator {
     }
 
     /// Stops all containers in the list, continuing even if individual operations fail
+    ///
+    /// States come from one listing, so only running containers are touched.
     pub fn stop_all(&self, container_names: &[String]) -> Result<()> {
         if container_names.is_empty() {
             return Ok(());
@@ -60,8 +62,36 @@ impl Orchestrator {
 
         info!(" Encerrando todos os containers...");
 
+        // One listing answers every state; if it fails, each container is
+        // inspected on its own instead of leaving everything running
+        let snapshot = match self
+            .container_service
+            .list(&ContainerFilter::names(container_names.iter().cloned()))
+        {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                warn!("  Falha ao listar containers ({}); parando um a um", e);
+                None
+            }
+        };
+
         for name in container_names {
-            match self.container_service.stop(name) {
+            let result = match &snapshot {
+                Some(snapshot) => {
+                    let running = snapshot
+                        .iter()
+                        .any(|info| info.name == *name && info.state == ContainerState::Running);
+
+                    if !running {
+                        debug!("Container {} já está parado ou não foi criado", name);
+                        continue;
+                    }
+                    self.container_service.stop_running(name)
+                }
+                None => self.container_service.stop(name),
+            };
+
+            match result {
                 Ok(_) => debug!("Container {} parado com sucesso", name),
                 Err(e) => error!("  Falha ao parar {}: {}", name, e),
             }
@@ -240,22 +270,6 @@ impl Orchestrator {
     }
 }
 
-fn parse_duration(s: &str) -> Result<Duration> {
-    let s = s.trim();
-    if let Some(stripped) = s.strip_suffix("ms") {
-        let millis: u64 = stripped.parse()?;
-        Ok(Duration::from_millis(millis))
-    } else if let Some(stripped) = s.strip_suffix('s') {
-        let secs: u64 = stripped.parse()?;
-        Ok(Duration::from_secs(secs))
-    } else if let Some(stripped) = s.strip_suffix('m') {
-        let mins: u64 = stripped.parse()?;
-        Ok(Duration::from_secs(mins * 60))
-    } else {
-        Err(anyhow::anyhow!("Formato de duração inválido: {}", s))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +305,10 @@ mod tests {
         assert!(commands.contains(&"stop:devobox".to_string()));
         assert!(commands.contains(&"stop:pg".to_string()));
         assert!(commands.contains(&"stop:redis".to_string()));
+
+        // One listing replaces the per-container inspects
+        assert!(commands.contains(&"list_containers".to_string()));
+        assert!(!commands.iter().any(|c| c.starts_with("get_container:")));
     }
 
     #[test]
@@ -307,6 +325,7 @@ mod tests {
 
         assert_eq!(mock.get_state("devobox"), Some(ContainerState::Stopped));
         assert_eq!(mock.get_state("pg"), Some(ContainerState::Stopped));
+        assert!(!mock.get_commands().contains(&"stop:pg".to_string()));
     }
 
     #[test]
@@ -327,6 +346,23 @@ mod tests {
         assert!(commands.contains(&"stop:pg".to_string()));
     }
 
+    #[test]
+    fn test_stop_all_falls_back_when_listing_fails() {
+        let (orchestrator, mock) = create_test_orchestrator();
+
+        mock.add_container("devobox", ContainerState::Running);
+        mock.add_container("pg", ContainerState::Stopped);
+        mock.set_fail_on("list_containers");
+
+        let containers = vec!["devobox".to_string(), "pg".to_string()];
+        assert!(orchestrator.stop_all(&containers).is_ok());
+
+        assert_eq!(mock.get_state("devobox"), Some(ContainerState::Stopped));
+        let commands = mock.get_commands();
+        assert!(commands.contains(&"get_container:pg".to_string()));
+        assert!(!commands.contains(&"stop:pg".to_string()));
+    }
+
     #[test]
     fn test_stop_all_with_empty_list() {
         let (orchestrator, _mock) = create_test_orchestrator();
@@ -352,6 +388,7 @@ mod tests {
             healthcheck_interval: None,
             healthcheck_timeout: None,
             healthcheck_retries: None,
+            project: None,
         };
         let svc2 = Service {
             name: "redis".to_string(),
@@ -364,6 +401,7 @@ mod tests {
             healthcheck_interval: None,
             healthcheck_timeout: None,
             healthcheck_retries: None,
+            project: None,
         };
 
         mock.add_container(&svc1.name, ContainerState::Stopped);
@@ -397,6 +435,7 @@ mod tests {
             healthcheck_interval: None,
             healthcheck_timeout: None,
             healthcheck_retries: None,
+            project: None,
         };
         let svc2 = Service {
             name: "devobox".to_string(),
@@ -409,6 +448,7 @@ mod tests {
             healthcheck_interval: None,
             healthcheck_timeout: None,
             healthcheck_retries: None,
+            project: None,
         };
 
         mock.add_container(&svc1.name, ContainerState::Running);
@@ -438,6 +478,7 @@ mod tests {
             healthcheck_interval: None,
             healthcheck_timeout: None,
             healthcheck_retries: None,
+            project: None,
         };
         let svc2 = Service {
             name: "redis".to_string(),
@@ -450,6 +491,7 @@ mod tests {
             healthcheck_interval: None,
             healthcheck_timeout: None,
             healthcheck_retries: None,
+            project: None,
         };
 
         mock.add_container(&svc1.name, ContainerState::Stopped);
@@ -588,6 +630,7 @@ mod tests {
             healthcheck_interval: Some("1s".to_string()),
             healthcheck_timeout: Some("1s".to_string()),
             healthcheck_retries: Some(1),
+            project: None,
         };
 
         mock.add_container(&svc.name, ContainerState::Stopped);
@@ -628,6 +671,7 @@ mod tests {
             healthcheck_interval: Some("1s".to_string()),
             healthcheck_timeout: Some("1s".to_string()),
             healthcheck_retries: Some(1), // Fails after 1 retry
+            project: None,
         };
 
         mock.add_container(&svc.name, ContainerState::Stopped);
@@ -667,6 +711,7 @@ mod tests {
             healthcheck_interval: None,
             healthcheck_timeout: None,
             healthcheck_retries: None,
+            project: None,
         };
 
         mock.add_container(&svc.name, ContainerState::Stopped);
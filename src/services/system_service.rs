@@ -1,4 +1,4 @@
-use crate::domain::ContainerRuntime;
+use crate::domain::{BuildOptions, ContainerRuntime, ImageInfo};
 use anyhow::Result;
 use std::path::Path;
 use std::sync::Arc;
@@ -14,8 +14,27 @@ impl SystemService {
         Self { runtime }
     }
 
-    pub fn build_image(&self, tag: &str, containerfile: &Path, context: &Path) -> Result<()> {
-        self.runtime.build_image(tag, containerfile, context)
+    pub fn build_image(
+        &self,
+        tag: &str,
+        containerfile: &Path,
+        context: &Path,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        self.runtime
+            .build_image(tag, containerfile, context, options)
+    }
+
+    pub fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        self.runtime.list_images(repository)
+    }
+
+    pub fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        self.runtime.tag_image(source, target)
+    }
+
+    pub fn remove_image(&self, reference: &str) -> Result<()> {
+        self.runtime.remove_image(reference)
     }
 
     pub fn prune_containers(&self) -> Result<()> {
@@ -104,7 +123,8 @@ mod tests {
         let containerfile = std::path::Path::new("/tmp/Containerfile");
         let context = std::path::Path::new("/tmp");
 
-        let result = service.build_image("test-img", containerfile, context);
+        let result =
+            service.build_image("test-img", containerfile, context, &BuildOptions::default());
         assert!(result.is_ok());
 
         let commands = mock.get_commands();
@@ -0,0 +1,577 @@
+use super::multiplexer::{
+    Multiplexer, ProjectLayoutInfo, SessionInfo, default_shell, extra_env, focused_tab, join,
+    project_tabs, quiet, unset_env,
+};
+use crate::domain::{LayoutPane, SplitDirection};
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Runs a tmux command and returns its stdout
+type Run<'a> = dyn FnMut(&[String]) -> Result<String> + 'a;
+
+/// Manages tmux sessions for projects
+///
+/// Project tabs become windows and panes become splits. Layout templates are
+/// Zellij KDL, so projects using one get the default window under tmux.
+pub struct TmuxService {
+    /// Shell of sessions created without a layout (default: tmux's)
+    shell: Option<String>,
+}
+
+impl TmuxService {
+    pub fn new() -> Self {
+        Self { shell: None }
+    }
+
+    pub fn with_shell(shell: Option<&str>) -> Self {
+        Self {
+            shell: shell.map(String::from),
+        }
+    }
+
+    fn session_exists(&self, session_name: &str) -> bool {
+        Command::new("tmux")
+            .args(["has-session", "-t", &exact(session_name)])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    /// Attaches the terminal, or switches to the session when already inside tmux
+    fn attach(&self, session_name: &str) -> Result<()> {
+        let verb = if std::env::var_os("TMUX").is_some() {
+            "switch-client"
+        } else {
+            "attach-session"
+        };
+
+        let status = Command::new("tmux")
+            .args([verb, "-t", &exact(session_name)])
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Falha ao anexar à sessão do tmux")?;
+
+        if !status.success() {
+            bail!("Anexar à sessão do tmux falhou");
+        }
+        Ok(())
+    }
+
+    /// Creates the detached session: a window per tab, a split per pane
+    fn build_session(
+        &self,
+        run: &mut Run,
+        session_name: &str,
+        main_project: &ProjectLayoutInfo,
+        dependencies: &[ProjectLayoutInfo],
+    ) -> Result<()> {
+        let mut focus = None;
+        let mut first_window = true;
+
+        for (index, project) in std::iter::once(main_project)
+            .chain(dependencies)
+            .enumerate()
+        {
+            let is_main = index == 0;
+            if project
+                .layout
+                .as_ref()
+                .is_some_and(|layout| layout.template.is_some())
+            {
+                warn!(
+                    "  Template de layout de {} é KDL do Zellij; usando a janela padrão no tmux",
+                    project.name
+                );
+            }
+
+            // The template fallback is the default tab
+            let mut layout_free = ProjectLayoutInfo {
+                name: project.name.clone(),
+                path: project.path.clone(),
+                startup_command: project.startup_command.clone(),
+                env: Vec::new(),
+                shell: project.shell.clone(),
+                layout: project.layout.clone(),
+            };
+            if let Some(layout) = &mut layout_free.layout {
+                layout.template = None;
+            }
+            let tabs = project_tabs(&layout_free);
+            let focused = focused_tab(&tabs);
+
+            // The main project's env goes to the session, the rest per pane
+            let context = if is_main {
+                PaneContext {
+                    env: Vec::new(),
+                    unset: Vec::new(),
+                    shell: project.shell.as_deref(),
+                }
+            } else {
+                PaneContext {
+                    env: extra_env(project, &main_project.env),
+                    unset: unset_env(project, &main_project.env),
+                    shell: project.shell.as_deref(),
+                }
+            };
+
+            for (i, tab) in tabs.iter().enumerate() {
+                let cwd = join(&project.path, tab.cwd.as_deref());
+                let (first, first_cwd) = first_leaf(&tab.panes, &cwd);
+                let name = tab.name.as_deref().unwrap_or(&project.name);
+
+                let mut args = if first_window {
+                    first_window = false;
+                    let mut args = args(&["new-session", "-d", "-s", session_name]);
+                    for entry in &main_project.env {
+                        args.extend(["-e".to_string(), entry.clone()]);
+                    }
+                    args
+                } else {
+                    args(&[
+                        "new-window",
+                        "-d",
+                        "-t",
+                        &format!("{}:", exact(session_name)),
+                    ])
+                };
+                args.extend(["-n".to_string(), name.to_string()]);
+                let window_pane = new_pane(run, args, &context, &first_cwd, first)?;
+
+                let nested = lay_out(
+                    run,
+                    &tab.panes,
+                    tab.split,
+                    window_pane.clone(),
+                    &cwd,
+                    &context,
+                )?;
+                if is_main && i == focused {
+                    focus = Some(nested.unwrap_or(window_pane));
+                }
+            }
+        }
+
+        if let Some(pane) = focus {
+            run(&args(&["select-window", "-t", &pane]))?;
+            run(&args(&["select-pane", "-t", &pane]))?;
+        }
+        Ok(())
+    }
+}
+
+impl Multiplexer for TmuxService {
+    fn name(&self) -> &'static str {
+        "tmux"
+    }
+
+    fn is_available(&self) -> bool {
+        Command::new("which")
+            .arg("tmux")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    fn create_with_layout(
+        &self,
+        session_name: &str,
+        main_project: &ProjectLayoutInfo,
+        dependencies: &[ProjectLayoutInfo],
+    ) -> Result<()> {
+        if !self.is_available() {
+            bail!(
+                "tmux não está instalado.\n\
+                 Instale com: mise install tmux"
+            );
+        }
+
+        if self.session_exists(session_name) {
+            info!("  Anexando à sessão existente: {}", session_name);
+            return self.attach(session_name);
+        }
+
+        info!("  Criando nova sessão com layout: {}", session_name);
+        let mut run = |args: &[String]| tmux(args);
+        if let Err(e) = self.build_session(&mut run, session_name, main_project, dependencies) {
+            // Don't leave a half-built session behind
+            let _ = self.kill_session(session_name);
+            return Err(e);
+        }
+        self.attach(session_name)
+    }
+
+    fn attach_or_create(&self, session_name: &str, workdir: &Path) -> Result<()> {
+        if !self.is_available() {
+            bail!(
+                "tmux não está instalado.\n\
+                 Instale com: mise install tmux"
+            );
+        }
+
+        if self.session_exists(session_name) {
+            info!("  Anexando à sessão existente: {}", session_name);
+            return self.attach(session_name);
+        }
+
+        info!("  Criando nova sessão: {}", session_name);
+        let mut new_session = args(&[
+            "new-session",
+            "-d",
+            "-s",
+            session_name,
+            "-c",
+            &workdir.to_string_lossy(),
+        ]);
+        new_session.extend(self.shell.clone());
+        tmux(&new_session)?;
+        self.attach(session_name)
+    }
+
+    fn list_command(&self) -> Vec<String> {
+        quiet("tmux list-sessions -F '#{session_created} #{session_name}'")
+    }
+
+    fn parse_sessions(&self, output: &str) -> Vec<SessionInfo> {
+        let now = SystemTime::now();
+        output
+            .lines()
+            .filter_map(|line| {
+                let (created, name) = line.trim().split_once(' ')?;
+                let created = UNIX_EPOCH + Duration::from_secs(created.parse().ok()?);
+                Some(SessionInfo {
+                    name: name.to_string(),
+                    age: now.duration_since(created).ok(),
+                    exited: false,
+                })
+            })
+            .collect()
+    }
+
+    fn kill_command(&self, session_name: &str) -> Vec<String> {
+        args(&["tmux", "kill-session", "-t", &exact(session_name)])
+    }
+
+    fn attach_command(&self, session_name: &str) -> Vec<String> {
+        let mut command = args(&["tmux", "new-session", "-A", "-s", session_name]);
+        command.extend(self.shell.clone());
+        command
+    }
+}
+
+impl Default for TmuxService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates the other siblings by splitting from the previous one (`anchor`
+/// already holds the first), then fills nested panes into their placeholders.
+/// Returns the pane marked `focus`, if any
+fn lay_out(
+    run: &mut Run,
+    panes: &[LayoutPane],
+    split: Option<SplitDirection>,
+    anchor: String,
+    cwd: &Path,
+    context: &PaneContext,
+) -> Result<Option<String>> {
+    let mut ids = vec![anchor];
+    for pane in panes.iter().skip(1) {
+        let (leaf, leaf_cwd) = first_leaf(std::slice::from_ref(pane), cwd);
+        let mut args = args(&["split-window", "-d", "-t", ids.last().unwrap()]);
+        // Zellij's default split stacks panes, tmux calls that -v
+        args.push(match split.unwrap_or(SplitDirection::Horizontal) {
+            SplitDirection::Vertical => "-h".to_string(),
+            SplitDirection::Horizontal => "-v".to_string(),
+        });
+        if let Some(size) = &pane.size {
+            args.extend(["-l".to_string(), size.clone()]);
+        }
+        ids.push(new_pane(run, args, context, &leaf_cwd, leaf)?);
+    }
+
+    let mut focus = None;
+    for (pane, id) in panes.iter().zip(ids) {
+        if pane.panes.is_empty() {
+            if pane.focus {
+                focus = Some(id);
+            }
+        } else {
+            let nested_cwd = join(cwd, pane.cwd.as_deref());
+            if let Some(pane) = lay_out(run, &pane.panes, pane.split, id, &nested_cwd, context)? {
+                focus = Some(pane);
+            }
+        }
+    }
+    Ok(focus)
+}
+
+/// Extra env and shell for the panes of one project
+struct PaneContext<'a> {
+    env: Vec<&'a str>,
+    /// Session env keys the project doesn't set
+    unset: Vec<&'a str>,
+    /// The project's shell, run by panes without a command
+    shell: Option<&'a str>,
+}
+
+/// Runs a new-session/new-window/split-window and returns the new pane's id
+fn new_pane(
+    run: &mut Run,
+    mut args: Vec<String>,
+    context: &PaneContext,
+    cwd: &Path,
+    leaf: Option<&LayoutPane>,
+) -> Result<String> {
+    args.extend(["-c".to_string(), cwd.to_string_lossy().into_owned()]);
+    for entry in &context.env {
+        args.extend(["-e".to_string(), entry.to_string()]);
+    }
+    args.extend(["-P".to_string(), "-F".to_string(), "#{pane_id}".to_string()]);
+
+    let command = leaf.and_then(|leaf| leaf.command.as_deref());
+    if context.unset.is_empty() {
+        match (command, context.shell) {
+            // tmux hands the command to the shell, so quoting works as typed
+            (Some(command), _) => args.push(command.to_string()),
+            (None, Some(shell)) => args.push(shell.to_string()),
+            (None, None) => {}
+        }
+    } else {
+        // `-e` can't remove the session's keys, so the pane runs under `env -u`
+        let mut wrapped = vec!["env".to_string()];
+        for key in &context.unset {
+            wrapped.extend(["-u".to_string(), sh_quote(key)]);
+        }
+        match command {
+            Some(command) => wrapped.extend(["sh -c".to_string(), sh_quote(command)]),
+            None => {
+                let shell = context
+                    .shell
+                    .map(String::from)
+                    .unwrap_or_else(default_shell);
+                wrapped.push(sh_quote(&shell));
+            }
+        }
+        args.push(wrapped.join(" "));
+    }
+
+    let id = run(&args)?.trim().to_string();
+    if command.is_some() {
+        // Like Zellij, keep the pane around when the command exits
+        run(&self::args(&[
+            "set-option",
+            "-p",
+            "-t",
+            &id,
+            "remain-on-exit",
+            "on",
+        ]))?;
+    }
+    Ok(id)
+}
+
+/// The pane the first split of `panes` starts as, and its directory
+fn first_leaf<'a>(
+    panes: &'a [LayoutPane],
+    cwd: &Path,
+) -> (Option<&'a LayoutPane>, std::path::PathBuf) {
+    match panes.first() {
+        Some(pane) => {
+            let cwd = join(cwd, pane.cwd.as_deref());
+            if pane.panes.is_empty() {
+                (Some(pane), cwd)
+            } else {
+                first_leaf(&pane.panes, &cwd)
+            }
+        }
+        None => (None, cwd.to_path_buf()),
+    }
+}
+
+/// Target matching the session name exactly (tmux matches prefixes otherwise)
+fn exact(session_name: &str) -> String {
+    format!("={session_name}")
+}
+
+/// Quotes a word for the shell tmux runs pane commands with
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn tmux(args: &[String]) -> Result<String> {
+    let output = Command::new("tmux")
+        .args(args)
+        .output()
+        .with_context(|| format!("executando tmux {}", args.join(" ")))?;
+
+    if !output.status.success() {
+        bail!(
+            "tmux {} falhou: {}",
+            args.first().map(String::as_str).unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LayoutConfig, LayoutTab};
+    use std::path::PathBuf;
+
+    /// Records the commands, answering -P with sequential pane ids
+    fn record(
+        service: &TmuxService,
+        main: &ProjectLayoutInfo,
+        deps: &[ProjectLayoutInfo],
+    ) -> Vec<String> {
+        let mut commands = Vec::new();
+        let mut next = 0;
+        let mut run = |args: &[String]| {
+            commands.push(args.join(" "));
+            if args.iter().any(|arg| arg == "-P") {
+                next += 1;
+                Ok(format!("%{next}\n"))
+            } else {
+                Ok(String::new())
+            }
+        };
+        service
+            .build_session(&mut run, "devobox-web", main, deps)
+            .unwrap();
+        commands
+    }
+
+    fn info(name: &str, startup_command: Option<&str>, env: &[&str]) -> ProjectLayoutInfo {
+        ProjectLayoutInfo {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/code/{name}")),
+            startup_command: startup_command.map(String::from),
+            env: env.iter().map(|entry| entry.to_string()).collect(),
+            shell: None,
+            layout: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_sessions() {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - 3600;
+        let output = format!("{created} devobox-app\n{created} my work\n");
+        let sessions = TmuxService::new().parse_sessions(&output);
+
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["devobox-app", "my work"]);
+        let age = sessions[0].age.unwrap().as_secs();
+        assert!((3600..3660).contains(&age), "{age}");
+    }
+
+    #[test]
+    fn test_default_layout_is_a_window_per_project() {
+        let commands = record(
+            &TmuxService::new(),
+            &info("web", Some("npm run \"dev server\""), &["PORT=3000"]),
+            &[info("api", None, &["PORT=3000", "RUST_LOG=debug"])],
+        );
+
+        assert_eq!(
+            commands,
+            vec![
+                "new-session -d -s devobox-web -e PORT=3000 -n web -c /code/web -P -F #{pane_id} npm run \"dev server\"",
+                "set-option -p -t %1 remain-on-exit on",
+                "new-window -d -t =devobox-web: -n api -c /code/api -e RUST_LOG=debug -P -F #{pane_id}",
+                "select-window -t %1",
+                "select-pane -t %1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dependency_panes_unset_the_main_project_env() {
+        let mut api = info("api", None, &[]);
+        api.shell = Some("zsh".to_string());
+        let commands = record(
+            &TmuxService::new(),
+            &info("web", None, &["DATABASE_URL=postgres://web"]),
+            &[info("worker", Some("cargo run"), &[]), api],
+        );
+
+        assert_eq!(
+            commands[1..3],
+            [
+                "new-window -d -t =devobox-web: -n worker -c /code/worker -P -F #{pane_id} env -u 'DATABASE_URL' sh -c 'cargo run'",
+                "set-option -p -t %2 remain-on-exit on",
+            ]
+        );
+        assert_eq!(
+            commands[3],
+            "new-window -d -t =devobox-web: -n api -c /code/api -P -F #{pane_id} env -u 'DATABASE_URL' 'zsh'"
+        );
+    }
+
+    #[test]
+    fn test_splits_follow_the_layout_tree() {
+        let mut web = info("web", None, &[]);
+        web.layout = Some(LayoutConfig {
+            template: None,
+            tabs: vec![LayoutTab {
+                name: Some("dev".to_string()),
+                split: Some(SplitDirection::Vertical),
+                panes: vec![
+                    LayoutPane {
+                        command: Some("npm run dev".to_string()),
+                        ..Default::default()
+                    },
+                    LayoutPane {
+                        split: Some(SplitDirection::Horizontal),
+                        size: Some("40%".to_string()),
+                        panes: vec![
+                            LayoutPane {
+                                cwd: Some("logs".into()),
+                                ..Default::default()
+                            },
+                            LayoutPane {
+                                command: Some("npm test".to_string()),
+                                focus: true,
+                                ..Default::default()
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+        });
+
+        let commands = record(&TmuxService::new(), &web, &[]);
+        assert_eq!(
+            commands,
+            vec![
+                "new-session -d -s devobox-web -n dev -c /code/web -P -F #{pane_id} npm run dev",
+                "set-option -p -t %1 remain-on-exit on",
+                // The right column starts as its first pane, then splits below
+                "split-window -d -t %1 -h -l 40% -c /code/web/logs -P -F #{pane_id}",
+                "split-window -d -t %2 -v -c /code/web -P -F #{pane_id} npm test",
+                "set-option -p -t %3 remain-on-exit on",
+                "select-window -t %3",
+                "select-pane -t %3",
+            ]
+        );
+    }
+}
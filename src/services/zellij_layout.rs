@@ -0,0 +1,530 @@
+use super::multiplexer::{
+    ProjectLayoutInfo, default_shell, extra_env, focused_tab, join, project_tabs, unset_env,
+};
+use crate::domain::{LayoutPane, LayoutTab};
+use anyhow::{Context, Result, bail};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+const DEFAULT_LAYOUT_TEMPLATE: &str = r#"layout {
+    default_tab_template {
+        pane size=1 borderless=true {
+            plugin location="zellij:tab-bar"
+        }
+        children
+        pane size=1 borderless=true {
+            plugin location="zellij:status-bar"
+        }
+    }
+"#;
+
+/// The KDL layout for a session: the main project's tabs (focused) followed by
+/// each dependency's. The main project's env is the session's, so panes only
+/// get the entries they don't already inherit and unset the ones they don't
+/// declare
+pub fn render_layout(
+    main_project: &ProjectLayoutInfo,
+    dependencies: &[ProjectLayoutInfo],
+) -> Result<String> {
+    let mut out = String::from(DEFAULT_LAYOUT_TEMPLATE);
+
+    write_project_tabs(&mut out, main_project, true, main_project)?;
+    for dep in dependencies {
+        write_project_tabs(&mut out, dep, false, main_project)?;
+    }
+
+    out.push_str("}\n"); // Close layout
+    Ok(out)
+}
+
+/// What a pane of `project` runs and with which extra env
+struct PaneContext<'a> {
+    project: &'a ProjectLayoutInfo,
+    env: Vec<&'a str>,
+    /// Session env keys the project doesn't set
+    unset: Vec<&'a str>,
+    /// Shell for panes without a command, when it isn't the session's default
+    shell: Option<&'a str>,
+}
+
+/// `session` is the main project: its env and shell are the session's
+fn write_project_tabs(
+    out: &mut String,
+    project: &ProjectLayoutInfo,
+    focus: bool,
+    session: &ProjectLayoutInfo,
+) -> Result<()> {
+    let context = PaneContext {
+        project,
+        env: extra_env(project, &session.env),
+        unset: unset_env(project, &session.env),
+        shell: project
+            .shell
+            .as_deref()
+            .filter(|shell| session.shell.as_deref() != Some(*shell)),
+    };
+
+    if let Some(template) = project
+        .layout
+        .as_ref()
+        .and_then(|layout| layout.template.as_ref())
+    {
+        return write_template(out, &context, template);
+    }
+
+    let tabs = project_tabs(project);
+    let focused = focused_tab(&tabs);
+    for (i, tab) in tabs.iter().enumerate() {
+        write_tab(out, &context, tab, focus && i == focused)?;
+    }
+    Ok(())
+}
+
+fn write_tab(out: &mut String, context: &PaneContext, tab: &LayoutTab, focus: bool) -> Result<()> {
+    let name = tab.name.as_deref().unwrap_or(&context.project.name);
+    let mut attrs = format!("name={}", kdl_string(name));
+    if focus {
+        attrs.push_str(" focus=true");
+    }
+    if let Some(split) = tab.split {
+        let _ = write!(attrs, " split_direction={}", kdl_string(split.as_str()));
+    }
+    let _ = writeln!(out, "    tab {attrs} {{");
+
+    let cwd = join(&context.project.path, tab.cwd.as_deref());
+    if tab.panes.is_empty() {
+        write_pane(out, context, &LayoutPane::default(), &cwd, 2)?;
+    }
+    for pane in &tab.panes {
+        write_pane(out, context, pane, &cwd, 2)?;
+    }
+
+    out.push_str("    }\n"); // Close tab
+    Ok(())
+}
+
+fn write_pane(
+    out: &mut String,
+    context: &PaneContext,
+    pane: &LayoutPane,
+    parent_cwd: &Path,
+    depth: usize,
+) -> Result<()> {
+    let indent = "    ".repeat(depth);
+    let cwd = join(parent_cwd, pane.cwd.as_deref());
+
+    let mut attrs = String::new();
+    if let Some(size) = &pane.size {
+        // Zellij takes fixed sizes as numbers and percentages as strings
+        match size.parse::<u32>() {
+            Ok(lines) => {
+                let _ = write!(attrs, " size={lines}");
+            }
+            Err(_) => {
+                let _ = write!(attrs, " size={}", kdl_string(size));
+            }
+        }
+    }
+    if let Some(name) = &pane.name {
+        let _ = write!(attrs, " name={}", kdl_string(name));
+    }
+    if pane.focus {
+        attrs.push_str(" focus=true");
+    }
+
+    if !pane.panes.is_empty() {
+        if let Some(split) = pane.split {
+            let _ = write!(attrs, " split_direction={}", kdl_string(split.as_str()));
+        }
+        let _ = writeln!(out, "{indent}pane{attrs} {{");
+        for child in &pane.panes {
+            write_pane(out, context, child, &cwd, depth + 1)?;
+        }
+        let _ = writeln!(out, "{indent}}}");
+        return Ok(());
+    }
+
+    let _ = writeln!(
+        out,
+        "{indent}pane{attrs} cwd={} {{",
+        kdl_string(&cwd.to_string_lossy())
+    );
+
+    let parts = pane_command(pane.command.as_deref(), context)?;
+    if let Some((program, args)) = parts.split_first() {
+        let _ = writeln!(out, "{indent}    command {}", kdl_string(program));
+        if !args.is_empty() {
+            let args: Vec<String> = args.iter().map(|arg| kdl_string(arg)).collect();
+            let _ = writeln!(out, "{indent}    args {}", args.join(" "));
+        }
+    }
+
+    let _ = writeln!(out, "{indent}}}"); // Close pane
+    Ok(())
+}
+
+/// Program and args for a pane; extra env is set by running it through `env`
+fn pane_command(command: Option<&str>, context: &PaneContext) -> Result<Vec<String>> {
+    let mut parts = match (command, context.shell) {
+        (Some(command), _) => split_command(command)?,
+        (None, Some(shell)) => vec![shell.to_string()],
+        (None, None) => Vec::new(),
+    };
+
+    let env = env_args(context);
+    if !env.is_empty() {
+        if parts.is_empty() {
+            parts.push(context.project.shell.clone().unwrap_or_else(default_shell));
+        }
+        let mut wrapped = vec!["env".to_string()];
+        wrapped.extend(env);
+        wrapped.append(&mut parts);
+        parts = wrapped;
+    }
+
+    Ok(parts)
+}
+
+/// `env` arguments that turn the session env into the project's: `-u KEY`
+/// for each key it doesn't set, then its extra entries
+fn env_args(context: &PaneContext) -> Vec<String> {
+    let mut args = Vec::new();
+    for key in &context.unset {
+        args.extend(["-u".to_string(), key.to_string()]);
+    }
+    args.extend(context.env.iter().map(|entry| entry.to_string()));
+    args
+}
+
+/// Splits a command line like a POSIX shell would (quotes and backslashes).
+/// Commands using pipes, redirections, `&&` or expansions run through `sh -c`
+fn split_command(command: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => bail!("Aspas não fechadas no comando: {}", command),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => bail!("Aspas não fechadas no comando: {}", command),
+                        },
+                        Some('$' | '`') => return Ok(via_shell(command)),
+                        Some(c) => word.push(c),
+                        None => bail!("Aspas não fechadas no comando: {}", command),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            '|' | '&' | ';' | '<' | '>' | '(' | ')' | '$' | '`' | '*' | '?' | '~' => {
+                return Ok(via_shell(command));
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+fn via_shell(command: &str) -> Vec<String> {
+    vec!["sh".to_string(), "-c".to_string(), command.to_string()]
+}
+
+/// Inlines a project's own KDL tabs, replacing `{{project_name}}`,
+/// `{{project_dir}}`, `{{startup_command}}`, `{{shell}}` and `{{env}}` (the
+/// `env` arguments as KDL strings, for `command="env"` panes)
+fn write_template(out: &mut String, context: &PaneContext, template: &Path) -> Result<()> {
+    let project = context.project;
+    let path = project.path.join(template);
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Lendo template de layout de {}: {:?}", project.name, path))?;
+
+    let env = env_args(context);
+    if !env.is_empty() && !content.contains("{{env}}") {
+        warn!(
+            "  O template de layout de {} não usa {{{{env}}}}: as abas dele não recebem o env do projeto",
+            project.name
+        );
+    }
+    let env: Vec<String> = env.iter().map(|arg| kdl_string(arg)).collect();
+    let shell = project.shell.clone().unwrap_or_else(default_shell);
+
+    let rendered = content
+        .replace("{{project_name}}", &kdl_escape(&project.name))
+        .replace(
+            "{{project_dir}}",
+            &kdl_escape(&project.path.to_string_lossy()),
+        )
+        .replace(
+            "{{startup_command}}",
+            &kdl_escape(project.startup_command.as_deref().unwrap_or_default()),
+        )
+        .replace("{{shell}}", &kdl_escape(&shell))
+        .replace("{{env}}", &env.join(" "));
+
+    out.push_str(&rendered);
+    if !rendered.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(())
+}
+
+/// A KDL string literal
+fn kdl_string(value: &str) -> String {
+    format!("\"{}\"", kdl_escape(value))
+}
+
+/// Escapes `value` for use between the quotes of a KDL string
+fn kdl_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{{{:x}}}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LayoutConfig, SplitDirection};
+    use std::path::PathBuf;
+
+    fn info(name: &str, startup_command: Option<&str>) -> ProjectLayoutInfo {
+        ProjectLayoutInfo {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/code/{name}")),
+            startup_command: startup_command.map(String::from),
+            env: vec![],
+            shell: None,
+            layout: None,
+        }
+    }
+
+    #[test]
+    fn test_split_command_keeps_quoted_args() {
+        assert_eq!(
+            split_command(r#"rails server -b "0.0.0.0" --name 'my app' a\ b"#).unwrap(),
+            vec![
+                "rails", "server", "-b", "0.0.0.0", "--name", "my app", "a b"
+            ]
+        );
+        assert_eq!(
+            split_command("npm run dev && open http://localhost").unwrap(),
+            vec!["sh", "-c", "npm run dev && open http://localhost"]
+        );
+        assert!(split_command("echo \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_names_and_args_are_escaped() {
+        let main = info("say \"hi\"", Some(r#"echo "a \"quoted\" word""#));
+        let kdl = render_layout(&main, &[]).unwrap();
+
+        assert!(kdl.contains(r#"    tab name="say \"hi\"" focus=true {"#));
+        assert!(kdl.contains(r#"            command "echo""#));
+        assert!(kdl.contains(r#"            args "a \"quoted\" word""#));
+    }
+
+    #[test]
+    fn test_dependency_panes_run_their_own_shell() {
+        let mut main = info("web", None);
+        main.shell = Some("zsh".to_string());
+        let mut same = info("api", None);
+        same.shell = Some("zsh".to_string());
+        let mut other = info("tools", None);
+        other.shell = Some("fish".to_string());
+
+        // The session's default shell is zsh; only fish needs spelling out
+        let kdl = render_layout(&main, &[same, other]).unwrap();
+        assert_eq!(kdl.matches("command ").count(), 1, "{kdl}");
+        assert!(kdl.contains(r#"            command "fish""#));
+    }
+
+    #[test]
+    fn test_dependency_panes_unset_the_main_project_env() {
+        let mut main = info("web", None);
+        main.env = vec!["DATABASE_URL=postgres://web".to_string()];
+        let api = info("api", Some("cargo run"));
+
+        // The session carries web's DATABASE_URL; api doesn't declare it
+        let kdl = render_layout(&main, &[api]).unwrap();
+        assert!(kdl.contains(r#"            command "env""#), "{kdl}");
+        assert!(
+            kdl.contains(r#"            args "-u" "DATABASE_URL" "cargo" "run""#),
+            "{kdl}"
+        );
+    }
+
+    #[test]
+    fn test_layout_tabs_and_splits() {
+        let mut main = info("web", Some("npm run dev"));
+        main.layout = Some(LayoutConfig {
+            template: None,
+            tabs: vec![
+                LayoutTab {
+                    name: Some("editor".to_string()),
+                    ..Default::default()
+                },
+                LayoutTab {
+                    name: Some("dev".to_string()),
+                    focus: true,
+                    split: Some(SplitDirection::Vertical),
+                    cwd: Some("frontend".into()),
+                    panes: vec![
+                        LayoutPane {
+                            command: Some("npm run dev".to_string()),
+                            size: Some("60%".to_string()),
+                            focus: true,
+                            ..Default::default()
+                        },
+                        LayoutPane {
+                            split: Some(SplitDirection::Horizontal),
+                            panes: vec![
+                                LayoutPane {
+                                    command: Some("npm test -- --watch".to_string()),
+                                    ..Default::default()
+                                },
+                                LayoutPane {
+                                    cwd: Some("../logs".into()),
+                                    size: Some("10".to_string()),
+                                    ..Default::default()
+                                },
+                            ],
+                            ..Default::default()
+                        },
+                    ],
+                },
+            ],
+        });
+
+        let kdl = render_layout(&main, &[info("api", None)]).unwrap();
+
+        assert!(kdl.contains(
+            "    tab name=\"editor\" {\n        pane cwd=\"/code/web\" {\n        }\n    }\n"
+        ));
+        assert!(kdl.contains("    tab name=\"dev\" focus=true split_direction=\"vertical\" {\n"));
+        assert!(kdl.contains(
+            "        pane size=\"60%\" focus=true cwd=\"/code/web/frontend\" {\n            command \"npm\"\n            args \"run\" \"dev\"\n"
+        ));
+        assert!(kdl.contains("        pane split_direction=\"horizontal\" {\n"));
+        assert!(kdl.contains("            pane size=10 cwd=\"/code/web/frontend/../logs\" {\n"));
+        assert!(kdl.contains("    tab name=\"api\" {\n        pane cwd=\"/code/api\" {\n"));
+        assert_eq!(kdl.matches("focus=true").count(), 2);
+    }
+
+    #[test]
+    fn test_template_substitutes_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("layout.kdl"),
+            "    tab name=\"{{project_name}}\" {\n        pane cwd=\"{{project_dir}}\" command=\"htop\"\n    }",
+        )
+        .unwrap();
+
+        let main = ProjectLayoutInfo {
+            name: "a\"b".to_string(),
+            path: dir.path().to_path_buf(),
+            startup_command: None,
+            env: vec![],
+            shell: None,
+            layout: Some(LayoutConfig {
+                template: Some("layout.kdl".into()),
+                tabs: vec![],
+            }),
+        };
+
+        let kdl = render_layout(&main, &[]).unwrap();
+        assert!(kdl.contains("    tab name=\"a\\\"b\" {\n"));
+        assert!(kdl.contains(&format!("pane cwd=\"{}\"", dir.path().display())));
+        assert!(kdl.ends_with("    }\n}\n"));
+    }
+
+    #[test]
+    fn test_dependency_template_gets_env_and_shell() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("layout.kdl"),
+            "    tab name=\"{{project_name}}\" {\n        pane command=\"env\" {\n            args {{env}} \"{{shell}}\"\n        }\n    }\n",
+        )
+        .unwrap();
+
+        let main = ProjectLayoutInfo {
+            name: "web".to_string(),
+            path: "/code/web".into(),
+            startup_command: None,
+            env: vec!["SHARED=1".to_string()],
+            shell: None,
+            layout: None,
+        };
+        let dep = ProjectLayoutInfo {
+            name: "api".to_string(),
+            path: dir.path().to_path_buf(),
+            startup_command: None,
+            env: vec![
+                "SHARED=1".to_string(),
+                "DATABASE_URL=postgres://api".to_string(),
+            ],
+            shell: Some("zsh".to_string()),
+            layout: Some(LayoutConfig {
+                template: Some("layout.kdl".into()),
+                tabs: vec![],
+            }),
+        };
+
+        // Only the env the session doesn't already have
+        let kdl = render_layout(&main, &[dep]).unwrap();
+        assert!(
+            kdl.contains("args \"DATABASE_URL=postgres://api\" \"zsh\"\n"),
+            "{kdl}"
+        );
+    }
+}
@@ -1,81 +1,25 @@
+use super::multiplexer::{Multiplexer, ProjectLayoutInfo, SessionInfo, quiet};
+use super::zellij_layout::render_layout;
 use anyhow::{Context, Result, bail};
-use std::fs::File;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::time::Duration;
 use tracing::{info, warn};
 
-const DEFAULT_LAYOUT_TEMPLATE: &str = r#"layout {
-    default_tab_template {
-        pane size=1 borderless=true {
-            plugin location="zellij:tab-bar"
-        }
-        children
-        pane size=1 borderless=true {
-            plugin location="zellij:status-bar"
-        }
-    }
-"#;
-
-pub struct ProjectLayoutInfo {
-    pub name: String,
-    pub path: PathBuf,
-    pub startup_command: Option<String>,
-}
-
 /// Manages Zellij sessions for projects
-pub struct ZellijService;
+pub struct ZellijService {
+    /// Default shell of sessions created without a layout (default: `$SHELL`)
+    shell: Option<String>,
+}
 
 impl ZellijService {
     pub fn new() -> Self {
-        Self
-    }
-
-    /// Checks if Zellij is installed and available in PATH
-    pub fn is_available(&self) -> bool {
-        Command::new("which")
-            .arg("zellij")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+        Self { shell: None }
     }
 
-    /// Creates or attaches to a Zellij session with a generated layout
-    pub fn create_with_layout(
-        &self,
-        session_name: &str,
-        main_project: &ProjectLayoutInfo,
-        dependencies: &[ProjectLayoutInfo],
-    ) -> Result<()> {
-        if !self.is_available() {
-            bail!(
-                "Zellij não está instalado.\n\
-                 Instale com: mise install zellij\n\
-                 Ou adicione ao mise.toml: zellij = \"latest\""
-            );
-        }
-
-        // Check if session exists
-        let exists = self.session_exists(session_name)?;
-
-        if exists {
-            info!("  Anexando à sessão existente: {}", session_name);
-            self.attach(session_name)
-        } else {
-            info!("  Criando nova sessão com layout: {}", session_name);
-            let layout_path =
-                self.generate_layout_file(session_name, main_project, dependencies)?;
-            let res = self.create_with_layout_file(session_name, &main_project.path, &layout_path);
-
-            // Cleanup temp file
-            if let Err(e) = std::fs::remove_file(&layout_path) {
-                warn!(
-                    "Não foi possível remover arquivo temporário de layout: {}",
-                    e
-                );
-            }
-
-            res
+    pub fn with_shell(shell: Option<&str>) -> Self {
+        Self {
+            shell: shell.map(String::from),
         }
     }
 
@@ -88,84 +32,43 @@ impl ZellijService {
     ) -> Result<PathBuf> {
         let temp_dir = std::env::temp_dir();
         let file_path = temp_dir.join(format!("{}.kdl", session_name));
-        let mut file = File::create(&file_path)?;
-
-        // Write the static header (Tab bar and Status bar configuration)
-        file.write_all(DEFAULT_LAYOUT_TEMPLATE.as_bytes())?;
-
-        // Write Main Project Tab (Focused)
-        self.write_project_tab(&mut file, main_project, true)?;
-
-        // Write Dependency Tabs
-        for dep in dependencies {
-            self.write_project_tab(&mut file, dep, false)?;
-        }
-
-        writeln!(file, "}}")?; // Close layout
+        std::fs::write(&file_path, render_layout(main_project, dependencies)?)?;
 
         Ok(file_path)
     }
 
-    /// Helper to write a single project tab configuration
-    fn write_project_tab(
-        &self,
-        file: &mut File,
-        project: &ProjectLayoutInfo,
-        focus: bool,
-    ) -> Result<()> {
-        writeln!(
-            file,
-            "    tab name=\"{}\" {} {{",
-            project.name,
-            if focus { "focus=true" } else { "" }
-        )?;
-
-        writeln!(
-            file,
-            "        pane cwd=\"{}\" {{",
-            project.path.to_string_lossy()
-        )?;
-
-        if let Some(cmd) = &project.startup_command {
-            // Split command string into program and args
-            let parts: Vec<&str> = cmd.split_whitespace().collect();
-            if !parts.is_empty() {
-                writeln!(file, "            command \"{}\"", parts[0])?;
-                if parts.len() > 1 {
-                    let args = parts[1..]
-                        .iter()
-                        .map(|s| format!("\"{}\"", s))
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    writeln!(file, "            args {}", args)?;
-                }
-            }
-        }
-
-        writeln!(file, "        }}")?; // Close pane
-        writeln!(file, "    }}")?; // Close tab
-        Ok(())
-    }
-
     fn create_with_layout_file(
         &self,
         session_name: &str,
-        workdir: &Path,
+        main_project: &ProjectLayoutInfo,
         layout_path: &Path,
     ) -> Result<()> {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/home/dev".to_string());
         let config_dir = Path::new(&home).join(".config/zellij");
 
-        let status = Command::new("zellij")
-            .args([
-                "attach",
-                "--create",
-                session_name,
-                "--layout",
-                &layout_path.to_string_lossy(),
-            ])
+        let mut cmd = Command::new("zellij");
+        cmd.args([
+            "attach",
+            "--create",
+            session_name,
+            "--layout",
+            &layout_path.to_string_lossy(),
+        ]);
+        // Panes without a command open the project's shell
+        if let Some(shell) = &main_project.shell {
+            cmd.args(["options", "--default-shell", shell]);
+        }
+
+        let status = cmd
             .env("ZELLIJ_CONFIG_DIR", config_dir)
-            .current_dir(workdir)
+            // Inherited by the session server and so by every pane's shell
+            .envs(
+                main_project
+                    .env
+                    .iter()
+                    .filter_map(|entry| entry.split_once('=')),
+            )
+            .current_dir(&main_project.path)
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
@@ -179,39 +82,6 @@ impl ZellijService {
         Ok(())
     }
 
-    /// Creates or attaches to a Zellij session
-    ///
-    /// If the session already exists, attaches to it.
-    /// Otherwise, creates a new session with the given name.
-    ///
-    /// # Arguments
-    /// * `session_name` - Name of the Zellij session
-    /// * `workdir` - Working directory for the session
-    ///
-    /// # Returns
-    /// * `Ok(())` - Session was created/attached successfully
-    /// * `Err` - If Zellij is not installed or session creation failed
-    pub fn attach_or_create(&self, session_name: &str, workdir: &Path) -> Result<()> {
-        if !self.is_available() {
-            bail!(
-                "Zellij não está instalado.\n\
-                 Instale com: mise install zellij\n\
-                 Ou adicione ao mise.toml: zellij = \"latest\""
-            );
-        }
-
-        // Check if session exists
-        let exists = self.session_exists(session_name)?;
-
-        if exists {
-            info!("  Anexando à sessão existente: {}", session_name);
-            self.attach(session_name)
-        } else {
-            info!("  Criando nova sessão: {}", session_name);
-            self.create(session_name, workdir)
-        }
-    }
-
     /// Checks if a session exists
     ///
     /// # Arguments
@@ -222,13 +92,10 @@ impl ZellijService {
     /// * `Ok(false)` - Session doesn't exist
     /// * `Err` - Error checking sessions
     fn session_exists(&self, session_name: &str) -> Result<bool> {
-        let output = Command::new("zellij")
-            .args(["list-sessions"])
-            .output()
-            .context("Falha ao listar sessões do Zellij")?;
-
-        let sessions = String::from_utf8_lossy(&output.stdout);
-        Ok(sessions.lines().any(|line| line.contains(session_name)))
+        Ok(self
+            .list_sessions()?
+            .iter()
+            .any(|session| session.name == session_name))
     }
 
     /// Creates a new session
@@ -245,7 +112,7 @@ impl ZellijService {
         let config_dir = Path::new(&home).join(".config/zellij");
 
         let status = Command::new("zellij")
-            .args(["attach", "--create", session_name])
+            .args(&self.attach_command(session_name)[1..])
             .env("ZELLIJ_CONFIG_DIR", config_dir)
             .current_dir(workdir)
             .stdin(Stdio::inherit())
@@ -284,55 +151,159 @@ impl ZellijService {
 
         Ok(())
     }
+}
+
+impl Multiplexer for ZellijService {
+    fn name(&self) -> &'static str {
+        "Zellij"
+    }
 
-    /// Lists all active Zellij sessions
-    ///
-    /// # Returns
-    /// * `Ok(Vec<String>)` - List of session names
-    /// * `Err` - Failed to list sessions
-    pub fn list_sessions(&self) -> Result<Vec<String>> {
+    /// Checks if Zellij is installed and available in PATH
+    fn is_available(&self) -> bool {
+        Command::new("which")
+            .arg("zellij")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Creates or attaches to a Zellij session with a generated layout
+    fn create_with_layout(
+        &self,
+        session_name: &str,
+        main_project: &ProjectLayoutInfo,
+        dependencies: &[ProjectLayoutInfo],
+    ) -> Result<()> {
         if !self.is_available() {
-            return Ok(Vec::new());
+            bail!(
+                "Zellij não está instalado.\n\
+                 Instale com: mise install zellij\n\
+                 Ou adicione ao mise.toml: zellij = \"latest\""
+            );
         }
 
-        let output = Command::new("zellij")
-            .args(["list-sessions"])
-            .output()
-            .context("Falha ao listar sessões")?;
+        // Check if session exists
+        let exists = self.session_exists(session_name)?;
 
-        let sessions = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
+        if exists {
+            info!("  Anexando à sessão existente: {}", session_name);
+            self.attach(session_name)
+        } else {
+            info!("  Criando nova sessão com layout: {}", session_name);
+            let layout_path =
+                self.generate_layout_file(session_name, main_project, dependencies)?;
+            let res = self.create_with_layout_file(session_name, main_project, &layout_path);
 
-        Ok(sessions)
+            // Cleanup temp file
+            if let Err(e) = std::fs::remove_file(&layout_path) {
+                warn!(
+                    "Não foi possível remover arquivo temporário de layout: {}",
+                    e
+                );
+            }
+
+            res
+        }
     }
 
-    /// Kills a specific session
+    /// Creates or attaches to a Zellij session
+    ///
+    /// If the session already exists, attaches to it.
+    /// Otherwise, creates a new session with the given name.
     ///
     /// # Arguments
-    /// * `session_name` - Name of the session to kill
+    /// * `session_name` - Name of the Zellij session
+    /// * `workdir` - Working directory for the session
     ///
     /// # Returns
-    /// * `Ok(())` - Session killed successfully
-    /// * `Err` - Failed to kill session
-    pub fn kill_session(&self, session_name: &str) -> Result<()> {
+    /// * `Ok(())` - Session was created/attached successfully
+    /// * `Err` - If Zellij is not installed or session creation failed
+    fn attach_or_create(&self, session_name: &str, workdir: &Path) -> Result<()> {
         if !self.is_available() {
-            bail!("Zellij não está instalado");
+            bail!(
+                "Zellij não está instalado.\n\
+                 Instale com: mise install zellij\n\
+                 Ou adicione ao mise.toml: zellij = \"latest\""
+            );
         }
 
-        let status = Command::new("zellij")
-            .args(["delete-session", session_name])
-            .status()
-            .context(format!("Falha ao matar sessão: {}", session_name))?;
+        // Check if session exists
+        let exists = self.session_exists(session_name)?;
 
-        if !status.success() {
-            warn!("Falha ao deletar sessão {}", session_name);
+        if exists {
+            info!("  Anexando à sessão existente: {}", session_name);
+            self.attach(session_name)
+        } else {
+            info!("  Criando nova sessão: {}", session_name);
+            self.create(session_name, workdir)
         }
+    }
 
-        Ok(())
+    /// Lists all active Zellij sessions
+    ///
+    /// # Returns
+    /// * `Ok(Vec<String>)` - List of session names
+    /// * `Err` - Failed to list sessions
+    fn list_command(&self) -> Vec<String> {
+        quiet("zellij list-sessions --no-formatting")
+    }
+
+    /// Lines look like `name [Created 1day 2h 3m ago] (EXITED - attach to resurrect)`
+    fn parse_sessions(&self, output: &str) -> Vec<SessionInfo> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                let name = line.split_whitespace().next()?;
+                let age = line
+                    .split_once("[Created ")
+                    .and_then(|(_, rest)| rest.split_once(" ago]"))
+                    .and_then(|(age, _)| parse_age(age));
+                Some(SessionInfo {
+                    name: name.to_string(),
+                    age,
+                    exited: line.contains("(EXITED"),
+                })
+            })
+            .collect()
     }
+
+    fn kill_command(&self, session_name: &str) -> Vec<String> {
+        // --force also stops a running session before deleting it
+        ["zellij", "delete-session", "--force", session_name]
+            .map(String::from)
+            .to_vec()
+    }
+
+    fn attach_command(&self, session_name: &str) -> Vec<String> {
+        let mut command = ["zellij", "attach", "--create", session_name]
+            .map(String::from)
+            .to_vec();
+        // Only applies when the session is created
+        if let Some(shell) = &self.shell {
+            command.extend(["options", "--default-shell", shell].map(String::from));
+        }
+        command
+    }
+}
+
+/// Reads Zellij's age format, e.g. `1day 2h 3m 4s` or `5s`
+fn parse_age(text: &str) -> Option<Duration> {
+    let mut total = 0;
+    for part in text.split_whitespace() {
+        let split = part.find(|c: char| !c.is_ascii_digit())?;
+        let (value, unit) = part.split_at(split);
+        let value: u64 = value.parse().ok()?;
+        total += value
+            * match unit {
+                "day" | "days" => 86_400,
+                "h" => 3_600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+    }
+    Some(Duration::from_secs(total))
 }
 
 impl Default for ZellijService {
@@ -367,6 +338,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_sessions() {
+        let output = "devobox-app [Created 1day 2h 3m 4s ago] (current)\n\
+                      devobox-app2 [Created 5s ago] (EXITED - attach to resurrect)\n";
+        let sessions = ZellijService::new().parse_sessions(output);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "devobox-app");
+        assert_eq!(sessions[0].age, Some(Duration::from_secs(93_784)));
+        assert!(!sessions[0].exited);
+        assert_eq!(sessions[1].name, "devobox-app2");
+        assert_eq!(sessions[1].age, Some(Duration::from_secs(5)));
+        assert!(sessions[1].exited);
+    }
+
     #[test]
     fn test_generate_layout_file_structure() {
         let service = ZellijService::new();
@@ -375,6 +361,9 @@ mod tests {
             name: "main-app".to_string(),
             path: PathBuf::from("/code/main"),
             startup_command: Some("npm start".to_string()),
+            env: vec!["PORT=3000".to_string()],
+            shell: None,
+            layout: None,
         };
 
         let deps = vec![
@@ -382,11 +371,17 @@ mod tests {
                 name: "api-service".to_string(),
                 path: PathBuf::from("/code/api"),
                 startup_command: Some("cargo run --release".to_string()),
+                env: vec!["PORT=3000".to_string(), "RUST_LOG=debug".to_string()],
+                shell: None,
+                layout: None,
             },
             ProjectLayoutInfo {
                 name: "db-service".to_string(),
                 path: PathBuf::from("/code/db"),
                 startup_command: None,
+                env: vec![],
+                shell: None,
+                layout: None,
             },
         ];
 
@@ -412,14 +407,46 @@ mod tests {
         assert!(content.contains("args \"start\""));
 
         // Verify Dependency 1
-        assert!(content.contains("tab name=\"api-service\"  {")); // Note: extra space might happen due to empty focus string
+        assert!(content.contains("tab name=\"api-service\" {"));
         assert!(content.contains("pane cwd=\"/code/api\" {"));
-        assert!(content.contains("command \"cargo\""));
-        assert!(content.contains("args \"run\" \"--release\""));
+        // Only the env the session doesn't already carry is added to the pane
+        assert!(content.contains("command \"env\""));
+        assert!(content.contains("args \"RUST_LOG=debug\" \"cargo\" \"run\" \"--release\""));
 
         // Verify Dependency 2 (No command)
-        assert!(content.contains("tab name=\"db-service\"  {"));
+        assert!(content.contains("tab name=\"db-service\" {"));
         assert!(content.contains("pane cwd=\"/code/db\" {"));
         assert!(!content.contains("command \"/code/db\"")); // Shouldn't treat path as command
     }
+
+    #[test]
+    fn test_dependency_env_without_command_wraps_a_shell() {
+        let service = ZellijService::new();
+        let main_project = ProjectLayoutInfo {
+            name: "web".to_string(),
+            path: PathBuf::from("/code/web"),
+            startup_command: None,
+            env: vec![],
+            shell: None,
+            layout: None,
+        };
+        let deps = vec![ProjectLayoutInfo {
+            name: "worker".to_string(),
+            path: PathBuf::from("/code/worker"),
+            startup_command: None,
+            env: vec!["GREETING=say \"hi\"".to_string()],
+            shell: None,
+            layout: None,
+        }];
+
+        let layout_path = service
+            .generate_layout_file("test-env-session", &main_project, &deps)
+            .unwrap();
+        let content = std::fs::read_to_string(&layout_path).unwrap();
+        let _ = std::fs::remove_file(layout_path);
+
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
+        assert!(content.contains("command \"env\""));
+        assert!(content.contains(&format!("args \"GREETING=say \\\"hi\\\"\" \"{shell}\"")));
+    }
 }
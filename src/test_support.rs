@@ -1,5 +1,8 @@
 use crate::domain::traits::ContainerHealthStatus;
-use crate::domain::{Container, ContainerRuntime, ContainerSpec, ContainerState};
+use crate::domain::{
+    BuildOptions, Container, ContainerFilter, ContainerInfo, ContainerRuntime, ContainerSpec,
+    ContainerState, ImageInfo, PROJECT_LABEL,
+};
 use anyhow::{Result, bail};
 use std::collections::HashMap;
 use std::path::Path;
@@ -24,6 +27,7 @@ pub struct MockContainerSpec {
     pub healthcheck_interval: Option<String>,
     pub healthcheck_timeout: Option<String>,
     pub healthcheck_retries: Option<u32>,
+    pub project: Option<String>,
 }
 
 #[derive(Debug)]
@@ -31,6 +35,8 @@ pub struct MockRuntime {
     containers: RwLock<HashMap<String, MockContainer>>,
     commands: RwLock<Vec<String>>,
     fail_on: RwLock<Option<String>>,
+    exec_outputs: RwLock<Vec<(String, String)>>,
+    images: RwLock<Vec<ImageInfo>>,
 }
 
 impl MockRuntime {
@@ -39,6 +45,8 @@ impl MockRuntime {
             containers: RwLock::new(HashMap::new()),
             commands: RwLock::new(Vec::new()),
             fail_on: RwLock::new(None),
+            exec_outputs: RwLock::new(Vec::new()),
+            images: RwLock::new(Vec::new()),
         }
     }
 
@@ -59,6 +67,15 @@ impl MockRuntime {
         *self.fail_on.write().unwrap() = Some(operation.to_string());
     }
 
+    /// Output of `exec_output` for commands containing `fragment`
+    #[allow(dead_code)]
+    pub fn set_exec_output(&self, fragment: &str, output: &str) {
+        self.exec_outputs
+            .write()
+            .unwrap()
+            .push((fragment.to_string(), output.to_string()));
+    }
+
     pub fn get_commands(&self) -> Vec<String> {
         self.commands.read().unwrap().clone()
     }
@@ -83,10 +100,54 @@ impl MockRuntime {
         }
     }
 
+    /// Tags `reference` (`repository:tag`) on image `id`, moving the tag if
+    /// another image had it
+    #[allow(dead_code)]
+    pub fn add_image(&self, reference: &str, id: &str) {
+        let (repository, tag) = reference.rsplit_once(':').unwrap_or((reference, "latest"));
+        let mut images = self.images.write().unwrap();
+        images.retain(|image| image.repository != repository || image.tag != tag);
+        images.push(ImageInfo {
+            repository: repository.to_string(),
+            tag: tag.to_string(),
+            id: id.to_string(),
+        });
+    }
+
+    /// Every image tag, as `repository:tag`
+    #[allow(dead_code)]
+    pub fn image_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .images
+            .read()
+            .unwrap()
+            .iter()
+            .map(ImageInfo::reference)
+            .collect();
+        tags.sort();
+        tags
+    }
+
+    fn image_id(&self, reference: &str) -> Option<String> {
+        self.images
+            .read()
+            .unwrap()
+            .iter()
+            .find(|image| image.reference() == reference)
+            .map(|image| image.id.clone())
+    }
+
     fn record_command(&self, cmd: &str) {
         self.commands.write().unwrap().push(cmd.to_string());
     }
 
+    /// Exec env is recorded on its own line so exec assertions stay unchanged
+    fn record_env(&self, container: &str, env: &[String]) {
+        if !env.is_empty() {
+            self.record_command(&format!("exec_env:{}:{}", container, env.join(" ")));
+        }
+    }
+
     fn check_fail(&self, operation: &str) -> Result<()> {
         if let Some(ref fail_on) = *self.fail_on.read().unwrap()
             && fail_on == operation
@@ -120,6 +181,51 @@ impl ContainerRuntime for MockRuntime {
         Ok(Container::new(name.to_string(), state))
     }
 
+    fn list_containers(&self, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>> {
+        self.record_command("list_containers");
+        self.check_fail("list_containers")?;
+
+        let containers = self.containers.read().unwrap();
+        let mut infos: Vec<ContainerInfo> = containers
+            .values()
+            .map(|c| ContainerInfo {
+                id: format!("mock-{}", c.name),
+                name: c.name.clone(),
+                image: c
+                    .spec
+                    .as_ref()
+                    .map(|spec| spec.image.clone())
+                    .unwrap_or_default(),
+                state: c.state.clone(),
+                health: if c.state == ContainerState::Running {
+                    c.health_status
+                        .clone()
+                        .unwrap_or(ContainerHealthStatus::NotApplicable)
+                } else {
+                    ContainerHealthStatus::NotApplicable
+                },
+                ports: c
+                    .spec
+                    .as_ref()
+                    .map(|spec| spec.ports.clone())
+                    .unwrap_or_default(),
+                labels: c
+                    .spec
+                    .as_ref()
+                    .and_then(|spec| spec.project.clone())
+                    .map(|project| (PROJECT_LABEL.to_string(), project))
+                    .into_iter()
+                    .collect(),
+                created: None,
+                exit_code: None,
+            })
+            .filter(|info| filter.matches(info))
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(infos)
+    }
+
     fn get_container_health(&self, name: &str) -> Result<ContainerHealthStatus> {
         self.record_command(&format!("get_health:{}", name));
         self.check_fail("get_health")?;
@@ -170,6 +276,7 @@ impl ContainerRuntime for MockRuntime {
                     healthcheck_interval: spec.healthcheck_interval.map(|s| s.to_string()),
                     healthcheck_timeout: spec.healthcheck_timeout.map(|s| s.to_string()),
                     healthcheck_retries: spec.healthcheck_retries,
+                    project: spec.project.map(|s| s.to_string()),
                 }),
                 health_status: None, // Initial health status is not set
             },
@@ -185,26 +292,92 @@ impl ContainerRuntime for MockRuntime {
         Ok(())
     }
 
-    fn exec_shell(
+    fn exec_command(
         &self,
         container: &str,
-        _workdir: Option<&Path>,
-        session_name: Option<&str>,
+        workdir: Option<&Path>,
+        env: &[String],
+        command: &[&str],
     ) -> Result<()> {
-        let session = session_name.unwrap_or("default");
-        self.record_command(&format!("exec_shell:{}:{}", container, session));
-        self.check_fail("exec_shell")?;
+        if let Some(workdir) = workdir {
+            self.record_command(&format!("exec_workdir:{}:{}", container, workdir.display()));
+        }
+        self.record_env(container, env);
+        self.record_command(&format!("exec:{}:{}", container, command.join(" ")));
+        self.check_fail("exec")?;
         Ok(())
     }
 
+    fn exec_output(&self, container: &str, command: &[&str]) -> Result<String> {
+        let command = command.join(" ");
+        self.record_command(&format!("exec_output:{}:{}", container, command));
+        self.check_fail("exec_output")?;
+        Ok(self
+            .exec_outputs
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(fragment, _)| command.contains(fragment.as_str()))
+            .map(|(_, output)| output.clone())
+            .unwrap_or_default())
+    }
+
+    fn container_logs(&self, name: &str, _tail: usize) -> Result<String> {
+        self.record_command(&format!("logs:{}", name));
+        self.check_fail("logs")?;
+        Ok(String::new())
+    }
+
     fn is_command_available(&self, cmd: &str) -> bool {
         self.record_command(&format!("is_available:{}", cmd));
         true
     }
 
-    fn build_image(&self, tag: &str, _containerfile: &Path, _context_dir: &Path) -> Result<()> {
+    fn build_image(
+        &self,
+        tag: &str,
+        _containerfile: &Path,
+        _context_dir: &Path,
+        _options: &BuildOptions,
+    ) -> Result<()> {
         self.record_command(&format!("build_image:{}", tag));
         self.check_fail("build_image")?;
+        self.add_image(tag, &format!("id-{tag}"));
+        Ok(())
+    }
+
+    fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        self.check_fail("list_images")?;
+        Ok(self
+            .images
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|image| image.repository == repository)
+            .cloned()
+            .collect())
+    }
+
+    fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        self.record_command(&format!("tag_image:{}:{}", source, target));
+        self.check_fail("tag_image")?;
+        let Some(id) = self.image_id(source) else {
+            bail!("Imagem {} não existe", source);
+        };
+        self.add_image(target, &id);
+        Ok(())
+    }
+
+    fn remove_image(&self, reference: &str) -> Result<()> {
+        self.record_command(&format!("remove_image:{}", reference));
+        self.check_fail("remove_image")?;
+        if self.image_id(reference).is_none() {
+            bail!("Imagem {} não existe", reference);
+        }
+        self.images
+            .write()
+            .unwrap()
+            .retain(|image| image.reference() != reference);
         Ok(())
     }
 
@@ -13,6 +13,7 @@ fn test_service_to_spec_conversion() {
         healthcheck_interval: None,
         healthcheck_timeout: None,
         healthcheck_retries: None,
+        project: None,
     };
 
     let spec = svc.to_spec();
@@ -41,6 +42,7 @@ fn test_container_spec_creation() {
         healthcheck_interval: None,
         healthcheck_timeout: None,
         healthcheck_retries: None,
+        project: None,
     };
 
     assert_eq!(spec.name, "test-container");
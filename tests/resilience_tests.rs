@@ -31,6 +31,7 @@ fn test_stress_healthcheck_timeout() -> Result<()> {
         healthcheck_interval: Some("10ms".into()), // Fast interval for test speed
         healthcheck_timeout: Some("10ms".into()),
         healthcheck_retries: Some(3),
+        project: None,
     };
 
     mock.add_container("slow_service", ContainerState::Stopped);
@@ -73,6 +74,7 @@ fn test_resilience_flaky_service() -> Result<()> {
         healthcheck_interval: Some("10ms".into()),
         healthcheck_timeout: Some("10ms".into()),
         healthcheck_retries: Some(5),
+        project: None,
     };
 
     mock.add_container("flaky", ContainerState::Stopped);
@@ -118,6 +120,7 @@ fn test_performance_serial_execution_bottleneck() -> Result<()> {
             healthcheck_interval: Some("20ms".into()), // Each healthcheck check takes 20ms
             healthcheck_timeout: Some("20ms".into()),
             healthcheck_retries: Some(10), // Sufficient retries
+            project: None,
         })
         .collect();
 
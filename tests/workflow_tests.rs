@@ -1,9 +1,18 @@
 use anyhow::Result;
 use devobox::cli::runtime::Runtime;
-use devobox::domain::ContainerState;
+use devobox::cli::session::SessionManager;
+use devobox::cli::{RuntimeContext, forward};
+use devobox::domain::{
+    BuildOptions, ContainerFilter, ContainerRuntime, ContainerState, Project, ProjectConfig,
+};
+use devobox::infra::config::EngineKind;
+use devobox::infra::dry_run::DryRunAdapter;
+use devobox::services::{ImageHistory, SystemService};
 use devobox::test_support::MockRuntime;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
 
 #[test]
 fn test_workflow_up_and_down() -> Result<()> {
@@ -128,19 +137,335 @@ image_name = "devobox:test"
     // 3. Initialize Runtime
     let runtime = Runtime::with_runtime(config_path, mock.clone())?;
 
-    // 4. Run status (just ensures it doesn't panic and calls get_state)
+    // 4. Run status (just ensures it doesn't panic)
     // We capture stdout in a real scenario, but here we just check mock interactions
     runtime.status()?;
 
+    // Status is answered by a single listing instead of one inspect per container
     let commands = mock.get_commands();
-    // It should check status of devobox-test
-    // Note: implementation of get_status calls get_container underneath?
-    // Let's check ContainerService::get_status implementation or MockRuntime
-    // MockRuntime records "get_container:name"
+    assert_eq!(commands, vec!["list_containers".to_string()]);
+
+    Ok(())
+}
+
+#[test]
+fn test_namespaced_services_accept_short_and_qualified_names() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let config_path = temp_dir.path().join("config");
+    for dir in ["config", "api", "web"] {
+        fs::create_dir_all(temp_dir.path().join(dir))?;
+    }
+
+    fs::write(
+        config_path.join("devobox.toml"),
+        r#"[container]
+name = "devobox-test"
+
+[projects]
+namespace_services = true
+
+[dependencies]
+include_projects = ["../api", "../web"]
+"#,
+    )?;
+    let project_services = r#"
+[services.pg]
+image = "postgres:16"
+type = "database"
+
+[services.mq]
+image = "rabbitmq:3"
+shared = true
+"#;
+    fs::write(temp_dir.path().join("api/devobox.toml"), project_services)?;
+    fs::write(
+        temp_dir.path().join("web/devobox.toml"),
+        format!("{project_services}\n[services.cache]\nimage = \"redis:7\"\n"),
+    )?;
+
+    let mock = Arc::new(MockRuntime::new());
+    let runtime = Runtime::with_runtime(&config_path, mock.clone())?;
+
+    // Each project gets its own pg; the shared mq is a single container
+    let names: Vec<&str> = runtime.services().iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["mq", "api-pg", "web-cache", "web-pg"]);
+
+    // Qualified names always work, short names when they are unique
+    runtime.start_svc("api-pg")?;
+    runtime.start_svc("cache")?;
+    runtime.start_svc("mq")?;
+    assert_eq!(mock.get_state("api-pg"), Some(ContainerState::Running));
+    assert_eq!(mock.get_state("web-cache"), Some(ContainerState::Running));
+    assert_eq!(mock.get_state("mq"), Some(ContainerState::Running));
+
+    let err = runtime.start_svc("pg").unwrap_err();
+    assert!(err.to_string().contains("api-pg, web-pg"), "{err}");
+
+    // Namespaced containers carry their project as a label
+    let labeled = mock.list_containers(&ContainerFilter {
+        names: Vec::new(),
+        labels: vec!["devobox.project=web".to_string()],
+    })?;
+    let labeled: Vec<&str> = labeled.iter().map(|info| info.name.as_str()).collect();
+    assert_eq!(labeled, vec!["web-cache"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_session_management_from_host() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    fs::write(
+        temp_dir.path().join("devobox.toml"),
+        "[container]\nname = \"devobox-test\"\nmultiplexer = \"tmux\"\n",
+    )?;
+
+    let mock = Arc::new(MockRuntime::new());
+    let runtime = Runtime::with_runtime(temp_dir.path(), mock.clone())?;
+    let manager = SessionManager::new(&runtime, RuntimeContext::Host)?;
+
+    // A stopped container has no sessions and isn't exec'd into
+    mock.add_container("devobox-test", ContainerState::Stopped);
+    assert!(manager.list()?.is_empty());
     assert!(
-        commands
+        !mock
+            .get_commands()
             .iter()
-            .any(|c| c.contains("get_container:devobox-test"))
+            .any(|c| c.starts_with("exec_output:"))
+    );
+
+    mock.add_container("devobox-test", ContainerState::Running);
+    mock.set_exec_output(
+        "list-sessions",
+        "1700000000 devobox-app2\n1700000000 devobox-app\n",
+    );
+
+    let names: Vec<String> = manager.list()?.into_iter().map(|s| s.name).collect();
+    assert_eq!(names, vec!["devobox-app2", "devobox-app"]);
+
+    // Project names resolve to their session, exactly
+    assert_eq!(manager.find("app")?.name, "devobox-app");
+    assert!(manager.find("ap").is_err());
+
+    manager.kill("devobox-app")?;
+    assert!(
+        mock.get_commands()
+            .contains(&"exec:devobox-test:tmux kill-session -t =devobox-app".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_project_shell_suggests_containerfile() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    fs::write(
+        temp_dir.path().join("devobox.toml"),
+        "[container]\nname = \"devobox-test\"\n",
+    )?;
+
+    let mock = Arc::new(MockRuntime::new());
+    mock.add_container("devobox-test", ContainerState::Running);
+    let runtime = Runtime::with_runtime(temp_dir.path(), mock.clone())?;
+
+    runtime.ensure_shell("zsh")?;
+    assert!(
+        mock.get_commands()
+            .contains(&"exec_output:devobox-test:sh -c command -v \"$1\" sh zsh".to_string())
+    );
+
+    mock.set_fail_on("exec_output");
+    let err = runtime.ensure_shell("fish").unwrap_err().to_string();
+    assert!(err.contains("apt-get install -y fish"), "{err}");
+
+    Ok(())
+}
+
+#[test]
+fn test_project_up_from_host_execs_into_container() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    fs::write(
+        temp_dir.path().join("devobox.toml"),
+        "[container]\nname = \"devobox-test\"\n",
+    )?;
+
+    let mock = Arc::new(MockRuntime::new());
+    mock.add_container("devobox-test", ContainerState::Stopped);
+    let runtime = Runtime::with_runtime(temp_dir.path(), mock.clone())?;
+
+    let project = Project::new(temp_dir.path().join("api"), ProjectConfig::default());
+    runtime.ensure_dev_container()?;
+    runtime.exec_project_up(&project, "empresa/api", true)?;
+
+    assert_eq!(
+        mock.get_state("devobox-test"),
+        Some(ContainerState::Running)
+    );
+    assert!(
+        mock.get_commands().contains(
+            &"exec:devobox-test:devobox project up empresa/api --session-only".to_string()
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_container_only_command_forwards_via_exec() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    fs::write(
+        temp_dir.path().join("devobox.toml"),
+        "[container]\nname = \"devobox-test\"\n",
+    )?;
+
+    let mock = Arc::new(MockRuntime::new());
+    mock.add_container("devobox-test", ContainerState::Stopped);
+    let runtime = Runtime::with_runtime(temp_dir.path(), mock.clone())?;
+
+    // --config-dir is a host path; the command runs from the project's dir
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    let args: Vec<String> = ["-v", "--config-dir", "/host/cfg", "project", "info"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    forward::exec_in_container(&runtime, &home.join("code/api"), &args)?;
+
+    assert_eq!(
+        mock.get_state("devobox-test"),
+        Some(ContainerState::Running)
+    );
+    let commands = mock.get_commands();
+    assert!(commands.contains(&"exec_workdir:devobox-test:/home/dev/code/api".to_string()));
+    assert!(commands.contains(&"exec:devobox-test:devobox -v project info".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_project_with_own_image_uses_its_dev_container() -> Result<()> {
+    let config_dir = tempfile::tempdir()?;
+    let code = tempfile::tempdir()?;
+    fs::write(
+        config_dir.path().join("devobox.toml"),
+        format!(
+            "[container]\nname = \"devobox-test\"\n\n[projects]\nroots = [\"{}\"]\n",
+            code.path().display()
+        ),
+    )?;
+    for (project, build) in [
+        ("api", "containerfile = \"Containerfile\""),
+        ("firmware", "image = \"rust-embedded:1\""),
+    ] {
+        let dir = code.path().join(project);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("devobox.toml"), format!("[build]\n{build}\n"))?;
+    }
+    fs::create_dir_all(code.path().join("shared"))?;
+    fs::write(code.path().join("shared/devobox.toml"), "")?;
+
+    let mock = Arc::new(MockRuntime::new());
+    mock.add_container("devobox-api", ContainerState::Stopped);
+    let runtime =
+        Runtime::for_dir_with_runtime(config_dir.path(), &code.path().join("api"), mock.clone())?;
+
+    assert_eq!(runtime.main_container_name()?, "devobox-api");
+    runtime.ensure_dev_container()?;
+    assert_eq!(mock.get_state("devobox-api"), Some(ContainerState::Running));
+
+    // Every dev container, this directory's first; projects without an image
+    // of their own share devobox-test
+    assert_eq!(
+        runtime.dev_containers()?,
+        vec!["devobox-api", "devobox-test", "devobox-firmware"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_dev_image_rollback_to_previous_build() -> Result<()> {
+    let mock = Arc::new(MockRuntime::new());
+    let system = Arc::new(SystemService::new(mock.clone()));
+    let history = ImageHistory::new(system.clone(), "devobox-img", 1);
+    let containerfile = Path::new("/tmp/Containerfile");
+
+    // Three builds, each tagged by its time and made current
+    for secs in [1_000, 2_000, 3_000] {
+        let version = history.version_at(UNIX_EPOCH + Duration::from_secs(secs));
+        system.build_image(
+            &version,
+            containerfile,
+            Path::new("/tmp"),
+            &BuildOptions::default(),
+        )?;
+        history.activate(&version)?;
+    }
+    assert_eq!(
+        history.apply_retention()?,
+        vec!["devobox-img:19700101-001640"]
+    );
+
+    let previous = history.previous()?;
+    assert_eq!(previous.tag, "19700101-003320");
+    history.activate(&previous.tag)?;
+
+    let current: Vec<String> = history
+        .versions()?
+        .into_iter()
+        .filter(|v| v.current)
+        .map(|v| v.tag)
+        .collect();
+    assert_eq!(current, vec!["19700101-003320"]);
+    assert!(
+        mock.get_commands()
+            .contains(&"tag_image:devobox-img:19700101-003320:devobox-img:latest".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_build_plans_version_and_retention() -> Result<()> {
+    let mock = Arc::new(MockRuntime::new());
+    mock.add_image("devobox-img:19700101-001640", "id-old");
+    mock.add_image("devobox-img:19700101-003320", "id-current");
+    mock.add_image("devobox-img:latest", "id-current");
+    let dry_run = Arc::new(DryRunAdapter::new(mock.clone(), EngineKind::Podman));
+    let system = Arc::new(SystemService::new(dry_run.clone()));
+    let history = ImageHistory::new(system.clone(), "devobox-img", 1);
+
+    // Same steps as `devobox --dry-run build`
+    let version = history.version_at(UNIX_EPOCH + Duration::from_secs(3_000));
+    system.build_image(
+        &version,
+        Path::new("/cfg/Containerfile"),
+        Path::new("/cfg"),
+        &BuildOptions::default(),
+    )?;
+    history.activate(&version)?;
+    assert_eq!(
+        history.apply_retention()?,
+        vec!["devobox-img:19700101-001640"]
+    );
+
+    let planned = dry_run.planned();
+    assert!(planned[0].starts_with("podman build --progress=plain -t devobox-img:19700101-005000"));
+    assert_eq!(
+        planned[1..],
+        [
+            "podman tag devobox-img:19700101-005000 devobox-img:latest",
+            "podman rmi devobox-img:19700101-001640",
+        ]
+    );
+
+    // Nothing was built, tagged or removed for real
+    assert_eq!(
+        mock.image_tags(),
+        vec![
+            "devobox-img:19700101-001640",
+            "devobox-img:19700101-003320",
+            "devobox-img:latest"
+        ]
     );
 
     Ok(())